@@ -0,0 +1,541 @@
+//! Command-line interface for managing the job queue without driving the TUI.
+
+#[cfg(unix)]
+use crate::control::client::ControlClient;
+use crate::config::{Config, StorageConfig};
+use crate::email::EmailConfig;
+use crate::control::{ControlContext, EngineState, Request, Response};
+use crate::events::EventBus;
+use crate::history::{HistoryStore, RunRecord};
+use crate::job::Job;
+use crate::persistence_manager::PersistenceManager;
+use crate::queue::QueueManager;
+use crate::tasks::{self, params};
+use crate::worker::Worker;
+use chrono::{TimeZone, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+pub const DEFAULT_QUEUE_PATH: &str = "queue.json";
+pub const DEFAULT_HISTORY_PATH: &str = "history.jsonl";
+/// Shown instead of secret payload values, in table and JSON output alike
+const SECRET_MASK: &str = "\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}";
+
+#[derive(Parser, Debug)]
+#[command(name = "scheduler", version, about = "Time- and priority-based job scheduler")]
+pub struct Cli {
+    /// Configuration file [default: scheduler.toml, if present]
+    #[arg(long, global = true)]
+    pub config: Option<String>,
+
+    /// Job queue file shared with the running scheduler [default: queue.json]
+    #[arg(long, global = true)]
+    pub queue: Option<String>,
+
+    /// Run history file (JSON lines) [default: history.jsonl]
+    #[arg(long, global = true)]
+    pub history: Option<String>,
+
+    /// Control socket of a running scheduler; the files are used when nothing listens [default: scheduler.sock]
+    #[arg(long, global = true)]
+    pub socket: Option<String>,
+
+    /// Output format for command results
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+
+    /// Run the scheduler without the TUI, logging to stdout (stop with Ctrl+C)
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Without a subcommand the scheduler starts with the TUI
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// Loads the configuration file and environment, then applies the path flags on top.
+    pub fn load_config(&self) -> Result<Config, String> {
+        let mut config = Config::load(self.config.as_deref())?;
+        if let Some(queue) = &self.queue {
+            config.storage.queue = queue.clone();
+        }
+        if let Some(history) = &self.history {
+            config.storage.history = history.clone();
+        }
+        if let Some(socket) = &self.socket {
+            config.storage.socket = socket.clone();
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Schedule a new job
+    Add {
+        /// Seconds from now, or an absolute Unix timestamp
+        #[arg(long, short = 't')]
+        time: String,
+        #[arg(long, short = 'p', default_value_t = 1)]
+        priority: u8,
+        #[arg(long, short = 'd')]
+        description: String,
+        /// Registered function name, e.g. backup_fn
+        #[arg(long, short = 'f')]
+        function: String,
+        #[arg(long, default_value_t = 3)]
+        retries: u32,
+        /// Job metadata passed to the task, repeatable
+        #[arg(long = "meta", short = 'm', value_name = "KEY=VALUE")]
+        meta: Vec<String>,
+        /// Job tag, repeatable; `heavy` jobs wait while the machine is overloaded
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// List all queued jobs
+    List,
+    /// Remove a job from the queue
+    Remove { id: String },
+    /// Show a single job and its recent runs
+    Show { id: String },
+    /// Keep a job queued but stop it from being dispatched
+    Pause { id: String },
+    /// Make a paused job eligible for dispatch again
+    Resume { id: String },
+    /// Execute a job immediately, keeping its schedule
+    RunNow { id: String },
+    /// Show recent job runs
+    History {
+        #[arg(long, short = 'n', default_value_t = 20)]
+        limit: usize,
+        /// Only show runs of this job
+        #[arg(long)]
+        job: Option<String>,
+    },
+    /// Inspect or control the engine of a running scheduler
+    Engine {
+        #[command(subcommand)]
+        action: EngineAction,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, Copy)]
+pub enum EngineAction {
+    Status,
+    Start,
+    Stop,
+}
+
+/// Where commands run: the live scheduler via its control socket, or the
+/// queue file directly when no scheduler is running.
+enum Backend {
+    #[cfg(unix)]
+    Remote(ControlClient),
+    Local(Box<LocalQueue>),
+}
+
+impl Backend {
+    fn connect(config: &Config) -> Self {
+        #[cfg(unix)]
+        if let Ok(client) = ControlClient::connect(&config.storage.socket) {
+            return Backend::Remote(client);
+        }
+        Backend::Local(Box::new(LocalQueue::open(&config.storage, &config.email)))
+    }
+
+    fn call(&mut self, request: Request) -> Result<Response, String> {
+        let response = match self {
+            #[cfg(unix)]
+            Backend::Remote(client) => client.request(&request)?,
+            Backend::Local(local) => local.call(request)?,
+        };
+        match response {
+            Response::Error(e) => Err(e),
+            other => Ok(other),
+        }
+    }
+
+    fn resolve(&mut self, input: &str) -> Result<Uuid, String> {
+        if let Ok(id) = Uuid::parse_str(input) {
+            return Ok(id);
+        }
+        match self.call(Request::List)? {
+            Response::Jobs(jobs) => resolve_id(&jobs, input),
+            other => Err(format!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+/// Queue loaded from disk for a single command; written back after mutations.
+struct LocalQueue {
+    persistence: PersistenceManager,
+    ctx: ControlContext,
+    worker_rx: Receiver<Job>,
+    email: EmailConfig,
+}
+
+impl LocalQueue {
+    fn open(storage: &StorageConfig, email: &EmailConfig) -> Self {
+        let persistence = PersistenceManager::new(&storage.queue);
+        let mut queue = QueueManager::new();
+        queue.load_from_vec(persistence.load_jobs());
+        let (worker_tx, worker_rx) = mpsc::channel();
+        let ctx = ControlContext {
+            queue: Arc::new(Mutex::new(queue)),
+            history: Arc::new(Mutex::new(HistoryStore::open(&storage.history))),
+            worker_tx,
+            engine: None,
+            events: EventBus::new(),
+            functions: builtin_worker().functions(),
+        };
+        Self {
+            persistence,
+            ctx,
+            worker_rx,
+            email: email.clone(),
+        }
+    }
+
+    fn call(&mut self, request: Request) -> Result<Response, String> {
+        let mutates = matches!(
+            request,
+            Request::Add { .. }
+                | Request::Remove { .. }
+                | Request::Pause { .. }
+                | Request::Resume { .. }
+                | Request::Reschedule { .. }
+        );
+        let response = self.ctx.handle(request);
+        if mutates && !matches!(response, Response::Error(_)) {
+            let jobs = self.ctx.queue.lock().map(|q| q.snapshot()).unwrap_or_default();
+            self.persistence.save_jobs(&jobs).map_err(|e| {
+                format!("Failed to write {}: {}", self.persistence.storage_path.display(), e)
+            })?;
+        }
+        Ok(response)
+    }
+
+    /// Executes jobs handed to the worker channel (run-now) in this process.
+    fn run_dispatched(&self, out: &mut dyn Write, format: OutputFormat) -> Result<(), String> {
+        let events = EventBus::new();
+        let run_events = events.subscribe();
+        let mut worker = Worker::new();
+        tasks::register_builtin(&mut worker);
+        tasks::register_email(&mut worker, self.email.clone());
+        worker.set_events(events);
+
+        for mut job in self.worker_rx.try_iter() {
+            worker.run_job(&mut job);
+
+            let mut record = None;
+            for event in run_events.try_iter() {
+                if let Some(r) = RunRecord::from_event(&event) {
+                    if let Ok(mut h) = self.ctx.history.lock() {
+                        h.append(r.clone()).map_err(io_err)?;
+                    }
+                    record = Some(r);
+                }
+                if format == OutputFormat::Table {
+                    writeln!(out, "{}", event).map_err(io_err)?;
+                }
+            }
+            match format {
+                OutputFormat::Json => write_json(out, &record).map_err(io_err)?,
+                OutputFormat::Table => {
+                    if let Some(r) = record {
+                        writeln!(out, "Run finished: {:?} in {} ms", r.outcome, r.duration_ms)
+                            .map_err(io_err)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs a single CLI command, against the running scheduler when its control
+/// socket answers and against the queue and history files otherwise.
+pub fn execute(cli: &Cli, command: &Command, out: &mut dyn Write) -> Result<(), String> {
+    let format = cli.format;
+    let config = cli.load_config()?;
+    let mut backend = Backend::connect(&config);
+
+    let (request, action) = match command {
+        Command::Add {
+            time,
+            priority,
+            description,
+            function,
+            retries,
+            meta,
+            tags,
+        } => {
+            let execution_time = Job::parse_execution_time(time)?;
+            let mut job = Job::new(execution_time, *priority, description, function, *retries)?;
+            if !meta.is_empty() {
+                job.metadata = Some(parse_metadata(meta)?);
+            }
+            job.tags = tags.clone();
+            (Request::Add { job }, "added")
+        }
+        Command::List => (Request::List, ""),
+        Command::Remove { id } => (Request::Remove { id: backend.resolve(id)? }, "removed"),
+        Command::Show { id } => (Request::Get { id: backend.resolve(id)? }, ""),
+        Command::Pause { id } => (Request::Pause { id: backend.resolve(id)? }, "paused"),
+        Command::Resume { id } => (Request::Resume { id: backend.resolve(id)? }, "resumed"),
+        Command::RunNow { id } => (Request::RunNow { id: backend.resolve(id)? }, "dispatched"),
+        Command::History { limit, job } => {
+            let job = match job {
+                Some(id) => Some(backend.resolve(id)?),
+                None => None,
+            };
+            (Request::History { limit: *limit, job }, "")
+        }
+        Command::Engine { action } => {
+            let request = match action {
+                EngineAction::Status => Request::EngineStatus,
+                EngineAction::Start => Request::EngineStart,
+                EngineAction::Stop => Request::EngineStop,
+            };
+            (request, "")
+        }
+    };
+
+    let response = backend.call(request)?;
+    if let (Backend::Local(local), Command::RunNow { .. }) = (&backend, command) {
+        return local.run_dispatched(out, format);
+    }
+
+    match response {
+        Response::Ok => Ok(()),
+        Response::Job(job) => write_job_result(out, format, &job, action),
+        Response::Jobs(jobs) => write_jobs(out, format, &jobs),
+        Response::JobDetail { job, runs } => write_job_detail(out, format, &job, &runs),
+        Response::History(records) => write_history(out, format, &records),
+        Response::Engine(state) => write_engine(out, format, &state),
+        Response::Error(e) => return Err(e),
+    }
+    .map_err(io_err)
+}
+
+/// Accepts a full UUID or any unambiguous prefix of one.
+pub fn resolve_id(jobs: &[Job], input: &str) -> Result<Uuid, String> {
+    if let Ok(id) = Uuid::parse_str(input) {
+        return Ok(id);
+    }
+    let needle = input.to_lowercase();
+    let matches: Vec<&Job> = jobs
+        .iter()
+        .filter(|j| j.id.to_string().starts_with(&needle))
+        .collect();
+    match matches.as_slice() {
+        [job] => Ok(job.id),
+        [] => Err(format!("No job matches '{}'.", input)),
+        _ => Err(format!("'{}' matches {} jobs; use a longer id.", input, matches.len())),
+    }
+}
+
+fn parse_metadata(pairs: &[String]) -> Result<HashMap<String, String>, String> {
+    pairs
+        .iter()
+        .map(|pair| {
+            pair.split_once('=')
+                .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+                .ok_or_else(|| format!("Invalid metadata '{}', expected KEY=VALUE.", pair))
+        })
+        .collect()
+}
+
+fn io_err(e: io::Error) -> String {
+    e.to_string()
+}
+
+fn format_time(ts: i64) -> String {
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn short_id(id: &Uuid) -> String {
+    id.to_string()[..8].to_string()
+}
+
+fn write_json<T: serde::Serialize>(out: &mut dyn Write, value: &T) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    writeln!(out, "{}", json)
+}
+
+/// Prints rows as left-aligned columns sized to their widest cell.
+fn write_table(out: &mut dyn Write, headers: &[&str], rows: &[Vec<String>]) -> io::Result<()> {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let render = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{:<width$}", c, width = *w))
+            .collect();
+        line.join("  ").trim_end().to_string()
+    };
+
+    writeln!(out, "{}", render(headers.to_vec()))?;
+    for row in rows {
+        writeln!(out, "{}", render(row.iter().map(String::as_str).collect()))?;
+    }
+    Ok(())
+}
+
+fn write_jobs(out: &mut dyn Write, format: OutputFormat, jobs: &[Job]) -> io::Result<()> {
+    if format == OutputFormat::Json {
+        let builtin = builtin_worker();
+        let jobs: Vec<Job> = jobs.iter().map(|job| mask_secrets(&builtin, job)).collect();
+        return write_json(out, &jobs);
+    }
+    if jobs.is_empty() {
+        return writeln!(out, "No jobs queued.");
+    }
+    let rows: Vec<Vec<String>> = jobs
+        .iter()
+        .map(|j| {
+            vec![
+                short_id(&j.id),
+                format_time(j.execution_time),
+                j.priority.to_string(),
+                format!("{:?}", j.status),
+                j.function.clone(),
+                j.description.clone(),
+            ]
+        })
+        .collect();
+    write_table(
+        out,
+        &["ID", "TIME (UTC)", "PRI", "STATUS", "FUNCTION", "DESCRIPTION"],
+        &rows,
+    )
+}
+
+fn write_job_result(out: &mut dyn Write, format: OutputFormat, job: &Job, action: &str) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => write_json(out, &mask_secrets(&builtin_worker(), job)),
+        OutputFormat::Table => writeln!(out, "Job {} ({}) {}.", job.id, job.description, action),
+    }
+}
+
+/// The built-in tasks, for their names and declared parameters
+fn builtin_worker() -> Worker {
+    let mut worker = Worker::new();
+    tasks::register_builtin(&mut worker);
+    worker
+}
+
+/// `job` with its secret payload values masked, like the TUI's job details
+fn mask_secrets(builtin: &Worker, job: &Job) -> Job {
+    let specs = builtin.params(&job.function);
+    let mut masked = job.clone();
+    for (key, value) in masked.metadata.iter_mut().flatten() {
+        if params::is_secret(specs, key) {
+            *value = SECRET_MASK.to_string();
+        }
+    }
+    masked
+}
+
+fn write_job_detail(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    job: &Job,
+    runs: &[RunRecord],
+) -> io::Result<()> {
+    let job = &mask_secrets(&builtin_worker(), job);
+    if format == OutputFormat::Json {
+        let value = serde_json::json!({ "job": job, "runs": runs });
+        return write_json(out, &value);
+    }
+
+    writeln!(out, "ID:          {}", job.id)?;
+    writeln!(out, "Description: {}", job.description)?;
+    writeln!(out, "Function:    {}", job.function)?;
+    writeln!(out, "Status:      {:?}", job.status)?;
+    writeln!(out, "Time (UTC):  {}", format_time(job.execution_time))?;
+    writeln!(out, "Priority:    {}", job.priority)?;
+    writeln!(out, "Retries:     {}/{}", job.retry_count, job.max_retries)?;
+    if !job.tags.is_empty() {
+        writeln!(out, "Tags:        {}", job.tags.join(", "))?;
+    }
+    if let Some(schedule) = &job.schedule {
+        writeln!(out, "Schedule:    {}", schedule)?;
+    }
+    if let Some(definition) = &job.definition {
+        writeln!(out, "Definition:  {}", definition)?;
+    }
+    if let Some(meta) = &job.metadata {
+        let mut keys: Vec<&String> = meta.keys().collect();
+        keys.sort();
+        writeln!(out, "Metadata:")?;
+        for key in keys {
+            writeln!(out, "  {} = {}", key, meta[key])?;
+        }
+    }
+    if !runs.is_empty() {
+        writeln!(out)?;
+        write_history(out, format, runs)?;
+    }
+    Ok(())
+}
+
+fn write_engine(out: &mut dyn Write, format: OutputFormat, state: &EngineState) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => write_json(out, state),
+        OutputFormat::Table => writeln!(
+            out,
+            "Engine {} ({} jobs queued).",
+            if state.running { "running" } else { "stopped" },
+            state.queued
+        ),
+    }
+}
+
+fn write_history(out: &mut dyn Write, format: OutputFormat, records: &[RunRecord]) -> io::Result<()> {
+    if format == OutputFormat::Json {
+        return write_json(out, &records);
+    }
+    if records.is_empty() {
+        return writeln!(out, "No runs recorded.");
+    }
+    let rows: Vec<Vec<String>> = records
+        .iter()
+        .map(|r| {
+            vec![
+                format_time(r.started_at),
+                short_id(&r.job_id),
+                r.function.clone(),
+                r.attempt.to_string(),
+                format!("{:?}", r.outcome),
+                format!("{} ms", r.duration_ms),
+                r.message.clone().or_else(|| r.output.clone()).unwrap_or_default(),
+            ]
+        })
+        .collect();
+    write_table(
+        out,
+        &["STARTED (UTC)", "JOB", "FUNCTION", "ATTEMPT", "OUTCOME", "DURATION", "MESSAGE"],
+        &rows,
+    )
+}
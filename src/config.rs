@@ -0,0 +1,700 @@
+//! Scheduler configuration: built-in defaults, overridden by a TOML file
+//! (`scheduler.toml` unless `--config` says otherwise), overridden by
+//! `SCHEDULER_*` environment variables. Everything is validated at startup
+//! and all problems are reported at once.
+
+use std::collections::HashMap;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+
+use crate::cli::{DEFAULT_HISTORY_PATH, DEFAULT_QUEUE_PATH};
+use crate::control::DEFAULT_SOCKET_PATH;
+use crate::job::Job;
+use crate::definitions::DEFAULT_JOBS_DIR;
+use crate::email::{EmailConfig, SmtpSecurity, TransportKind};
+use crate::monitor::{DEFAULT_SAMPLE_INTERVAL, LoadThreshold};
+use crate::schedule::Schedule;
+use crate::telemetry::otlp::OtlpTarget;
+use crate::telemetry::{self, TelemetryConfig};
+use crate::tui::{ThemeName, TuiConfig};
+
+pub const DEFAULT_CONFIG_PATH: &str = "scheduler.toml";
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub storage: StorageConfig,
+    /// How often the engine checks the queue for due jobs
+    pub poll_interval: Duration,
+    pub worker: WorkerConfig,
+    pub telemetry: TelemetryConfig,
+    /// Prometheus endpoint, off unless set
+    pub metrics_addr: Option<String>,
+    pub monitor: MonitorConfig,
+    pub notifications: NotificationsConfig,
+    pub http: HttpConfig,
+    pub definitions: DefinitionsConfig,
+    /// How `email_fn` delivers mail
+    pub email: EmailConfig,
+    pub tui: TuiConfig,
+    /// Seeded into the queue when it starts out empty
+    pub jobs: Vec<JobSpec>,
+    /// File the configuration was read from, if any
+    pub source: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageConfig {
+    pub queue: String,
+    pub history: String,
+    pub socket: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerConfig {
+    /// Jobs run concurrently on this many worker threads
+    pub pool_size: usize,
+    /// Only these registered functions are enabled; all of them when unset
+    pub functions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorConfig {
+    pub sample_interval: Duration,
+    pub threshold: LoadThreshold,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationsConfig {
+    pub desktop: bool,
+    pub on_success: bool,
+    pub on_failure: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpConfig {
+    pub addr: Option<String>,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionsConfig {
+    /// Directory of `*.toml` / `*.yaml` job definition files
+    pub dir: PathBuf,
+    /// Re-apply the definitions whenever a file changes
+    pub watch: bool,
+}
+
+/// A job declared in configuration or in a job definition file rather than added at runtime.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobSpec {
+    /// Identifies the job across reloads of a definition file
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Defaults to `name` in definition files
+    #[serde(default)]
+    pub description: String,
+    pub function: String,
+    /// `every 15m` or a cron expression; the first run is the next match
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Seconds after startup
+    #[serde(default)]
+    pub delay_secs: Option<i64>,
+    /// Absolute Unix timestamp; takes precedence over `delay_secs`
+    #[serde(default)]
+    pub at: Option<i64>,
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+    #[serde(default = "default_retries")]
+    pub max_retries: u32,
+    /// Passed to the task as job metadata
+    #[serde(default, alias = "payload")]
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_priority() -> u8 {
+    1
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+impl JobSpec {
+    fn demo(delay_secs: i64, priority: u8, description: &str, function: &str, max_retries: u32) -> Self {
+        Self {
+            name: None,
+            description: description.to_string(),
+            function: function.to_string(),
+            schedule: None,
+            delay_secs: Some(delay_secs),
+            at: None,
+            priority,
+            max_retries,
+            metadata: HashMap::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn to_job(&self) -> Result<Job, String> {
+        let now = Job::now();
+        let execution_time = match (self.at, self.delay_secs, self.schedule.as_deref()) {
+            (Some(at), _, _) => at,
+            (None, Some(delay), _) => now
+                .checked_add(delay)
+                .ok_or_else(|| format!("delay_secs {} is out of range", delay))?,
+            (None, None, Some(schedule)) => schedule
+                .parse::<Schedule>()?
+                .next_after(now)
+                .ok_or_else(|| format!("schedule '{}' has no upcoming runs", schedule))?,
+            (None, None, None) => now,
+        };
+        let mut job = Job::new(
+            execution_time,
+            self.priority,
+            &self.description,
+            &self.function,
+            self.max_retries,
+        )?;
+        if !self.metadata.is_empty() {
+            job.metadata = Some(self.metadata.clone());
+        }
+        job.tags = self.tags.clone();
+        job.definition = self.name.clone();
+        job.schedule = self.schedule.clone();
+        Ok(job)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            storage: StorageConfig {
+                queue: DEFAULT_QUEUE_PATH.to_string(),
+                history: DEFAULT_HISTORY_PATH.to_string(),
+                socket: DEFAULT_SOCKET_PATH.to_string(),
+            },
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            worker: WorkerConfig {
+                pool_size: 1,
+                functions: None,
+            },
+            telemetry: TelemetryConfig::default(),
+            metrics_addr: None,
+            monitor: MonitorConfig {
+                sample_interval: DEFAULT_SAMPLE_INTERVAL,
+                threshold: LoadThreshold::default(),
+            },
+            notifications: NotificationsConfig {
+                desktop: true,
+                on_success: true,
+                on_failure: true,
+            },
+            http: HttpConfig::default(),
+            definitions: DefinitionsConfig {
+                dir: PathBuf::from(DEFAULT_JOBS_DIR),
+                watch: true,
+            },
+            email: EmailConfig::default(),
+            tui: TuiConfig::default(),
+            jobs: vec![
+                JobSpec::demo(1, 5, "Backup Database", "backup_fn", 3),
+                JobSpec::demo(3, 1, "Send Emails", "email_fn", 1),
+                JobSpec::demo(1, 1, "Urgent Hotfix", "hotfix_fn", 3),
+            ],
+            source: None,
+        }
+    }
+}
+
+/// The TOML file as written; every field is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    storage: StorageSection,
+    engine: EngineSection,
+    worker: WorkerSection,
+    telemetry: TelemetrySection,
+    monitor: MonitorSection,
+    notifications: NotificationsSection,
+    http: HttpSection,
+    definitions: DefinitionsSection,
+    email: EmailSection,
+    tui: TuiSection,
+    jobs: Option<Vec<JobSpec>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StorageSection {
+    queue: Option<String>,
+    history: Option<String>,
+    socket: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct EngineSection {
+    poll_interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WorkerSection {
+    pool_size: Option<usize>,
+    functions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TelemetrySection {
+    level: Option<String>,
+    format: Option<String>,
+    dir: Option<PathBuf>,
+    file_name: Option<String>,
+    rotation: Option<String>,
+    max_size_bytes: Option<u64>,
+    max_files: Option<usize>,
+    stdout: Option<bool>,
+    tui_level: Option<String>,
+    otlp_export: Option<String>,
+    metrics_addr: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct MonitorSection {
+    sample_interval_secs: Option<u64>,
+    heavy_max_cpu: Option<f32>,
+    heavy_max_load: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct NotificationsSection {
+    desktop: Option<bool>,
+    on_success: Option<bool>,
+    on_failure: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct HttpSection {
+    addr: Option<String>,
+    token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DefinitionsSection {
+    dir: Option<PathBuf>,
+    watch: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct EmailSection {
+    transport: Option<String>,
+    from: Option<String>,
+    outbox_dir: Option<PathBuf>,
+    smtp: SmtpSection,
+    resend: ResendSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SmtpSection {
+    host: Option<String>,
+    port: Option<u16>,
+    username: Option<String>,
+    password: Option<String>,
+    security: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TuiSection {
+    log_lines: Option<usize>,
+    keymap: Option<String>,
+    keys: HashMap<String, Vec<String>>,
+    mouse: Option<bool>,
+    theme: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ResendSection {
+    api_key: Option<String>,
+    endpoint: Option<String>,
+}
+
+impl Config {
+    /// Reads `path`, or `scheduler.toml` if it exists when no path is given,
+    /// then applies environment overrides and validates the result.
+    pub fn load(path: Option<&str>) -> Result<Self, String> {
+        Self::load_with_env(path, |key| std::env::var(key).ok())
+    }
+
+    pub fn load_with_env(path: Option<&str>, var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let file = match path {
+            Some(path) => Some(PathBuf::from(path)),
+            None => Some(PathBuf::from(DEFAULT_CONFIG_PATH)).filter(|p| p.exists()),
+        };
+        let text = match file.as_ref() {
+            Some(file) => Some(
+                fs::read_to_string(file)
+                    .map_err(|e| format!("Cannot read config file {}: {}", file.display(), e))?,
+            ),
+            None => None,
+        };
+        Self::resolve(text.as_deref(), file.as_deref(), var)
+    }
+
+    /// Builds the configuration from TOML text (no file access), applying `var` overrides.
+    pub fn from_toml(text: &str, var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        Self::resolve(Some(text), None, var)
+    }
+
+    fn resolve(
+        text: Option<&str>,
+        source: Option<&Path>,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, String> {
+        let origin = source
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "configuration".to_string());
+        let file: FileConfig = match text {
+            Some(text) => toml::from_str(text).map_err(|e| format!("Invalid {}: {}", origin, e))?,
+            None => FileConfig::default(),
+        };
+
+        let mut config = Config {
+            source: source.map(Path::to_path_buf),
+            ..Config::default()
+        };
+        let mut errors = Vec::new();
+        config.apply_file(file, &mut errors);
+        config.apply_env(&var, &mut errors);
+        config.validate(&mut errors);
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(format!("Invalid {}:\n  - {}", origin, errors.join("\n  - ")))
+        }
+    }
+
+    fn apply_file(&mut self, file: FileConfig, errors: &mut Vec<String>) {
+        set(&mut self.storage.queue, file.storage.queue);
+        set(&mut self.storage.history, file.storage.history);
+        set(&mut self.storage.socket, file.storage.socket);
+        if let Some(ms) = file.engine.poll_interval_ms {
+            self.poll_interval = Duration::from_millis(ms);
+        }
+        set(&mut self.worker.pool_size, file.worker.pool_size);
+        if file.worker.functions.is_some() {
+            self.worker.functions = file.worker.functions;
+        }
+
+        let t = file.telemetry;
+        let telemetry = &mut self.telemetry;
+        set(&mut telemetry.level, t.level);
+        if let Some(format) = t.format {
+            collect(telemetry::parse_format(&format), errors, |f| telemetry.format = f);
+        }
+        set(&mut telemetry.dir, t.dir);
+        set(&mut telemetry.file_name, t.file_name);
+        if let Some(rotation) = t.rotation {
+            collect(telemetry::parse_rotation(&rotation), errors, |r| telemetry.rotation = r);
+        }
+        set(&mut telemetry.max_size_bytes, t.max_size_bytes);
+        set(&mut telemetry.max_files, t.max_files);
+        set(&mut telemetry.stdout, t.stdout);
+        if let Some(level) = t.tui_level {
+            match level.parse::<LevelFilter>() {
+                Ok(level) => telemetry.tui_level = level,
+                Err(_) => errors.push(format!("telemetry.tui_level: unknown level '{}'", level)),
+            }
+        }
+        if let Some(target) = t.otlp_export {
+            telemetry.otlp_export = Some(OtlpTarget::parse(&target));
+        }
+        if t.metrics_addr.is_some() {
+            self.metrics_addr = t.metrics_addr;
+        }
+
+        if let Some(secs) = file.monitor.sample_interval_secs {
+            self.monitor.sample_interval = Duration::from_secs(secs);
+        }
+        if file.monitor.heavy_max_cpu.is_some() {
+            self.monitor.threshold.max_system_cpu_percent = file.monitor.heavy_max_cpu;
+        }
+        if file.monitor.heavy_max_load.is_some() {
+            self.monitor.threshold.max_load_average = file.monitor.heavy_max_load;
+        }
+
+        set(&mut self.notifications.desktop, file.notifications.desktop);
+        set(&mut self.notifications.on_success, file.notifications.on_success);
+        set(&mut self.notifications.on_failure, file.notifications.on_failure);
+
+        if file.http.addr.is_some() {
+            self.http.addr = file.http.addr;
+        }
+        if file.http.token.is_some() {
+            self.http.token = file.http.token;
+        }
+        set(&mut self.definitions.dir, file.definitions.dir);
+        set(&mut self.definitions.watch, file.definitions.watch);
+
+        let e = file.email;
+        let email = &mut self.email;
+        if let Some(transport) = e.transport {
+            collect(
+                transport.parse::<TransportKind>().map_err(|err| format!("email.transport: {}", err)),
+                errors,
+                |t| email.transport = t,
+            );
+        }
+        if e.from.is_some() {
+            email.from = e.from;
+        }
+        set(&mut email.outbox_dir, e.outbox_dir);
+        if e.smtp.host.is_some() {
+            email.smtp.host = e.smtp.host;
+        }
+        if e.smtp.port.is_some() {
+            email.smtp.port = e.smtp.port;
+        }
+        if e.smtp.username.is_some() {
+            email.smtp.username = e.smtp.username;
+        }
+        if e.smtp.password.is_some() {
+            email.smtp.password = e.smtp.password;
+        }
+        if let Some(security) = e.smtp.security {
+            collect(
+                security.parse::<SmtpSecurity>().map_err(|err| format!("email.smtp.security: {}", err)),
+                errors,
+                |s| email.smtp.security = s,
+            );
+        }
+        if let Some(secs) = e.smtp.timeout_secs {
+            email.smtp.timeout = Duration::from_secs(secs);
+        }
+        if e.resend.api_key.is_some() {
+            email.resend.api_key = e.resend.api_key;
+        }
+        set(&mut email.resend.endpoint, e.resend.endpoint);
+        set(&mut self.tui.log_lines, file.tui.log_lines);
+        if let Some(preset) = file.tui.keymap {
+            collect(preset.parse().map_err(|e| format!("tui.keymap: {}", e)), errors, |p| self.tui.preset = p);
+        }
+        self.tui.keys = file.tui.keys;
+        set(&mut self.tui.mouse, file.tui.mouse);
+        if let Some(theme) = file.tui.theme {
+            collect(theme.parse().map_err(|e| format!("tui.theme: {}", e)), errors, |t| self.tui.theme = Some(t));
+        }
+        if let Some(jobs) = file.jobs {
+            self.jobs = jobs;
+        }
+    }
+
+    /// `SCHEDULER_QUEUE`, `SCHEDULER_HISTORY`, `SCHEDULER_SOCKET`, `SCHEDULER_POLL_INTERVAL_MS`,
+    /// `SCHEDULER_WORKERS`, `SCHEDULER_NOTIFICATIONS`, `SCHEDULER_METRICS_ADDR`,
+    /// `SCHEDULER_HTTP_ADDR`, `SCHEDULER_API_TOKEN`, `SCHEDULER_JOBS_DIR`, `SCHEDULER_TUI_LOG_LINES`,
+    /// `SCHEDULER_TUI_KEYMAP`, `SCHEDULER_TUI_THEME`, `NO_COLOR`,
+    /// plus the logging, heavy-job and email variables read by `TelemetryConfig`, `LoadThreshold`
+    /// and `EmailConfig`.
+    fn apply_env(&mut self, var: &impl Fn(&str) -> Option<String>, errors: &mut Vec<String>) {
+        set(&mut self.storage.queue, var("SCHEDULER_QUEUE"));
+        set(&mut self.storage.history, var("SCHEDULER_HISTORY"));
+        set(&mut self.storage.socket, var("SCHEDULER_SOCKET"));
+        if let Some(ms) = var("SCHEDULER_POLL_INTERVAL_MS") {
+            collect(parse_number::<u64>("SCHEDULER_POLL_INTERVAL_MS", &ms), errors, |ms| {
+                self.poll_interval = Duration::from_millis(ms)
+            });
+        }
+        if let Some(size) = var("SCHEDULER_WORKERS") {
+            collect(parse_number("SCHEDULER_WORKERS", &size), errors, |n| self.worker.pool_size = n);
+        }
+        if let Some(enabled) = var("SCHEDULER_NOTIFICATIONS") {
+            match enabled.to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => self.notifications.desktop = true,
+                "0" | "false" | "no" | "off" => self.notifications.desktop = false,
+                _ => errors.push(format!("SCHEDULER_NOTIFICATIONS must be true or false, got '{}'", enabled)),
+            }
+        }
+        if let Some(addr) = var("SCHEDULER_METRICS_ADDR") {
+            self.metrics_addr = Some(addr);
+        }
+        if let Some(addr) = var("SCHEDULER_HTTP_ADDR") {
+            self.http.addr = Some(addr);
+        }
+        if let Some(token) = var("SCHEDULER_API_TOKEN") {
+            self.http.token = Some(token);
+        }
+        if let Some(dir) = var("SCHEDULER_JOBS_DIR") {
+            self.definitions.dir = PathBuf::from(dir);
+        }
+        if let Some(lines) = var("SCHEDULER_TUI_LOG_LINES") {
+            collect(parse_number("SCHEDULER_TUI_LOG_LINES", &lines), errors, |n| self.tui.log_lines = n);
+        }
+        if let Some(preset) = var("SCHEDULER_TUI_KEYMAP") {
+            collect(preset.parse().map_err(|e| format!("SCHEDULER_TUI_KEYMAP: {}", e)), errors, |p| self.tui.preset = p);
+        }
+        if let Some(theme) = var("SCHEDULER_TUI_THEME") {
+            collect(theme.parse().map_err(|e| format!("SCHEDULER_TUI_THEME: {}", e)), errors, |t| self.tui.theme = Some(t));
+        }
+        // https://no-color.org: honoured unless a theme was chosen explicitly
+        if self.tui.theme.is_none() && var("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            self.tui.theme = Some(ThemeName::Monochrome);
+        }
+        if let Err(e) = self.telemetry.apply_env(var) {
+            errors.push(e);
+        }
+        if let Err(e) = self.monitor.threshold.apply_env(var) {
+            errors.push(e);
+        }
+        if let Err(e) = self.email.apply_env(var) {
+            errors.push(e);
+        }
+    }
+
+    fn validate(&self, errors: &mut Vec<String>) {
+        for (name, path) in [
+            ("storage.queue", &self.storage.queue),
+            ("storage.history", &self.storage.history),
+            ("storage.socket", &self.storage.socket),
+        ] {
+            if path.trim().is_empty() {
+                errors.push(format!("{} must not be empty", name));
+            }
+        }
+        if self.poll_interval < Duration::from_millis(10) {
+            errors.push(format!(
+                "engine.poll_interval_ms must be at least 10, got {}",
+                self.poll_interval.as_millis()
+            ));
+        }
+        if self.worker.pool_size == 0 {
+            errors.push("worker.pool_size must be at least 1".to_string());
+        }
+        if let Err(e) = EnvFilter::try_new(&self.telemetry.level) {
+            errors.push(format!("telemetry.level '{}' is invalid: {}", self.telemetry.level, e));
+        }
+        if self.telemetry.max_size_bytes == 0 {
+            errors.push("telemetry.max_size_bytes must be greater than 0".to_string());
+        }
+        if self.monitor.sample_interval.is_zero() {
+            errors.push("monitor.sample_interval_secs must be at least 1".to_string());
+        }
+        if let Some(cpu) = self.monitor.threshold.max_system_cpu_percent
+            && !(0.0..=100.0).contains(&cpu)
+        {
+            errors.push(format!("monitor.heavy_max_cpu must be between 0 and 100, got {}", cpu));
+        }
+        for (name, addr) in [("telemetry.metrics_addr", &self.metrics_addr), ("http.addr", &self.http.addr)] {
+            if let Some(addr) = addr
+                && addr.parse::<SocketAddr>().is_err()
+            {
+                errors.push(format!("{} '{}' is not a valid address like 127.0.0.1:8080", name, addr));
+            }
+        }
+        if self.http.addr.is_some() && self.http.token.as_deref().is_none_or(str::is_empty) {
+            errors.push("http.addr is set but http.token (or SCHEDULER_API_TOKEN) is missing".to_string());
+        }
+        errors.extend(self.email.validate());
+        if self.tui.log_lines == 0 {
+            errors.push("tui.log_lines must be at least 1".to_string());
+        }
+        if let Err(e) = self.tui.keymap() {
+            errors.extend(e);
+        }
+        for (i, job) in self.jobs.iter().enumerate() {
+            let name = format!("jobs[{}] ('{}')", i, job.description);
+            if job.description.trim().is_empty() {
+                errors.push(format!("jobs[{}]: description must not be empty", i));
+            }
+            if job.function.trim().is_empty() {
+                errors.push(format!("{}: function must not be empty", name));
+            }
+            if let Some(at) = job.at
+                && at < Job::now()
+            {
+                errors.push(format!("{}: at {} is in the past", name, at));
+            }
+            match job.delay_secs {
+                Some(d) if d < 0 => errors.push(format!("{}: delay_secs must not be negative", name)),
+                Some(d) if Job::now().checked_add(d).is_none_or(|t| Job::check_execution_time(t).is_err()) => {
+                    errors.push(format!("{}: delay_secs {} is out of range", name, d));
+                }
+                _ => {}
+            }
+            if let Some(Err(e)) = job.schedule.as_deref().map(str::parse::<Schedule>) {
+                errors.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+
+    /// Checks function names against what the worker actually registered.
+    pub fn validate_functions(&self, registered: &[String]) -> Result<(), String> {
+        let known = |name: &String| registered.contains(name);
+        let mut errors = Vec::new();
+        for name in self.worker.functions.iter().flatten().filter(|n| !known(n)) {
+            errors.push(format!("worker.functions: '{}' is not a registered function", name));
+        }
+        let enabled: Vec<&String> = match self.worker.functions.as_ref() {
+            Some(functions) => functions.iter().filter(|n| known(n)).collect(),
+            None => registered.iter().collect(),
+        };
+        for job in self.jobs.iter().filter(|j| !enabled.contains(&&j.function)) {
+            errors.push(format!(
+                "jobs ('{}'): function '{}' is not enabled",
+                job.description, job.function
+            ));
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let origin = self
+            .source
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "configuration".to_string());
+        Err(format!(
+            "Invalid {}:\n  - {}\n  (available functions: {})",
+            origin,
+            errors.join("\n  - "),
+            registered.join(", ")
+        ))
+    }
+}
+
+fn set<T>(target: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *target = value;
+    }
+}
+
+fn collect<T>(result: Result<T, String>, errors: &mut Vec<String>, apply: impl FnOnce(T)) {
+    match result {
+        Ok(value) => apply(value),
+        Err(e) => errors.push(e),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} must be a number, got '{}'", name, value))
+}
@@ -0,0 +1,117 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use uuid::Uuid;
+
+use super::{EngineState, Request, Response};
+use crate::history::RunRecord;
+use crate::job::Job;
+
+/// Client for the control socket of a running scheduler.
+pub struct ControlClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl ControlClient {
+    pub fn connect(socket_path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = socket_path.as_ref();
+        let writer = UnixStream::connect(path)
+            .map_err(|e| format!("Cannot connect to {}: {}", path.display(), e))?;
+        let reader = BufReader::new(writer.try_clone().map_err(|e| e.to_string())?);
+        Ok(Self { reader, writer })
+    }
+
+    /// Sends a raw request and waits for its response line.
+    pub fn request(&mut self, request: &Request) -> Result<Response, String> {
+        let json = serde_json::to_string(request).map_err(|e| e.to_string())?;
+        writeln!(self.writer, "{}", json).map_err(|e| e.to_string())?;
+        self.writer.flush().map_err(|e| e.to_string())?;
+
+        let mut line = String::new();
+        let read = self.reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("Scheduler closed the connection.".to_string());
+        }
+        serde_json::from_str(&line).map_err(|e| format!("Invalid response: {}", e))
+    }
+
+    pub fn add(&mut self, job: Job) -> Result<Job, String> {
+        expect_job(self.request(&Request::Add { job })?)
+    }
+
+    pub fn list(&mut self) -> Result<Vec<Job>, String> {
+        match self.request(&Request::List)? {
+            Response::Jobs(jobs) => Ok(jobs),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn get(&mut self, id: Uuid) -> Result<(Job, Vec<RunRecord>), String> {
+        match self.request(&Request::Get { id })? {
+            Response::JobDetail { job, runs } => Ok((job, runs)),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Result<Job, String> {
+        expect_job(self.request(&Request::Remove { id })?)
+    }
+
+    pub fn pause(&mut self, id: Uuid) -> Result<Job, String> {
+        expect_job(self.request(&Request::Pause { id })?)
+    }
+
+    pub fn resume(&mut self, id: Uuid) -> Result<Job, String> {
+        expect_job(self.request(&Request::Resume { id })?)
+    }
+
+    pub fn run_now(&mut self, id: Uuid) -> Result<Job, String> {
+        expect_job(self.request(&Request::RunNow { id })?)
+    }
+
+    pub fn reschedule(&mut self, id: Uuid, execution_time: i64) -> Result<Job, String> {
+        expect_job(self.request(&Request::Reschedule { id, execution_time })?)
+    }
+
+    pub fn history(&mut self, limit: usize, job: Option<Uuid>) -> Result<Vec<RunRecord>, String> {
+        match self.request(&Request::History { limit, job })? {
+            Response::History(records) => Ok(records),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn engine_status(&mut self) -> Result<EngineState, String> {
+        expect_engine(self.request(&Request::EngineStatus)?)
+    }
+
+    pub fn engine_start(&mut self) -> Result<EngineState, String> {
+        expect_engine(self.request(&Request::EngineStart)?)
+    }
+
+    pub fn engine_stop(&mut self) -> Result<EngineState, String> {
+        expect_engine(self.request(&Request::EngineStop)?)
+    }
+}
+
+fn expect_job(response: Response) -> Result<Job, String> {
+    match response {
+        Response::Job(job) => Ok(job),
+        other => Err(unexpected(other)),
+    }
+}
+
+fn expect_engine(response: Response) -> Result<EngineState, String> {
+    match response {
+        Response::Engine(state) => Ok(state),
+        other => Err(unexpected(other)),
+    }
+}
+
+fn unexpected(response: Response) -> String {
+    match response {
+        Response::Error(e) => e,
+        other => format!("Unexpected response: {:?}", other),
+    }
+}
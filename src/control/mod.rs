@@ -0,0 +1,171 @@
+//! Local control API: a JSON-lines request/response protocol over a Unix socket
+//! that lets the CLI and other tools drive a running scheduler.
+
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use crate::engine::TimePriorityEngine;
+use crate::events::EventBus;
+use crate::history::HistoryStore;
+use crate::job::{Job, Status};
+use crate::queue::QueueManager;
+use crate::schedule::Schedule;
+use uuid::Uuid;
+
+pub mod protocol;
+#[cfg(unix)]
+pub mod client;
+#[cfg(unix)]
+pub mod server;
+
+pub use protocol::{EngineState, Request, Response};
+
+pub const DEFAULT_SOCKET_PATH: &str = "scheduler.sock";
+
+/// Shared handles a request is executed against.
+pub struct ControlContext {
+    pub queue: Arc<Mutex<QueueManager>>,
+    pub history: Arc<Mutex<HistoryStore>>,
+    pub worker_tx: Sender<Job>,
+    pub engine: Option<Arc<TimePriorityEngine>>,
+    /// Job lifecycle events, for clients that stream them
+    pub events: EventBus,
+    /// Registered task functions; added jobs must use one of them
+    pub functions: Vec<String>,
+}
+
+impl ControlContext {
+    pub fn handle(&self, request: Request) -> Response {
+        match request {
+            Request::Add { mut job } => {
+                if let Err(e) = self.check_new_job(&job) {
+                    return Response::Error(e);
+                }
+                // Never replace a queued job through a client-chosen id, and start it
+                // fresh: only definition files own jobs that carry a definition name
+                job.id = Uuid::new_v4();
+                job.status = Status::Pending;
+                job.retry_count = 0;
+                job.definition = None;
+                self.with_queue(|q| {
+                    q.push(job.clone());
+                    Response::Job(job)
+                })
+            }
+            Request::List => self.with_queue(|q| Response::Jobs(q.snapshot())),
+            Request::Get { id } => {
+                let Some(job) = self.with_queue(|q| q.get(id).cloned()) else {
+                    return not_found();
+                };
+                let runs = self
+                    .history
+                    .lock()
+                    .map(|h| h.for_job(id, 5))
+                    .unwrap_or_default();
+                Response::JobDetail { job, runs }
+            }
+            Request::Remove { id } => match self.with_queue(|q| q.remove(id)) {
+                Some(job) => Response::Job(job),
+                None => not_found(),
+            },
+            Request::Pause { id } => self.with_queue(|q| {
+                if q.pause(id) {
+                    q.get(id).cloned().map_or_else(not_found, Response::Job)
+                } else if q.get(id).is_some() {
+                    Response::Error("Job is already paused.".to_string())
+                } else {
+                    not_found()
+                }
+            }),
+            Request::Resume { id } => self.with_queue(|q| {
+                if q.resume(id) {
+                    q.get(id).cloned().map_or_else(not_found, Response::Job)
+                } else if q.get(id).is_some() {
+                    Response::Error("Job is not paused.".to_string())
+                } else {
+                    not_found()
+                }
+            }),
+            Request::RunNow { id } => {
+                // Dispatch a copy so the queued job keeps its schedule
+                let Some(job) = self.with_queue(|q| q.get(id).map(Job::run_now_copy)) else {
+                    return not_found();
+                };
+                match self.worker_tx.send(job.clone()) {
+                    Ok(()) => Response::Job(job),
+                    Err(e) => Response::Error(format!("Dispatch error: {}", e)),
+                }
+            }
+            Request::Reschedule { id, execution_time } => self.with_queue(|q| {
+                if let Err(e) = Job::check_execution_time(execution_time) {
+                    Response::Error(e)
+                } else if q.reschedule(id, execution_time) {
+                    q.get(id).cloned().map_or_else(not_found, Response::Job)
+                } else {
+                    not_found()
+                }
+            }),
+            Request::History { limit, job } => {
+                let records = self
+                    .history
+                    .lock()
+                    .map(|h| match job {
+                        Some(id) => h.for_job(id, limit),
+                        None => h.recent(limit),
+                    })
+                    .unwrap_or_default();
+                Response::History(records)
+            }
+            Request::EngineStatus => self.engine_state(),
+            Request::EngineStart => match &self.engine {
+                Some(engine) => {
+                    engine.start();
+                    self.engine_state()
+                }
+                None => no_engine(),
+            },
+            Request::EngineStop => match &self.engine {
+                Some(engine) => {
+                    engine.stop();
+                    self.engine_state()
+                }
+                None => no_engine(),
+            },
+        }
+    }
+
+    fn check_new_job(&self, job: &Job) -> Result<(), String> {
+        Job::check_execution_time(job.execution_time)?;
+        if !self.functions.contains(&job.function) {
+            return Err(format!("No function registered for '{}'", job.function));
+        }
+        if let Some(schedule) = &job.schedule {
+            schedule.parse::<Schedule>().map_err(|e| format!("Invalid schedule '{}': {}", schedule, e))?;
+        }
+        Ok(())
+    }
+
+    fn with_queue<T>(&self, f: impl FnOnce(&mut QueueManager) -> T) -> T {
+        // A poisoned lock still holds a usable queue; keep serving requests
+        let mut q = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut q)
+    }
+
+    fn engine_state(&self) -> Response {
+        match &self.engine {
+            Some(engine) => Response::Engine(EngineState {
+                running: engine.is_running(),
+                queued: self.with_queue(|q| q.len()),
+            }),
+            None => no_engine(),
+        }
+    }
+}
+
+fn not_found() -> Response {
+    Response::Error("Job not found.".to_string())
+}
+
+fn no_engine() -> Response {
+    Response::Error("No engine is running in this process.".to_string())
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::history::RunRecord;
+use crate::job::Job;
+
+/// One request per line on the control socket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    Add { job: Job },
+    List,
+    Get { id: Uuid },
+    Remove { id: Uuid },
+    Pause { id: Uuid },
+    Resume { id: Uuid },
+    RunNow { id: Uuid },
+    Reschedule { id: Uuid, execution_time: i64 },
+    History { limit: usize, job: Option<Uuid> },
+    EngineStatus,
+    EngineStart,
+    EngineStop,
+}
+
+/// One response line per request, in the same order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "result", content = "data", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Job(Job),
+    Jobs(Vec<Job>),
+    JobDetail { job: Job, runs: Vec<RunRecord> },
+    History(Vec<RunRecord>),
+    Engine(EngineState),
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineState {
+    pub running: bool,
+    pub queued: usize,
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use super::{ControlContext, Request, Response};
+
+/// Listens on a Unix socket and answers JSON-line requests against a `ControlContext`.
+/// The socket file is removed again when the server is dropped.
+pub struct ControlServer {
+    socket_path: PathBuf,
+}
+
+impl ControlServer {
+    pub fn bind(socket_path: impl AsRef<Path>, ctx: Arc<ControlContext>) -> io::Result<Self> {
+        let socket_path = socket_path.as_ref().to_path_buf();
+        if socket_path.exists() {
+            // A live socket means another scheduler owns the queue
+            if UnixStream::connect(&socket_path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another scheduler", socket_path.display()),
+                ));
+            }
+            fs::remove_file(&socket_path)?;
+        }
+
+        let listener = UnixListener::bind(&socket_path)?;
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let ctx = Arc::clone(&ctx);
+                        thread::spawn(move || {
+                            if let Err(e) = serve_connection(stream, &ctx) {
+                                tracing::warn!("[Control] Connection error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => tracing::warn!("[Control] Accept failed: {}", e),
+                }
+            }
+        });
+
+        tracing::info!("[Control] Listening on {}", socket_path.display());
+        Ok(Self { socket_path })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.socket_path);
+    }
+}
+
+fn serve_connection(stream: UnixStream, ctx: &ControlContext) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => ctx.handle(request),
+            Err(e) => Response::Error(format!("Invalid request: {}", e)),
+        };
+        let json = serde_json::to_string(&response).map_err(io::Error::other)?;
+        writeln!(writer, "{}", json)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,304 @@
+//! Recurring jobs declared in version-controlled TOML/YAML files. The queue is
+//! reconciled against the definitions at startup and whenever a file changes:
+//! new definitions are queued, changed ones are updated in place and jobs whose
+//! definition was deleted are removed. Jobs added at runtime are left alone.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+
+use crate::config::JobSpec;
+use crate::job::Job;
+use crate::queue::QueueManager;
+use crate::schedule::Schedule;
+
+pub const DEFAULT_JOBS_DIR: &str = "jobs";
+/// How often the watcher looks for changed definition files
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+const EXTENSIONS: &[&str] = &["toml", "yaml", "yml"];
+
+/// A file holds either a single job or a `jobs` list.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobList {
+    jobs: Vec<JobSpec>,
+}
+
+/// Parses one definition file, TOML or YAML depending on its extension.
+pub fn parse_file(path: &Path) -> Result<Vec<JobSpec>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let yaml = matches!(extension(path), Some("yaml" | "yml"));
+    parse(&text, yaml).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Parses definition text; `yaml` selects YAML over TOML.
+pub fn parse(text: &str, yaml: bool) -> Result<Vec<JobSpec>, String> {
+    let mut specs = if yaml {
+        let value: serde_yaml::Value = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+        if value.get("jobs").is_some() {
+            serde_yaml::from_value::<JobList>(value).map(|l| l.jobs)
+        } else {
+            serde_yaml::from_value::<JobSpec>(value).map(|spec| vec![spec])
+        }
+        .map_err(|e| e.to_string())?
+    } else {
+        let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        if table.contains_key("jobs") {
+            table.try_into::<JobList>().map(|l| l.jobs)
+        } else {
+            table.try_into::<JobSpec>().map(|spec| vec![spec])
+        }
+        .map_err(|e| e.to_string())?
+    };
+    for spec in &mut specs {
+        if spec.description.is_empty()
+            && let Some(name) = &spec.name
+        {
+            spec.description = name.clone();
+        }
+    }
+    Ok(specs)
+}
+
+/// Reads every definition file in `dir` (not recursive), sorted by file name.
+/// A missing directory means no definitions. All problems are reported together.
+pub fn load_dir(dir: &Path) -> Result<Vec<JobSpec>, String> {
+    let mut specs = Vec::new();
+    let mut errors = Vec::new();
+    for path in definition_files(dir) {
+        match parse_file(&path) {
+            Ok(mut parsed) => specs.append(&mut parsed),
+            Err(e) => errors.push(e),
+        }
+    }
+    errors.extend(validate(&specs));
+    if errors.is_empty() {
+        Ok(specs)
+    } else {
+        Err(format!("Invalid job definitions in {}:\n  - {}", dir.display(), errors.join("\n  - ")))
+    }
+}
+
+fn validate(specs: &[JobSpec]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut names = HashSet::new();
+    for spec in specs {
+        let Some(name) = spec.name.as_deref().filter(|n| !n.trim().is_empty()) else {
+            errors.push(format!("job '{}': name is required", spec.description));
+            continue;
+        };
+        if !names.insert(name) {
+            errors.push(format!("job '{}': name is defined more than once", name));
+        }
+        if spec.function.trim().is_empty() {
+            errors.push(format!("job '{}': function must not be empty", name));
+        }
+        match spec.schedule.as_deref() {
+            None => errors.push(format!("job '{}': schedule is required", name)),
+            Some(schedule) => {
+                if let Err(e) = schedule.parse::<Schedule>() {
+                    errors.push(format!("job '{}': {}", name, e));
+                }
+            }
+        }
+        if spec.at.is_some() || spec.delay_secs.is_some() {
+            errors.push(format!("job '{}': use schedule instead of at/delay_secs", name));
+        }
+    }
+    errors
+}
+
+/// Fails if a definition names a function the worker does not provide.
+pub fn check_functions(specs: &[JobSpec], available: &[String]) -> Result<(), String> {
+    let unknown: Vec<String> = specs
+        .iter()
+        .filter(|spec| !available.contains(&spec.function))
+        .map(|spec| {
+            let name = spec.name.as_deref().unwrap_or(&spec.description);
+            format!("job '{}': function '{}' is not enabled", name, spec.function)
+        })
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Invalid job definitions:\n  - {}\n  (available functions: {})",
+        unknown.join("\n  - "),
+        available.join(", ")
+    ))
+}
+
+/// Names of the definitions touched by one reconciliation
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReconcileReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ReconcileReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Brings the queue in line with `specs`. Every definition ends up with a queued
+/// job; its next run time and state are kept unless the schedule changed.
+pub fn reconcile(queue: &mut QueueManager, specs: &[JobSpec]) -> ReconcileReport {
+    let now = Job::now();
+    let by_name: HashMap<&str, &JobSpec> = specs
+        .iter()
+        .filter_map(|spec| spec.name.as_deref().map(|name| (name, spec)))
+        .collect();
+    let mut report = ReconcileReport::default();
+    let mut queued = HashSet::new();
+
+    for job in queue.snapshot() {
+        let Some(name) = job.definition.clone() else {
+            continue;
+        };
+        // A retry can sit in the queue next to the job's next scheduled run. It has
+        // no schedule (the worker drops it) and is left to finish as it is
+        match by_name.get(name.as_str()) {
+            Some(_) if job.schedule.is_none() => {}
+            Some(spec) => {
+                if let Some(updated) = apply(spec, &job, now) {
+                    queue.update(updated);
+                    report.updated.push(name.clone());
+                }
+                queued.insert(name);
+            }
+            None => {
+                queue.remove(job.id);
+                report.removed.push(name);
+            }
+        }
+    }
+
+    for spec in specs {
+        let Some(name) = spec.name.as_ref().filter(|name| !queued.contains(*name)) else {
+            continue;
+        };
+        match spec.to_job() {
+            Ok(job) => {
+                queue.push(job);
+                report.added.push(name.clone());
+            }
+            Err(e) => tracing::warn!("Cannot queue job definition '{}': {}", name, e),
+        }
+    }
+    report
+}
+
+/// The job with the definition's fields, or None if nothing changed.
+fn apply(spec: &JobSpec, job: &Job, now: i64) -> Option<Job> {
+    let metadata = Some(spec.metadata.clone()).filter(|m| !m.is_empty());
+    let unchanged = job.description == spec.description
+        && job.function == spec.function
+        && job.priority == spec.priority
+        && job.max_retries == spec.max_retries
+        && job.metadata == metadata
+        && job.tags == spec.tags
+        && job.schedule == spec.schedule;
+    if unchanged {
+        return None;
+    }
+
+    let mut updated = job.clone();
+    if job.schedule != spec.schedule
+        && let Some(next) = spec
+            .schedule
+            .as_deref()
+            .and_then(|s| s.parse::<Schedule>().ok())
+            .and_then(|s| s.next_after(now))
+    {
+        updated.execution_time = next;
+    }
+    updated.description = spec.description.clone();
+    updated.function = spec.function.clone();
+    updated.priority = spec.priority;
+    updated.max_retries = spec.max_retries;
+    updated.metadata = metadata;
+    updated.tags = spec.tags.clone();
+    updated.schedule = spec.schedule.clone();
+    Some(updated)
+}
+
+/// Loads `dir`, checks the functions and reconciles the queue, logging the outcome.
+/// On any error the queue is left untouched.
+pub fn apply_dir(dir: &Path, queue: &Mutex<QueueManager>, available: &[String]) -> Result<ReconcileReport, String> {
+    let specs = load_dir(dir)?;
+    check_functions(&specs, available)?;
+    let report = match queue.lock() {
+        Ok(mut q) => reconcile(&mut q, &specs),
+        Err(_) => return Err("queue lock poisoned".to_string()),
+    };
+    if !report.is_empty() {
+        tracing::info!(
+            added = ?report.added,
+            updated = ?report.updated,
+            removed = ?report.removed,
+            "Applied job definitions from {}",
+            dir.display()
+        );
+    }
+    Ok(report)
+}
+
+/// Re-applies the definitions whenever a file in `dir` is added, changed or
+/// removed, until the queue is dropped.
+pub fn start_watcher(dir: PathBuf, queue: &Arc<Mutex<QueueManager>>, available: Vec<String>, interval: Duration) {
+    let queue = Arc::downgrade(queue);
+    // Taken before returning so no change made after this call is missed
+    let mut last = fingerprint(&dir);
+    thread::spawn(move || {
+        loop {
+            thread::sleep(interval);
+            let Some(queue) = queue.upgrade() else {
+                break;
+            };
+            let current = fingerprint(&dir);
+            if current == last {
+                continue;
+            }
+            last = current;
+            if let Err(e) = apply_dir(&dir, &queue, &available) {
+                tracing::error!("Job definitions not applied: {}", e);
+            }
+        }
+    });
+}
+
+fn extension(path: &Path) -> Option<&str> {
+    path.extension().and_then(|e| e.to_str())
+}
+
+fn definition_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && extension(path).is_some_and(|e| EXTENSIONS.contains(&e)))
+        .collect();
+    files.sort();
+    files
+}
+
+fn fingerprint(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+    definition_files(dir)
+        .into_iter()
+        .map(|path| {
+            let meta = fs::metadata(&path).ok();
+            let modified = meta.as_ref().and_then(|m| m.modified().ok());
+            let len = meta.map(|m| m.len()).unwrap_or(0);
+            (path, modified, len)
+        })
+        .collect()
+}
@@ -0,0 +1,323 @@
+//! Outgoing email: the message `email_fn` builds from a job's payload and the
+//! transports that deliver it. Which transport is used comes from the `[email]`
+//! section of the configuration (or `EMAIL_TRANSPORT`).
+
+pub mod outbox;
+pub mod resend;
+pub mod smtp;
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use lettre::Message;
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+
+pub const DEFAULT_OUTBOX_DIR: &str = "outbox";
+pub const DEFAULT_SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delivers a message; implemented by SMTP, the Resend API and the local outbox.
+pub trait Transport: Send + Sync {
+    /// Sends `email` and returns a short receipt (server reply, message id or file path).
+    fn send(&self, email: &Email) -> Result<String, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportKind {
+    Resend,
+    Smtp,
+    /// Maildir on local disk, for tests and offline environments
+    Outbox,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (port 587)
+    StartTls,
+    /// TLS from the start (port 465)
+    Tls,
+    /// No encryption, e.g. a local relay or test server (port 25)
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailConfig {
+    pub transport: TransportKind,
+    /// Sender used when the job does not set `SMTP_FROM`
+    pub from: Option<String>,
+    pub smtp: SmtpConfig,
+    pub resend: ResendConfig,
+    /// Maildir the outbox transport delivers into
+    pub outbox_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtpConfig {
+    pub host: Option<String>,
+    /// Defaults to the standard port for `security`
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub security: SmtpSecurity,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResendConfig {
+    pub api_key: Option<String>,
+    pub endpoint: String,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            transport: TransportKind::Resend,
+            from: None,
+            smtp: SmtpConfig {
+                host: None,
+                port: None,
+                username: None,
+                password: None,
+                security: SmtpSecurity::StartTls,
+                timeout: DEFAULT_SMTP_TIMEOUT,
+            },
+            resend: ResendConfig {
+                api_key: None,
+                endpoint: resend::ENDPOINT.to_string(),
+            },
+            outbox_dir: PathBuf::from(DEFAULT_OUTBOX_DIR),
+        }
+    }
+}
+
+impl EmailConfig {
+    /// Reads `EMAIL_TRANSPORT`, `SMTP_FROM`, `SMTP_HOST`, `SMTP_PORT`, `SMTP_USERNAME`,
+    /// `SMTP_PASSWORD`, `SMTP_SECURITY`, `RESEND_API_KEY` and `EMAIL_OUTBOX_DIR`.
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+        if let Some(transport) = var("EMAIL_TRANSPORT") {
+            self.transport = transport.parse().map_err(|e| format!("EMAIL_TRANSPORT: {}", e))?;
+        }
+        if let Some(from) = var("SMTP_FROM") {
+            self.from = Some(from);
+        }
+        if let Some(host) = var("SMTP_HOST") {
+            self.smtp.host = Some(host);
+        }
+        if let Some(port) = var("SMTP_PORT") {
+            self.smtp.port = Some(
+                port.parse()
+                    .map_err(|_| format!("SMTP_PORT must be a port number, got '{}'", port))?,
+            );
+        }
+        if let Some(username) = var("SMTP_USERNAME") {
+            self.smtp.username = Some(username);
+        }
+        if let Some(password) = var("SMTP_PASSWORD") {
+            self.smtp.password = Some(password);
+        }
+        if let Some(security) = var("SMTP_SECURITY") {
+            self.smtp.security = security.parse().map_err(|e| format!("SMTP_SECURITY: {}", e))?;
+        }
+        if let Some(key) = var("RESEND_API_KEY") {
+            self.resend.api_key = Some(key);
+        }
+        if let Some(dir) = var("EMAIL_OUTBOX_DIR") {
+            self.outbox_dir = PathBuf::from(dir);
+        }
+        Ok(())
+    }
+
+    /// Problems that would make every send fail, e.g. SMTP without a host.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.transport == TransportKind::Smtp && self.smtp.host.as_deref().is_none_or(|h| h.trim().is_empty()) {
+            errors.push("email.smtp.host (or SMTP_HOST) is required for the smtp transport".to_string());
+        }
+        if self.smtp.username.is_some() != self.smtp.password.is_some() {
+            errors.push("email.smtp.username and password must be set together".to_string());
+        }
+        errors
+    }
+
+    pub fn transport(&self) -> Result<Box<dyn Transport>, String> {
+        Ok(match self.transport {
+            TransportKind::Resend => Box::new(resend::ResendTransport::new(&self.resend)?),
+            TransportKind::Smtp => Box::new(smtp::SmtpTransport::new(&self.smtp)?),
+            TransportKind::Outbox => Box::new(outbox::Outbox::new(&self.outbox_dir)),
+        })
+    }
+}
+
+impl FromStr for TransportKind {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.trim().to_lowercase().as_str() {
+            "resend" => Ok(TransportKind::Resend),
+            "smtp" => Ok(TransportKind::Smtp),
+            "outbox" | "maildir" => Ok(TransportKind::Outbox),
+            other => Err(format!("expected resend, smtp or outbox, got '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransportKind::Resend => "resend",
+            TransportKind::Smtp => "smtp",
+            TransportKind::Outbox => "outbox",
+        })
+    }
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.trim().to_lowercase().as_str() {
+            "starttls" => Ok(SmtpSecurity::StartTls),
+            "tls" | "ssl" => Ok(SmtpSecurity::Tls),
+            "none" | "plain" => Ok(SmtpSecurity::None),
+            other => Err(format!("expected starttls, tls or none, got '{}'", other)),
+        }
+    }
+}
+
+/// One outgoing message. Addresses are `user@host` or `Name <user@host>`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Email {
+    pub from: String,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: String,
+    pub text: Option<String>,
+    pub html: Option<String>,
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+impl Attachment {
+    /// Reads `path`, guessing the content type from its extension.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read(path).map_err(|e| format!("Cannot attach {}: {}", path.display(), e))?;
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| format!("Cannot attach {}: not a file", path.display()))?;
+        Ok(Self {
+            content_type: content_type_for(path).to_string(),
+            filename,
+            content,
+        })
+    }
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    match extension.to_lowercase().as_str() {
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Splits a `,` or `;` separated address list, dropping empty entries.
+pub fn parse_addresses(list: &str) -> Vec<String> {
+    list.split([',', ';'])
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl Email {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.from.trim().is_empty() {
+            return Err("No sender: set SMTP_FROM or email.from".to_string());
+        }
+        if self.to.is_empty() && self.cc.is_empty() && self.bcc.is_empty() {
+            return Err("No recipients: set SMTP_RECIPIENT".to_string());
+        }
+        Ok(())
+    }
+
+    /// Every address the message is delivered to
+    pub fn recipients(&self) -> impl Iterator<Item = &String> {
+        self.to.iter().chain(&self.cc).chain(&self.bcc)
+    }
+
+    /// The MIME message for SMTP and the outbox. `keep_bcc` leaves the `Bcc`
+    /// header in, which only makes sense for a local copy.
+    pub fn to_message(&self, keep_bcc: bool) -> Result<Message, String> {
+        let mut builder = Message::builder().from(mailbox(&self.from)?).subject(&self.subject);
+        for address in &self.to {
+            builder = builder.to(mailbox(address)?);
+        }
+        for address in &self.cc {
+            builder = builder.cc(mailbox(address)?);
+        }
+        for address in &self.bcc {
+            builder = builder.bcc(mailbox(address)?);
+        }
+        if keep_bcc {
+            builder = builder.keep_bcc();
+        }
+
+        let text = self.text.clone().unwrap_or_default();
+        let body = match &self.html {
+            Some(html) if self.text.is_some() => Body::Multi(MultiPart::alternative_plain_html(text, html.clone())),
+            Some(html) => Body::Single(SinglePart::html(html.clone())),
+            None => Body::Single(SinglePart::plain(text)),
+        };
+        let message = if self.attachments.is_empty() {
+            match body {
+                Body::Single(part) => builder.singlepart(part),
+                Body::Multi(part) => builder.multipart(part),
+            }
+        } else {
+            let mut mixed = match body {
+                Body::Single(part) => MultiPart::mixed().singlepart(part),
+                Body::Multi(part) => MultiPart::mixed().multipart(part),
+            };
+            for attachment in &self.attachments {
+                let content_type = ContentType::parse(&attachment.content_type)
+                    .map_err(|e| format!("Attachment '{}': {}", attachment.filename, e))?;
+                mixed = mixed.singlepart(
+                    lettre::message::Attachment::new(attachment.filename.clone())
+                        .body(attachment.content.clone(), content_type),
+                );
+            }
+            builder.multipart(mixed)
+        };
+        message.map_err(|e| format!("Cannot build email: {}", e))
+    }
+}
+
+enum Body {
+    Single(SinglePart),
+    Multi(MultiPart),
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, String> {
+    address
+        .parse()
+        .map_err(|e| format!("Invalid email address '{}': {}", address, e))
+}
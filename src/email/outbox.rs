@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use super::{Email, Transport};
+
+/// Delivers into a Maildir (`tmp/`, `new/`, `cur/`) instead of sending. Each
+/// message is written to `tmp/` and renamed into `new/`, so readers never see a
+/// partial file; any mail client or `cat` can inspect them.
+pub struct Outbox {
+    dir: PathBuf,
+}
+
+impl Outbox {
+    pub fn new(dir: &Path) -> Self {
+        Self { dir: dir.to_path_buf() }
+    }
+
+    /// Messages delivered so far, oldest first
+    pub fn messages(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(self.dir.join("new")) else {
+            return Vec::new();
+        };
+        let mut files: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        files.sort();
+        files
+    }
+}
+
+impl Transport for Outbox {
+    fn send(&self, email: &Email) -> Result<String, String> {
+        let message = email.to_message(true)?;
+        for sub in ["tmp", "new", "cur"] {
+            let dir = self.dir.join(sub);
+            fs::create_dir_all(&dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        }
+        // Maildir names: unique, and sorting by name sorts by delivery time
+        let name = format!(
+            "{}.{}.scheduler.eml",
+            chrono::Utc::now().timestamp_micros(),
+            Uuid::new_v4().simple()
+        );
+        let tmp = self.dir.join("tmp").join(&name);
+        let path = self.dir.join("new").join(&name);
+        fs::write(&tmp, message.formatted()).map_err(|e| format!("Cannot write {}: {}", tmp.display(), e))?;
+        fs::rename(&tmp, &path).map_err(|e| format!("Cannot deliver {}: {}", path.display(), e))?;
+        Ok(format!("saved to {}", path.display()))
+    }
+}
@@ -0,0 +1,79 @@
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+
+use super::{Email, ResendConfig, Transport};
+
+pub const ENDPOINT: &str = "https://api.resend.com/emails";
+
+/// Sends through the Resend HTTP API (<https://resend.com/docs/api-reference/emails/send-email>).
+pub struct ResendTransport {
+    api_key: String,
+    endpoint: String,
+}
+
+impl ResendTransport {
+    pub fn new(config: &ResendConfig) -> Result<Self, String> {
+        let api_key = config
+            .api_key
+            .clone()
+            .filter(|k| !k.is_empty())
+            .ok_or("RESEND_API_KEY missing in .env")?;
+        Ok(Self {
+            api_key,
+            endpoint: config.endpoint.clone(),
+        })
+    }
+}
+
+impl Transport for ResendTransport {
+    fn send(&self, email: &Email) -> Result<String, String> {
+        let mut body = serde_json::json!({
+            "from": email.from,
+            "to": email.to,
+            "subject": email.subject,
+        });
+        let fields = [
+            ("cc", (!email.cc.is_empty()).then(|| serde_json::json!(email.cc))),
+            ("bcc", (!email.bcc.is_empty()).then(|| serde_json::json!(email.bcc))),
+            ("text", email.text.as_ref().map(|t| serde_json::json!(t))),
+            ("html", email.html.as_ref().map(|h| serde_json::json!(h))),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                body[key] = value;
+            }
+        }
+        if !email.attachments.is_empty() {
+            body["attachments"] = email
+                .attachments
+                .iter()
+                .map(|a| {
+                    serde_json::json!({
+                        "filename": a.filename,
+                        "content": STANDARD.encode(&a.content),
+                        "content_type": a.content_type,
+                    })
+                })
+                .collect();
+        }
+
+        let response = crate::tasks::http_client()
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .map_err(|e| format!("HTTP request failed: {}", e))?;
+        let status = response.status();
+        let text = response.text().unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("Resend API error ({}): {}", status, text));
+        }
+        let id = serde_json::from_str::<serde_json::Value>(&text)
+            .ok()
+            .and_then(|v| v["id"].as_str().map(str::to_string));
+        Ok(match id {
+            Some(id) => format!("Resend id {}", id),
+            None => format!("Resend {}", status),
+        })
+    }
+}
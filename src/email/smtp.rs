@@ -0,0 +1,50 @@
+use lettre::Transport as _;
+use lettre::transport::smtp::authentication::Credentials;
+
+use super::{Email, SmtpConfig, SmtpSecurity, Transport};
+
+/// Sends through an SMTP server, optionally authenticated.
+pub struct SmtpTransport {
+    inner: lettre::SmtpTransport,
+    host: String,
+}
+
+impl SmtpTransport {
+    pub fn new(config: &SmtpConfig) -> Result<Self, String> {
+        let host = config
+            .host
+            .as_deref()
+            .map(str::trim)
+            .filter(|h| !h.is_empty())
+            .ok_or("SMTP_HOST is not set")?;
+        let mut builder = match config.security {
+            SmtpSecurity::StartTls => lettre::SmtpTransport::starttls_relay(host),
+            SmtpSecurity::Tls => lettre::SmtpTransport::relay(host),
+            SmtpSecurity::None => Ok(lettre::SmtpTransport::builder_dangerous(host)),
+        }
+        .map_err(|e| format!("SMTP server {}: {}", host, e))?
+        .timeout(Some(config.timeout));
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(Self {
+            inner: builder.build(),
+            host: host.to_string(),
+        })
+    }
+}
+
+impl Transport for SmtpTransport {
+    fn send(&self, email: &Email) -> Result<String, String> {
+        let message = email.to_message(false)?;
+        let response = self
+            .inner
+            .send(&message)
+            .map_err(|e| format!("SMTP delivery via {} failed: {}", self.host, e))?;
+        let reply: Vec<&str> = response.message().collect();
+        Ok(format!("{} {}", response.code(), reply.join(" ")))
+    }
+}
@@ -1,4 +1,7 @@
+use crate::events::{EngineEvent, EventBus, JobEvent, JobEventKind, LogEvent, LogLevel, LogSource};
+use crate::config::DEFAULT_POLL_INTERVAL;
 use crate::job::{Job, Status};
+use crate::monitor::ResourceMonitor;
 use crate::queue::QueueManager;
 use chrono::Utc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -10,7 +13,9 @@ use std::time::Duration;
 pub struct TimePriorityEngine {
     queue: Arc<Mutex<QueueManager>>,
     worker_tx: Sender<Job>,
-    log_tx: Option<Sender<String>>,
+    events: Option<EventBus>,
+    monitor: Option<Arc<ResourceMonitor>>,
+    poll_interval: Duration,
     is_running: Arc<AtomicBool>,
     handle: Mutex<Option<JoinHandle<()>>>,
 }
@@ -20,93 +25,140 @@ impl TimePriorityEngine {
         Self {
             queue,
             worker_tx,
-            log_tx: None,
+            events: None,
+            monitor: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
             is_running: Arc::new(AtomicBool::new(false)),
             handle: Mutex::new(None),
         }
     }
 
-    /// For TUI: engine sends log lines to this channel instead of println!.
-    pub fn new_with_log(
+    /// Engine publishes its lifecycle and every dispatch on the event bus
+    /// instead of writing to the tracing log.
+    pub fn new_with_events(
         queue: Arc<Mutex<QueueManager>>,
         worker_tx: Sender<Job>,
-        log_tx: Sender<String>,
+        events: EventBus,
     ) -> Self {
         Self {
             queue,
             worker_tx,
-            log_tx: Some(log_tx),
+            events: Some(events),
+            monitor: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
             is_running: Arc::new(AtomicBool::new(false)),
             handle: Mutex::new(None),
         }
     }
 
+    /// Keep jobs tagged `heavy` queued while the monitor reports overload
+    pub fn set_monitor(&mut self, monitor: Arc<ResourceMonitor>) {
+        self.monitor = Some(monitor);
+    }
+
+    /// How often the queue is checked for due jobs (500ms by default)
+    pub fn set_poll_interval(&mut self, interval: Duration) {
+        self.poll_interval = interval;
+    }
+
     /// Starts the Time & Priority Engine in a background thread.
     /// It polls the queue at a set interval for jobs that are ready to execute.
     pub fn start(&self) {
         let mut handle_lock = self.handle.lock().unwrap();
         if handle_lock.is_some() {
-            println!("[Engine] Already running.");
+            tracing::info!("[Engine] Already running.");
             return;
         }
 
         self.is_running.store(true, Ordering::SeqCst);
         let queue_clone = Arc::clone(&self.queue);
         let tx_clone = self.worker_tx.clone();
-        let log_tx = self.log_tx.clone();
+        let events = self.events.clone();
+        let monitor = self.monitor.clone();
+        let poll_interval = self.poll_interval;
         let running_flag = Arc::clone(&self.is_running);
 
         let thread_handle = thread::spawn(move || {
-            if let Some(ref tx) = log_tx {
-                let _ = tx.send("[Engine] Started.".to_string());
+            if let Some(ref bus) = events {
+                bus.publish(EngineEvent::Started);
             } else {
-                println!("[Engine] Started polling thread.");
+                tracing::info!("[Engine] Started polling thread.");
             }
+            let mut holding = false;
             while running_flag.load(Ordering::Relaxed) {
                 let now = Utc::now().timestamp();
-
                 let mut ready_jobs = Vec::new();
+                let mut held = 0;
                 // Secure the lock briefly to extract ready jobs
                 if let Ok(mut q) = queue_clone.lock() {
-                    ready_jobs = q.pop_ready(now);
+                    ready_jobs = q.pop_ready_where(now, |job| {
+                        let hold = monitor.as_ref().is_some_and(|m| m.should_hold(job));
+                        held += usize::from(hold);
+                        hold
+                    });
+                    // Recurring jobs: queue the next run as this one is dispatched
+                    for next in ready_jobs.iter().filter_map(|job| job.next_occurrence(now)) {
+                        q.push(next);
+                    }
+                }
+
+                // Report only when holding starts or ends, not on every tick
+                if (held > 0) != holding {
+                    holding = held > 0;
+                    let (level, message) = if holding {
+                        (LogLevel::Warn, format!("Load above threshold, holding back {} heavy job(s).", held))
+                    } else {
+                        (LogLevel::Info, "No heavy jobs held back any more.".to_string())
+                    };
+                    match events {
+                        Some(ref bus) => bus.publish(LogEvent::new(level, LogSource::Engine, None, message)),
+                        None => tracing::warn!("[Engine] {}", message),
+                    }
                 }
 
                 // Push ready jobs to the worker channel
                 for mut job in ready_jobs {
                     job.status = Status::Running;
-                    if let Some(ref tx) = log_tx {
-                        let _ = tx.send(format!(
-                            "[Engine] Dispatched '{}' (priority {})",
-                            job.description, job.priority
-                        ));
+                    if let Some(ref bus) = events {
+                        let lag_secs = now - job.execution_time;
+                        bus.publish(JobEvent::new(&job, JobEventKind::Dispatched { lag_secs }));
                     } else {
-                        println!(
+                        tracing::info!(
                             "[Engine] Job {} ('{}') is ready (priority: {}). Dispatching to worker...",
-                            job.id, job.description, job.priority
+                            job.id,
+                            job.description,
+                            job.priority
                         );
                     }
+                    let job_id = job.id;
                     if let Err(e) = tx_clone.send(job) {
-                        if let Some(ref tx) = log_tx {
-                            let _ = tx.send(format!("[Engine] Dispatch error: {}", e));
+                        if let Some(ref bus) = events {
+                            bus.publish(EngineEvent::DispatchError {
+                                job_id,
+                                error: e.to_string(),
+                            });
                         } else {
-                            eprintln!("[Engine] Failed to dispatch job: {}", e);
+                            tracing::error!("[Engine] Failed to dispatch job: {}", e);
                         }
                     }
                 }
 
-                // Poll every 500ms
-                thread::sleep(Duration::from_millis(500));
+                thread::sleep(poll_interval);
             }
-            if let Some(ref tx) = log_tx {
-                let _ = tx.send("[Engine] Stopped.".to_string());
+            if let Some(ref bus) = events {
+                bus.publish(EngineEvent::Stopped);
             } else {
-                println!("[Engine] Polling thread stopped gracefully.");
+                tracing::info!("[Engine] Polling thread stopped gracefully.");
             }
         });
 
         *handle_lock = Some(thread_handle);
     }
 
+    pub fn is_running(&self) -> bool {
+        self.is_running.load(Ordering::SeqCst)
+    }
+
     /// Signals the Engine thread to stop and waits for it to finish gracefully.
     pub fn stop(&self) {
         self.is_running.store(false, Ordering::SeqCst);
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::job::Job;
+
+/// Everything the scheduler reports while running. Consumers (TUI, history,
+/// notifications, HTTP streams) subscribe to an `EventBus` and match on these
+/// instead of parsing log strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchedulerEvent {
+    Job(JobEvent),
+    Engine(EngineEvent),
+    Log(LogEvent),
+}
+
+/// A change in a job's lifecycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobEvent {
+    /// Unix seconds when the event happened
+    pub at: i64,
+    pub job_id: Uuid,
+    pub description: String,
+    pub function: String,
+    pub priority: u8,
+    #[serde(flatten)]
+    pub kind: JobEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobEventKind {
+    Added { execution_time: i64 },
+    Dispatched { lag_secs: i64 },
+    Started { attempt: u32 },
+    Succeeded {
+        attempt: u32,
+        duration_ms: u64,
+        /// Snippet of what the task produced, e.g. an HTTP response
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    Failed {
+        attempt: u32,
+        duration_ms: u64,
+        error: String,
+        will_retry: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    Retried { attempt: u32, max_retries: u32 },
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EngineEvent {
+    Started,
+    Stopped,
+    DispatchError { job_id: Uuid, error: String },
+}
+
+/// Free-form message from a component, usually a task reporting progress.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEvent {
+    pub at: i64,
+    pub level: LogLevel,
+    pub source: LogSource,
+    pub job_id: Option<Uuid>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSource {
+    Queue,
+    Engine,
+    Worker,
+    Task,
+    /// Anything else logged through `tracing` (persistence, control socket, ...)
+    System,
+}
+
+impl JobEvent {
+    pub fn new(job: &Job, kind: JobEventKind) -> Self {
+        Self {
+            at: Job::now(),
+            job_id: job.id,
+            description: job.description.clone(),
+            function: job.function.clone(),
+            priority: job.priority,
+            kind,
+        }
+    }
+
+    /// Short snake_case name of the event kind, e.g. `dispatched`
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            JobEventKind::Added { .. } => "added",
+            JobEventKind::Dispatched { .. } => "dispatched",
+            JobEventKind::Started { .. } => "started",
+            JobEventKind::Succeeded { .. } => "succeeded",
+            JobEventKind::Failed { .. } => "failed",
+            JobEventKind::Retried { .. } => "retried",
+            JobEventKind::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl LogEvent {
+    pub fn new(level: LogLevel, source: LogSource, job_id: Option<Uuid>, message: impl Into<String>) -> Self {
+        Self {
+            at: Job::now(),
+            level,
+            source,
+            job_id,
+            message: message.into(),
+        }
+    }
+}
+
+impl SchedulerEvent {
+    /// Component the event originates from
+    pub fn source(&self) -> LogSource {
+        match self {
+            SchedulerEvent::Job(e) => match e.kind {
+                JobEventKind::Added { .. } | JobEventKind::Cancelled => LogSource::Queue,
+                JobEventKind::Dispatched { .. } => LogSource::Engine,
+                _ => LogSource::Worker,
+            },
+            SchedulerEvent::Engine(_) => LogSource::Engine,
+            SchedulerEvent::Log(e) => e.source,
+        }
+    }
+
+    pub fn level(&self) -> LogLevel {
+        match self {
+            SchedulerEvent::Job(e) => match e.kind {
+                JobEventKind::Failed { will_retry: false, .. } => LogLevel::Error,
+                JobEventKind::Failed { .. } | JobEventKind::Retried { .. } => LogLevel::Warn,
+                _ => LogLevel::Info,
+            },
+            SchedulerEvent::Engine(EngineEvent::DispatchError { .. }) => LogLevel::Error,
+            SchedulerEvent::Engine(_) => LogLevel::Info,
+            SchedulerEvent::Log(e) => e.level,
+        }
+    }
+
+    /// Job the event is about, if any
+    pub fn job_id(&self) -> Option<Uuid> {
+        match self {
+            SchedulerEvent::Job(e) => Some(e.job_id),
+            SchedulerEvent::Engine(EngineEvent::DispatchError { job_id, .. }) => Some(*job_id),
+            SchedulerEvent::Engine(_) => None,
+            SchedulerEvent::Log(e) => e.job_id,
+        }
+    }
+}
+
+impl fmt::Display for LogSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LogSource::Queue => "Queue",
+            LogSource::Engine => "Engine",
+            LogSource::Worker => "Worker",
+            LogSource::Task => "Task",
+            LogSource::System => "System",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One human-readable log line, as shown in the TUI log panel.
+impl fmt::Display for SchedulerEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] ", self.source())?;
+        match self {
+            SchedulerEvent::Job(e) => match &e.kind {
+                JobEventKind::Added { .. } => write!(f, "Added '{}' ({})", e.description, e.function),
+                JobEventKind::Dispatched { .. } => {
+                    write!(f, "Dispatched '{}' (priority {})", e.description, e.priority)
+                }
+                JobEventKind::Started { .. } => write!(f, "Executing '{}'", e.description),
+                JobEventKind::Succeeded { duration_ms, .. } => {
+                    write!(f, "Done '{}' in {} ms", e.description, duration_ms)
+                }
+                JobEventKind::Failed { error, .. } => write!(f, "Error in '{}': {}", e.description, error),
+                JobEventKind::Retried { attempt, max_retries } => {
+                    write!(f, "Job '{}' will retry ({}/{})", e.description, attempt, max_retries)
+                }
+                JobEventKind::Cancelled => write!(f, "Removed '{}'", e.description),
+            },
+            SchedulerEvent::Engine(EngineEvent::Started) => write!(f, "Started."),
+            SchedulerEvent::Engine(EngineEvent::Stopped) => write!(f, "Stopped."),
+            SchedulerEvent::Engine(EngineEvent::DispatchError { error, .. }) => {
+                write!(f, "Dispatch error: {}", error)
+            }
+            SchedulerEvent::Log(e) => write!(f, "{}", e.message),
+        }
+    }
+}
+
+impl From<JobEvent> for SchedulerEvent {
+    fn from(event: JobEvent) -> Self {
+        SchedulerEvent::Job(event)
+    }
+}
+
+impl From<EngineEvent> for SchedulerEvent {
+    fn from(event: EngineEvent) -> Self {
+        SchedulerEvent::Engine(event)
+    }
+}
+
+impl From<LogEvent> for SchedulerEvent {
+    fn from(event: LogEvent) -> Self {
+        SchedulerEvent::Log(event)
+    }
+}
+
+/// Fan-out of scheduler events to any number of subscribers. Cloning shares the bus.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Sender<SchedulerEvent>>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every event published after this call is delivered to the returned receiver.
+    pub fn subscribe(&self) -> Receiver<SchedulerEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subs) = self.subscribers.lock() {
+            subs.push(tx);
+        }
+        rx
+    }
+
+    /// Delivers the event to all live subscribers, forgetting disconnected ones.
+    pub fn publish(&self, event: impl Into<SchedulerEvent>) {
+        let event = event.into();
+        if let Ok(mut subs) = self.subscribers.lock() {
+            subs.retain(|tx| tx.send(event.clone()).is_ok());
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().map(|s| s.len()).unwrap_or(0)
+    }
+}
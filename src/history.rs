@@ -0,0 +1,166 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, mpsc::Receiver},
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::events::{JobEventKind, SchedulerEvent};
+
+/// How many records are kept in memory; the file on disk keeps everything.
+const MAX_CACHED_RECORDS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutcome {
+    Success,
+    Failed,
+}
+
+/// One execution attempt of a job, as recorded by the worker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub job_id: Uuid,
+    pub description: String,
+    pub function: String,
+    pub attempt: u32,
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub outcome: RunOutcome,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// What the task reported producing, e.g. the start of an HTTP response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl RunRecord {
+    /// Builds the record of a finished attempt from its succeeded/failed event.
+    pub fn from_event(event: &SchedulerEvent) -> Option<Self> {
+        let SchedulerEvent::Job(e) = event else {
+            return None;
+        };
+        let (attempt, duration_ms, outcome, message, output) = match &e.kind {
+            JobEventKind::Succeeded { attempt, duration_ms, output } => {
+                (*attempt, *duration_ms, RunOutcome::Success, None, output.clone())
+            }
+            JobEventKind::Failed { attempt, duration_ms, error, output, .. } => {
+                (*attempt, *duration_ms, RunOutcome::Failed, Some(error.clone()), output.clone())
+            }
+            _ => return None,
+        };
+        Some(Self {
+            job_id: e.job_id,
+            description: e.description.clone(),
+            function: e.function.clone(),
+            attempt,
+            started_at: e.at - (duration_ms / 1000) as i64,
+            duration_ms,
+            outcome,
+            message,
+            output,
+        })
+    }
+}
+
+/// Appends a record for every finished attempt seen on the event stream.
+pub fn start_recorder(history: Arc<Mutex<HistoryStore>>, events: Receiver<SchedulerEvent>) {
+    thread::spawn(move || {
+        for event in events {
+            let Some(record) = RunRecord::from_event(&event) else {
+                continue;
+            };
+            if let Ok(mut h) = history.lock()
+                && let Err(e) = h.append(record)
+            {
+                tracing::error!("Failed to append run history: {}", e);
+            }
+        }
+    });
+}
+
+/// Append-only run history stored as JSON lines next to the queue file.
+pub struct HistoryStore {
+    pub storage_path: PathBuf,
+    records: VecDeque<RunRecord>,
+}
+
+impl HistoryStore {
+    /// Opens the history file, loading existing records. Malformed lines are skipped.
+    pub fn open(storage_path: &str) -> Self {
+        let storage_path = PathBuf::from(storage_path);
+        let mut records: VecDeque<RunRecord> = fs::read_to_string(&storage_path)
+            .map(|data| {
+                data.lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if records.len() > MAX_CACHED_RECORDS {
+            records.drain(..records.len() - MAX_CACHED_RECORDS);
+        }
+
+        Self {
+            storage_path,
+            records,
+        }
+    }
+
+    pub fn append(&mut self, record: RunRecord) -> io::Result<()> {
+        let line = serde_json::to_string(&record).map_err(io::Error::other)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.storage_path)?;
+        writeln!(file, "{}", line)?;
+
+        self.records.push_back(record);
+        if self.records.len() > MAX_CACHED_RECORDS {
+            self.records.pop_front();
+        }
+        Ok(())
+    }
+
+    /// Returns up to `limit` records, newest first.
+    pub fn recent(&self, limit: usize) -> Vec<RunRecord> {
+        self.records.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Returns up to `limit` records of a single job, newest first.
+    pub fn for_job(&self, id: Uuid, limit: usize) -> Vec<RunRecord> {
+        self.records
+            .iter()
+            .rev()
+            .filter(|r| r.job_id == id)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Records of runs that overlap `from..to` (Unix seconds), oldest first.
+    pub fn between(&self, from: i64, to: i64) -> Vec<RunRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.started_at < to && r.started_at + (r.duration_ms / 1000) as i64 >= from)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the newest record matching `predicate`.
+    pub fn latest(&self, predicate: impl Fn(&RunRecord) -> bool) -> Option<RunRecord> {
+        self.records.iter().rev().find(|r| predicate(r)).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
@@ -0,0 +1,298 @@
+//! Embedded REST API (cargo feature `http`) so other services can manage jobs
+//! over HTTP, plus a server-sent events stream of job lifecycle events at
+//! `GET /events`. Every request except `GET /openapi.json` needs
+//! `Authorization: Bearer <token>`.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request as HttpRequest, Response as HttpResponse, Server};
+use uuid::Uuid;
+
+use crate::control::{ControlContext, Request, Response};
+use crate::events::SchedulerEvent;
+use crate::job::Job;
+
+pub mod openapi;
+
+/// Comment line sent on idle event streams so proxies keep them open
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Body of `POST /jobs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewJob {
+    /// Absolute Unix timestamp; takes precedence over `delay_secs`
+    #[serde(default)]
+    pub execution_time: Option<i64>,
+    /// Seconds from now
+    #[serde(default)]
+    pub delay_secs: Option<i64>,
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+    pub description: String,
+    pub function: String,
+    #[serde(default = "default_retries")]
+    pub max_retries: u32,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Body of `POST /jobs/{id}/reschedule`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reschedule {
+    #[serde(default)]
+    pub execution_time: Option<i64>,
+    #[serde(default)]
+    pub delay_secs: Option<i64>,
+}
+
+fn default_priority() -> u8 {
+    1
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+fn resolve_time(execution_time: Option<i64>, delay_secs: Option<i64>) -> Result<i64, String> {
+    match (execution_time, delay_secs) {
+        (Some(t), _) => Ok(t),
+        (None, Some(d)) => Job::now().checked_add(d).ok_or_else(|| format!("delay_secs {} is out of range", d)),
+        (None, None) => Err("Either execution_time or delay_secs is required.".to_string()),
+    }
+}
+
+/// Serves the REST API on a background thread; stops when dropped.
+pub struct HttpServer {
+    server: Arc<Server>,
+    addr: SocketAddr,
+}
+
+impl HttpServer {
+    pub fn start(addr: &str, token: &str, ctx: Arc<ControlContext>) -> io::Result<Self> {
+        let server = Arc::new(Server::http(addr).map_err(io::Error::other)?);
+        let addr = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| io::Error::other("HTTP server is not bound to an IP address"))?;
+
+        let token = token.to_string();
+        let accept = Arc::clone(&server);
+        thread::spawn(move || {
+            for request in accept.incoming_requests() {
+                let ctx = Arc::clone(&ctx);
+                let token = token.clone();
+                // One thread per request so slow clients don't stall the API
+                thread::spawn(move || handle(request, &token, &ctx));
+            }
+        });
+
+        tracing::info!("[HTTP] Listening on http://{}", addr);
+        Ok(Self { server, addr })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// An API reply before it is turned into a tiny_http response.
+struct Reply {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Reply {
+    fn ok(body: impl Serialize) -> Self {
+        Self::with_status(200, body)
+    }
+
+    fn with_status(status: u16, body: impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_value(body).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::with_status(status, serde_json::json!({ "error": message.into() }))
+    }
+}
+
+fn handle(mut request: HttpRequest, token: &str, ctx: &ControlContext) {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let reply = if matches!((&method, segments.as_slice()), (Method::Get, ["openapi.json"])) {
+        Reply::ok(openapi::document())
+    } else if !authorized(&request, token) {
+        Reply::error(401, "Missing or invalid bearer token.")
+    } else if matches!((&method, segments.as_slice()), (Method::Get, ["events"])) {
+        stream_events(request, ctx, query);
+        return;
+    } else {
+        let mut body = String::new();
+        match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => route(ctx, &method, &segments, query, &body),
+            Err(e) => Reply::error(400, format!("Unreadable body: {}", e)),
+        }
+    };
+
+    let json = serde_json::to_string(&reply.body).unwrap_or_default();
+    let response = HttpResponse::from_string(json)
+        .with_status_code(reply.status)
+        .with_header(content_type("application/json"));
+    if let Err(e) = request.respond(response) {
+        tracing::warn!("[HTTP] Failed to respond to {} {}: {}", method, url, e);
+    }
+}
+
+fn route(ctx: &ControlContext, method: &Method, segments: &[&str], query: &str, body: &str) -> Reply {
+    match (method, segments) {
+        (Method::Get, ["jobs"]) => call(ctx, Request::List, 200),
+        (Method::Post, ["jobs"]) => {
+            let new_job: NewJob = match serde_json::from_str(body) {
+                Ok(j) => j,
+                Err(e) => return Reply::error(400, format!("Invalid job: {}", e)),
+            };
+            let job = resolve_time(new_job.execution_time, new_job.delay_secs).and_then(|t| {
+                Job::new(t, new_job.priority, new_job.description, new_job.function, new_job.max_retries)
+            });
+            match job {
+                Ok(mut job) => {
+                    job.metadata = new_job.metadata;
+                    job.tags = new_job.tags;
+                    call(ctx, Request::Add { job }, 201)
+                }
+                Err(e) => Reply::error(400, e),
+            }
+        }
+        (Method::Get, ["jobs", id]) => with_id(id, |id| call(ctx, Request::Get { id }, 200)),
+        (Method::Delete, ["jobs", id]) => with_id(id, |id| call(ctx, Request::Remove { id }, 200)),
+        (Method::Post, ["jobs", id, "reschedule"]) => with_id(id, |id| {
+            let reschedule: Reschedule = match serde_json::from_str(body) {
+                Ok(r) => r,
+                Err(e) => return Reply::error(400, format!("Invalid reschedule: {}", e)),
+            };
+            match resolve_time(reschedule.execution_time, reschedule.delay_secs) {
+                Ok(execution_time) => call(ctx, Request::Reschedule { id, execution_time }, 200),
+                Err(e) => Reply::error(400, e),
+            }
+        }),
+        (Method::Get, ["history"]) => {
+            let params = parse_query(query);
+            let limit = params.get("limit").and_then(|l| l.parse().ok()).unwrap_or(50);
+            let job = match params.get("job").map(|j| Uuid::parse_str(j)) {
+                Some(Ok(id)) => Some(id),
+                Some(Err(_)) => return Reply::error(400, "Invalid job id."),
+                None => None,
+            };
+            call(ctx, Request::History { limit, job }, 200)
+        }
+        (_, ["jobs"] | ["jobs", _] | ["jobs", _, "reschedule"] | ["history"] | ["events"]) => {
+            Reply::error(405, "Method not allowed.")
+        }
+        _ => Reply::error(404, "Not found."),
+    }
+}
+
+/// Streams job events as `text/event-stream` until the client disconnects.
+/// `?job=<id>` and `?types=failed,succeeded` narrow the stream.
+fn stream_events(request: HttpRequest, ctx: &ControlContext, query: &str) {
+    let params = parse_query(query);
+    let job_filter = params.get("job").and_then(|j| Uuid::parse_str(j).ok());
+    let type_filter: Option<Vec<&str>> = params.get("types").map(|t| t.split(',').collect());
+
+    let events = ctx.events.subscribe();
+    let mut writer = request.into_writer();
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+    if send(&mut writer, head).is_err() {
+        return;
+    }
+
+    loop {
+        let chunk = match events.recv_timeout(KEEP_ALIVE) {
+            Ok(SchedulerEvent::Job(event)) => {
+                if job_filter.is_some_and(|id| id != event.job_id)
+                    || type_filter.as_ref().is_some_and(|t| !t.contains(&event.name()))
+                {
+                    continue;
+                }
+                let json = serde_json::to_string(&event).unwrap_or_default();
+                format!("event: {}\ndata: {}\n\n", event.name(), json)
+            }
+            Ok(_) => continue,
+            Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if send(&mut writer, &chunk).is_err() {
+            break;
+        }
+    }
+}
+
+fn send(writer: &mut Box<dyn Write + Send>, chunk: &str) -> io::Result<()> {
+    writer.write_all(chunk.as_bytes())?;
+    writer.flush()
+}
+
+/// Runs a control request and maps its response onto HTTP.
+fn call(ctx: &ControlContext, request: Request, success: u16) -> Reply {
+    match ctx.handle(request) {
+        Response::Ok => Reply::with_status(success, serde_json::json!({})),
+        Response::Job(job) => Reply::with_status(success, job),
+        Response::Jobs(jobs) => Reply::with_status(success, jobs),
+        Response::JobDetail { job, runs } => {
+            Reply::with_status(success, serde_json::json!({ "job": job, "runs": runs }))
+        }
+        Response::History(records) => Reply::with_status(success, records),
+        Response::Engine(state) => Reply::with_status(success, state),
+        Response::Error(e) if e == "Job not found." => Reply::error(404, e),
+        Response::Error(e) => Reply::error(400, e),
+    }
+}
+
+fn with_id(id: &str, f: impl FnOnce(Uuid) -> Reply) -> Reply {
+    match Uuid::parse_str(id) {
+        Ok(id) => f(id),
+        Err(_) => Reply::error(400, "Invalid job id."),
+    }
+}
+
+/// Percent-decoded `key=value` pairs of a query string.
+fn parse_query(query: &str) -> HashMap<String, String> {
+    url::form_urlencoded::parse(query.as_bytes()).into_owned().collect()
+}
+
+fn authorized(request: &HttpRequest, token: &str) -> bool {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", value).expect("static header is valid")
+}
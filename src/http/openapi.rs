@@ -0,0 +1,194 @@
+use serde_json::{Value, json};
+
+/// OpenAPI 3 description of the REST API, served at `GET /openapi.json`.
+pub fn document() -> Value {
+    let id_param = json!({
+        "name": "id", "in": "path", "required": true,
+        "schema": { "type": "string", "format": "uuid" }
+    });
+    let error = json!({
+        "description": "Error",
+        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } }
+    });
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Task Scheduler API", "version": env!("CARGO_PKG_VERSION") },
+        "components": {
+            "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+            "schemas": schemas()
+        },
+        "security": [{ "bearer": [] }],
+        "paths": {
+            "/jobs": {
+                "get": {
+                    "summary": "List queued jobs",
+                    "responses": { "200": array_of("Job"), "401": error }
+                },
+                "post": {
+                    "summary": "Create a job",
+                    "requestBody": body_of("NewJob"),
+                    "responses": { "201": single("Job"), "400": error, "401": error }
+                }
+            },
+            "/jobs/{id}": {
+                "parameters": [id_param],
+                "get": {
+                    "summary": "Get a job and its recent runs",
+                    "responses": { "200": single("JobDetail"), "404": error }
+                },
+                "delete": {
+                    "summary": "Cancel a job",
+                    "responses": { "200": single("Job"), "404": error }
+                }
+            },
+            "/jobs/{id}/reschedule": {
+                "parameters": [id_param],
+                "post": {
+                    "summary": "Move a job to a new execution time",
+                    "requestBody": body_of("Reschedule"),
+                    "responses": { "200": single("Job"), "400": error, "404": error }
+                }
+            },
+            "/events": {
+                "get": {
+                    "summary": "Server-sent events stream of job lifecycle events",
+                    "parameters": [
+                        { "name": "job", "in": "query", "schema": { "type": "string", "format": "uuid" } },
+                        { "name": "types", "in": "query", "description": "Comma-separated event types", "schema": { "type": "string" } }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "One `event: <type>` / `data: <JobEvent>` pair per event",
+                            "content": { "text/event-stream": { "schema": schema_ref("JobEvent") } }
+                        },
+                        "401": error
+                    }
+                }
+            },
+            "/history": {
+                "get": {
+                    "summary": "Recent job runs, newest first",
+                    "parameters": [
+                        { "name": "limit", "in": "query", "schema": { "type": "integer", "default": 50 } },
+                        { "name": "job", "in": "query", "schema": { "type": "string", "format": "uuid" } }
+                    ],
+                    "responses": { "200": array_of("RunRecord"), "401": error }
+                }
+            }
+        }
+    })
+}
+
+fn schemas() -> Value {
+    let status = json!({ "type": "string", "enum": ["Pending", "Running", "Success", "Failed", "Paused"] });
+    let metadata = json!({ "type": "object", "additionalProperties": { "type": "string" }, "nullable": true });
+    let tags = json!({ "type": "array", "items": { "type": "string" }, "description": "`heavy` jobs wait while the machine is overloaded" });
+
+    json!({
+        "Job": {
+            "type": "object",
+            "required": ["id", "execution_time", "priority", "description", "function", "status", "max_retries", "retry_count"],
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "execution_time": { "type": "integer", "description": "Unix seconds" },
+                "priority": { "type": "integer", "minimum": 0, "maximum": 255 },
+                "description": { "type": "string" },
+                "function": { "type": "string" },
+                "status": status,
+                "max_retries": { "type": "integer", "minimum": 0 },
+                "retry_count": { "type": "integer", "minimum": 0 },
+                "metadata": metadata,
+                "tags": tags,
+                "definition": { "type": "string", "description": "Job definition file entry that manages this job" },
+                "schedule": { "type": "string", "description": "Recurring schedule, e.g. `every 15m` or `0 2 * * *`" }
+            }
+        },
+        "NewJob": {
+            "type": "object",
+            "required": ["description", "function"],
+            "description": "One of execution_time or delay_secs is required",
+            "properties": {
+                "execution_time": { "type": "integer", "description": "Unix seconds" },
+                "delay_secs": { "type": "integer", "description": "Seconds from now" },
+                "priority": { "type": "integer", "minimum": 0, "maximum": 255, "default": 1 },
+                "description": { "type": "string" },
+                "function": { "type": "string" },
+                "max_retries": { "type": "integer", "minimum": 0, "default": 3 },
+                "metadata": metadata,
+                "tags": tags
+            }
+        },
+        "Reschedule": {
+            "type": "object",
+            "description": "One of execution_time or delay_secs is required",
+            "properties": {
+                "execution_time": { "type": "integer", "description": "Unix seconds" },
+                "delay_secs": { "type": "integer", "description": "Seconds from now" }
+            }
+        },
+        "RunRecord": {
+            "type": "object",
+            "required": ["job_id", "description", "function", "attempt", "started_at", "duration_ms", "outcome"],
+            "properties": {
+                "job_id": { "type": "string", "format": "uuid" },
+                "description": { "type": "string" },
+                "function": { "type": "string" },
+                "attempt": { "type": "integer" },
+                "started_at": { "type": "integer", "description": "Unix seconds" },
+                "duration_ms": { "type": "integer" },
+                "outcome": { "type": "string", "enum": ["Success", "Failed"] },
+                "message": { "type": "string", "nullable": true },
+                "output": { "type": "string", "description": "Start of what the task produced, e.g. an HTTP response" }
+            }
+        },
+        "JobEvent": {
+            "type": "object",
+            "required": ["at", "job_id", "description", "function", "type"],
+            "properties": {
+                "at": { "type": "integer", "description": "Unix seconds" },
+                "job_id": { "type": "string", "format": "uuid" },
+                "description": { "type": "string" },
+                "function": { "type": "string" },
+                "type": { "type": "string", "enum": ["added", "dispatched", "started", "succeeded", "failed", "retried", "cancelled"] },
+                "execution_time": { "type": "integer", "description": "added" },
+                "lag_secs": { "type": "integer", "description": "dispatched: seconds past execution_time" },
+                "attempt": { "type": "integer", "description": "started, succeeded, failed, retried" },
+                "duration_ms": { "type": "integer", "description": "succeeded" },
+                "error": { "type": "string", "description": "failed" },
+                "max_retries": { "type": "integer", "description": "retried" }
+            }
+        },
+        "JobDetail": {
+            "type": "object",
+            "properties": {
+                "job": { "$ref": "#/components/schemas/Job" },
+                "runs": { "type": "array", "items": { "$ref": "#/components/schemas/RunRecord" } }
+            }
+        },
+        "Error": {
+            "type": "object",
+            "required": ["error"],
+            "properties": { "error": { "type": "string" } }
+        }
+    })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn single(name: &str) -> Value {
+    json!({ "description": name, "content": { "application/json": { "schema": schema_ref(name) } } })
+}
+
+fn array_of(name: &str) -> Value {
+    json!({
+        "description": format!("List of {}", name),
+        "content": { "application/json": { "schema": { "type": "array", "items": schema_ref(name) } } }
+    })
+}
+
+fn body_of(name: &str) -> Value {
+    json!({ "required": true, "content": { "application/json": { "schema": schema_ref(name) } } })
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::schedule::Schedule;
+
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{info, warn};
@@ -11,8 +13,12 @@ pub enum Status {
     Running,
     Success,
     Failed,
+    Paused,
 }
 
+/// Tag for resource-intensive jobs, held back while the machine is overloaded
+pub const HEAVY_TAG: &str = "heavy";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Job {
     pub id: Uuid,
@@ -25,6 +31,14 @@ pub struct Job {
     pub retry_count: u32,
     #[serde(default)]
     pub metadata: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Name of the job definition file entry this job was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+    /// Recurring schedule (see `schedule::Schedule`); the next run is queued on dispatch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
 }
 
 impl Job {
@@ -35,6 +49,28 @@ impl Job {
             .as_secs() as i64
     }
 
+    /// Parses a user-entered time: small values are seconds from now,
+    /// anything else is an absolute Unix timestamp. A leading `+` is allowed.
+    pub fn parse_execution_time(input: &str) -> Result<i64, String> {
+        let trimmed = input.trim().trim_start_matches('+');
+        match trimmed.parse::<i64>() {
+            Ok(val) if val < 1_000_000_000 => Ok(Self::now() + val),
+            Ok(val) => Ok(val),
+            Err(_) => Err("Invalid time. Enter seconds (e.g. 5) or Unix timestamp.".to_string()),
+        }
+    }
+
+    /// Rejects times in the past and times outside what chrono can represent.
+    pub fn check_execution_time(execution_time: i64) -> Result<(), String> {
+        if execution_time < Self::now() {
+            return Err(format!("execution_time {} is in the past", execution_time));
+        }
+        if chrono::DateTime::from_timestamp(execution_time, 0).is_none() {
+            return Err(format!("execution_time {} is out of range", execution_time));
+        }
+        Ok(())
+    }
+
     pub fn new(
         execution_time: i64,
         priority: u8,
@@ -42,9 +78,7 @@ impl Job {
         function: impl Into<String>,
         max_retries: u32,
     ) -> Result<Job, String> {
-        if execution_time < Self::now() {
-            return Err(format!("execution_time {} is in the past", execution_time));
-        }
+        Self::check_execution_time(execution_time)?;
 
         Ok(Self {
             id: Uuid::new_v4(),
@@ -56,17 +90,59 @@ impl Job {
             max_retries,
             retry_count: 0,
             metadata: None,
+            tags: Vec::new(),
+            definition: None,
+            schedule: None,
+        })
+    }
+
+    /// A fresh pending copy of a recurring job for its next run after `after`.
+    /// None for one-off jobs or when the schedule has no further runs.
+    pub fn next_occurrence(&self, after: i64) -> Option<Job> {
+        let schedule: Schedule = match self.schedule.as_deref()?.parse() {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                warn!(job.id = %self.id, "Not rescheduling: {}", e);
+                return None;
+            }
+        };
+        Some(Job {
+            id: Uuid::new_v4(),
+            execution_time: schedule.next_after(after)?,
+            status: Status::Pending,
+            retry_count: 0,
+            ..self.clone()
         })
     }
 
+    /// A one-off copy to dispatch right away (run-now). It has its own id, so a retry
+    /// of the copy never replaces the queued job and its runs are recorded apart.
+    pub fn run_now_copy(&self) -> Job {
+        Job {
+            id: Uuid::new_v4(),
+            status: Status::Running,
+            retry_count: 0,
+            schedule: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    pub fn is_heavy(&self) -> bool {
+        self.has_tag(HEAVY_TAG)
+    }
+
     pub fn start(&mut self) {
         self.status = Status::Running;
-        info!("Job {} started running.", self.id);
+        info!(job.id = %self.id, "Job started running.");
     }
 
     pub fn complete(&mut self) {
         self.status = Status::Success;
-        info!("Job {} completed successfully.", self.id);
+        info!(job.id = %self.id, "Job completed successfully.");
     }
 
     pub fn fail_and_retry(&mut self) -> bool {
@@ -74,15 +150,18 @@ impl Job {
             self.retry_count += 1;
             self.status = Status::Pending;
             warn!(
-                "Job {} failed. Retrying ({}/{}).",
-                self.id, self.retry_count, self.max_retries
+                job.id = %self.id,
+                retry = self.retry_count,
+                max_retries = self.max_retries,
+                "Job failed. Retrying."
             );
             true
         } else {
             self.status = Status::Failed;
             warn!(
-                "Job {} failed permanently after {} retries.",
-                self.id, self.max_retries
+                job.id = %self.id,
+                max_retries = self.max_retries,
+                "Job failed permanently."
             );
             false
         }
@@ -128,6 +207,15 @@ mod tests {
         assert_eq!(job.status, Status::Success);
     }
 
+    #[test]
+    fn test_parse_execution_time() {
+        let now = Job::now();
+        let offset = Job::parse_execution_time("+30").unwrap();
+        assert!(offset >= now + 30 && offset <= now + 31);
+        assert_eq!(Job::parse_execution_time("2000000000").unwrap(), 2_000_000_000);
+        assert!(Job::parse_execution_time("soon").is_err());
+    }
+
     #[test]
     fn test_job_retries() {
         let mut job = Job::new(Job::now() + 10, 1, "desc", "func", 1).unwrap();
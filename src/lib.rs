@@ -1,4 +1,3 @@
-
 pub mod persistence_manager;
 pub mod engine;
 pub mod job;
@@ -7,3 +6,16 @@ pub mod tui;
 pub mod worker;
 pub mod telemetry;
 pub mod tasks;
+pub mod history;
+pub mod cli;
+pub mod config;
+pub mod control;
+pub mod events;
+pub mod notifications;
+pub mod metrics;
+pub mod monitor;
+pub mod schedule;
+pub mod definitions;
+pub mod email;
+#[cfg(feature = "http")]
+pub mod http;
@@ -1,76 +1,212 @@
+use clap::Parser;
+use scheduler::cli::{self, Cli};
+use scheduler::control::ControlContext;
+use scheduler::definitions;
 use scheduler::engine::TimePriorityEngine;
-use scheduler::job::Job;
+use scheduler::events::EventBus;
+use scheduler::history::{self, HistoryStore};
+use scheduler::metrics::{self, Metrics, MetricsServer};
+use scheduler::monitor::ResourceMonitor;
+use scheduler::notifications;
 use scheduler::persistence_manager::PersistenceManager;
 use scheduler::queue::QueueManager;
 use scheduler::telemetry;
 use scheduler::tui;
 use std::sync::{Arc, Mutex, mpsc};
-use std::thread;
-use scheduler::tasks::{Task, backup_db::BackupDbTask, send_email::SendEmailTask, hotfix::HotfixTask};
+use scheduler::tasks;
+use scheduler::worker::{self, Worker};
 
 fn main() -> std::io::Result<()> {
     // Load .env for RESEND_API_KEY, SMTP_FROM, SMTP_RECIPIENT
     dotenvy::dotenv().ok();
 
-    // Initialize Telemetry
-    let _guard = telemetry::init_telemetry();
+    let cli = Cli::parse();
+    if let Some(command) = &cli.command {
+        if let Err(e) = cli::execute(&cli, command, &mut std::io::stdout()) {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Defaults < scheduler.toml < SCHEDULER_* env vars < command-line flags
+    let config = exit_on_error(cli.load_config());
+
+    // Scheduler events shared by queue, engine, worker and tasks
+    let events = EventBus::new();
+    let log_rx = events.subscribe();
+
+    // Initialize Telemetry; while the TUI runs, logs go to its log panel instead of stdout
+    let tui_events = (!cli.headless).then(|| events.clone());
+    let _guard = exit_on_error(telemetry::init_telemetry(&config.telemetry, tui_events));
+    telemetry::start_event_logger(events.subscribe());
+    match &config.source {
+        Some(path) => tracing::info!("Loaded configuration from {}", path.display()),
+        None => tracing::info!("No configuration file found, using defaults"),
+    }
     tracing::info!("Scheduler Component Initialized!");
-    telemetry::log_resource_usage();
 
-    let persistence = PersistenceManager::new("queue.json");
+    // Task functions, optionally narrowed to worker.functions
+    let mut builtin = Worker::new();
+    tasks::register_builtin(&mut builtin);
+    exit_on_error(config.validate_functions(&builtin.functions()));
+    if let Some(functions) = &config.worker.functions {
+        exit_on_error(builtin.restrict_to(functions));
+    }
+    let available_functions = builtin.functions();
+
+    // Background CPU/memory/load sampling; heavy jobs wait while over the threshold
+    let monitor = Arc::new(ResourceMonitor::new(config.monitor.threshold));
+    monitor.start(config.monitor.sample_interval);
+
+    let persistence = PersistenceManager::new(&config.storage.queue);
     let loaded_jobs = persistence.load_jobs();
 
     let mut q = QueueManager::new();
     q.load_from_vec(loaded_jobs);
     let snapshot_tx = persistence.start_memory_snapshot();
     q.set_persistence(snapshot_tx);
+    q.set_events(events.clone());
 
     let queue = Arc::new(Mutex::new(q));
 
     // Channel from the Time & Priority Engine to the Worker Executor
     let (worker_tx, worker_rx) = mpsc::channel();
-    let (log_tx, log_rx) = mpsc::channel();
 
-    let engine =
-        TimePriorityEngine::new_with_log(Arc::clone(&queue), worker_tx.clone(), log_tx.clone());
+    let mut engine =
+        TimePriorityEngine::new_with_events(Arc::clone(&queue), worker_tx.clone(), events.clone());
+    engine.set_monitor(Arc::clone(&monitor));
+    engine.set_poll_interval(config.poll_interval);
+    let engine = Arc::new(engine);
     engine.start();
 
-    // Start the real Worker in a separate thread
-    thread::spawn(move || {
-        let mut worker = scheduler::worker::Worker::new();
-        // Register actual functions from worker.rs (or inline closures)
-        worker.register("backup_fn", BackupDbTask::run);
-        worker.register("email_fn", SendEmailTask::run);
-        worker.register("hotfix_fn", HotfixTask::run);
+    let history = Arc::new(Mutex::new(HistoryStore::open(&config.storage.history)));
+    history::start_recorder(Arc::clone(&history), events.subscribe());
+    if config.notifications.desktop {
+        notifications::start_desktop_notifier(events.subscribe(), config.notifications);
+    }
 
-        worker.start(worker_rx, log_tx);
+    // Prometheus metrics, served when telemetry.metrics_addr is set
+    let mut job_metrics = Metrics::new();
+    job_metrics.set_queue(Arc::clone(&queue));
+    job_metrics.set_monitor(Arc::clone(&monitor));
+    let job_metrics = Arc::new(job_metrics);
+    metrics::start_collector(Arc::clone(&job_metrics), events.subscribe());
+    let _metrics_server = config.metrics_addr.as_deref().and_then(|addr| {
+        MetricsServer::start(addr, job_metrics)
+            .map_err(|e| tracing::warn!("Metrics endpoint unavailable on {}: {}", addr, e))
+            .ok()
     });
 
-    // Previous code: schedule demo jobs only if queue is empty
-    if queue.lock().unwrap().is_empty() {
-        let now = chrono::Utc::now().timestamp();
-        if let Ok(mut q) = queue.lock() {
-            if let Ok(j1) = Job::new(now + 1, 5, "Backup Database", "backup_fn", 3) {
-                q.push(j1);
+    // Control socket for the CLI and other local tools
+    let control = Arc::new(ControlContext {
+        queue: Arc::clone(&queue),
+        history: Arc::clone(&history),
+        worker_tx: worker_tx.clone(),
+        engine: Some(Arc::clone(&engine)),
+        events: events.clone(),
+        functions: available_functions.clone(),
+    });
+    #[cfg(unix)]
+    let _control_server =
+        match scheduler::control::server::ControlServer::bind(&config.storage.socket, Arc::clone(&control)) {
+            Ok(server) => Some(server),
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                engine.stop();
+                eprintln!("error: {}", e);
+                std::process::exit(1);
             }
-            if let Ok(j2) = Job::new(now + 3, 1, "Send Emails", "email_fn", 1) {
-                q.push(j2);
+            Err(e) => {
+                tracing::warn!("Control socket unavailable: {}", e);
+                None
             }
-            if let Ok(j3) = Job::new(now + 1, 1, "Urgent Hotfix", "hotfix_fn", 3) {
-                q.push(j3);
+        };
+
+    // REST API, enabled by http.addr (requires http.token)
+    #[cfg(feature = "http")]
+    let _http_server = start_http(&config, Arc::clone(&control));
+
+    // Every worker gets the same registry; failed runs go back into the queue to retry
+    let make_worker = {
+        let events = events.clone();
+        let queue = Arc::clone(&queue);
+        let functions = available_functions.clone();
+        let email = config.email.clone();
+        move || {
+            let mut worker = Worker::new();
+            tasks::register_builtin(&mut worker);
+            tasks::register_email(&mut worker, email.clone());
+            // Same names the builtin registry was narrowed to above
+            let _ = worker.restrict_to(&functions);
+            worker.set_events(events.clone());
+            worker.set_queue(Arc::clone(&queue));
+            worker
+        }
+    };
+    let _workers = worker::start_pool(config.worker.pool_size, worker_rx, make_worker);
+
+    // Recurring jobs from the definition files, kept in sync while running
+    let dir = &config.definitions.dir;
+    if config.definitions.watch {
+        definitions::start_watcher(dir.clone(), &queue, available_functions.clone(), definitions::WATCH_INTERVAL);
+    }
+    exit_on_error(definitions::apply_dir(dir, &queue, &available_functions));
+
+    // Seed the configured jobs only if the queue is empty
+    if let Ok(mut q) = queue.lock()
+        && q.is_empty()
+    {
+        for spec in &config.jobs {
+            match spec.to_job() {
+                Ok(job) => q.push(job),
+                Err(e) => tracing::warn!("Skipping job '{}': {}", spec.description, e),
             }
         }
     }
 
+    if cli.headless {
+        drop(log_rx);
+        tracing::info!("Jobs scheduled. Running headless, press Ctrl+C to stop.");
+        let (stop_tx, stop_rx) = mpsc::channel();
+        if let Err(e) = ctrlc::set_handler(move || {
+            let _ = stop_tx.send(());
+        }) {
+            tracing::warn!("Cannot install Ctrl+C handler: {}", e);
+        }
+        let _ = stop_rx.recv();
+        engine.stop();
+        tracing::info!("Scheduler stopped.");
+        return Ok(());
+    }
+
     tracing::info!("Jobs scheduled. Starting TUI...");
-    telemetry::log_resource_usage();
-
-    let result = tui::run_tui(
-        queue,
-        log_rx,
-        worker_tx,
-        vec!["backup_fn".into(), "email_fn".into(), "hotfix_fn".into()],
-    );
+
+    // `param_schemas` maps every enabled task function to the parameters it declares
+    let mut app = tui::AppState::new(queue, log_rx, worker_tx, builtin.param_schemas(), Some(monitor), Some(history));
+    app.set_engine(Arc::clone(&engine));
+    app.set_pool_size(config.worker.pool_size);
+    let result = tui::run_tui(app, &config.tui);
     engine.stop();
     result
 }
+
+fn exit_on_error<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    })
+}
+
+#[cfg(feature = "http")]
+fn start_http(config: &scheduler::config::Config, control: Arc<ControlContext>) -> Option<scheduler::http::HttpServer> {
+    let addr = config.http.addr.as_deref()?;
+    // validate() already rejects an address without a token
+    let token = config.http.token.as_deref()?;
+    match scheduler::http::HttpServer::start(addr, token, control) {
+        Ok(server) => Some(server),
+        Err(e) => {
+            tracing::warn!("HTTP API unavailable on {}: {}", addr, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Prometheus metrics fed from the event bus and served as text at `GET /metrics`.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, Gauge, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::events::{JobEventKind, SchedulerEvent};
+use crate::monitor::ResourceMonitor;
+use crate::queue::QueueManager;
+
+/// Seconds a job waited past its `execution_time` before being dispatched
+const LAG_BUCKETS: &[f64] = &[0.0, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 300.0];
+/// Seconds a task function ran
+const DURATION_BUCKETS: &[f64] = &[0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 15.0, 60.0, 300.0];
+/// How long a client may take to send its request before the connection is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Metrics {
+    registry: Registry,
+    enqueued: IntCounterVec,
+    dispatched: IntCounterVec,
+    succeeded: IntCounterVec,
+    failed: IntCounterVec,
+    retried: IntCounterVec,
+    dispatch_lag: HistogramVec,
+    run_duration: HistogramVec,
+    queue_depth: IntGauge,
+    process_cpu: Gauge,
+    process_rss: Gauge,
+    system_cpu: Gauge,
+    system_memory: Gauge,
+    load_average: Gauge,
+    queue: Option<Arc<Mutex<QueueManager>>>,
+    monitor: Option<Arc<ResourceMonitor>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let per_function = |name: &str, help: &str| {
+            let counter = IntCounterVec::new(Opts::new(name, help), &["function"])
+                .expect("valid counter definition");
+            registry
+                .register(Box::new(counter.clone()))
+                .expect("metric names are unique");
+            counter
+        };
+        let enqueued = per_function("scheduler_jobs_enqueued_total", "Jobs added to the queue");
+        let dispatched = per_function("scheduler_jobs_dispatched_total", "Jobs handed to the worker");
+        let succeeded = per_function("scheduler_jobs_succeeded_total", "Job runs that succeeded");
+        let failed = per_function("scheduler_jobs_failed_total", "Job runs that failed");
+        let retried = per_function("scheduler_jobs_retried_total", "Failed jobs scheduled for a retry");
+
+        let histogram = |name: &str, help: &str, buckets: &[f64]| {
+            let opts = HistogramOpts::new(name, help).buckets(buckets.to_vec());
+            let histogram =
+                HistogramVec::new(opts, &["function"]).expect("valid histogram definition");
+            registry
+                .register(Box::new(histogram.clone()))
+                .expect("metric names are unique");
+            histogram
+        };
+        let dispatch_lag = histogram(
+            "scheduler_dispatch_lag_seconds",
+            "Delay between a job's execution_time and its dispatch",
+            LAG_BUCKETS,
+        );
+        let run_duration = histogram(
+            "scheduler_job_duration_seconds",
+            "Time spent running a job's task function",
+            DURATION_BUCKETS,
+        );
+
+        let queue_depth = IntGauge::new("scheduler_queue_depth", "Jobs currently in the queue")
+            .expect("valid gauge definition");
+        registry
+            .register(Box::new(queue_depth.clone()))
+            .expect("metric names are unique");
+        let gauge = |name: &str, help: &str| {
+            let gauge = Gauge::new(name, help).expect("valid gauge definition");
+            registry
+                .register(Box::new(gauge.clone()))
+                .expect("metric names are unique");
+            gauge
+        };
+        let process_cpu = gauge("scheduler_process_cpu_usage_percent", "CPU used by the scheduler process");
+        let process_rss = gauge("scheduler_process_resident_memory_bytes", "Resident memory of the scheduler process");
+        let system_cpu = gauge("scheduler_system_cpu_usage_percent", "Global CPU usage");
+        let system_memory = gauge("scheduler_system_memory_used_bytes", "Used system memory");
+        let load_average = gauge("scheduler_system_load_average", "One-minute system load average");
+
+        Self {
+            registry,
+            enqueued,
+            dispatched,
+            succeeded,
+            failed,
+            retried,
+            dispatch_lag,
+            run_duration,
+            queue_depth,
+            process_cpu,
+            process_rss,
+            system_cpu,
+            system_memory,
+            load_average,
+            queue: None,
+            monitor: None,
+        }
+    }
+
+    /// Report the depth of this queue on every scrape
+    pub fn set_queue(&mut self, queue: Arc<Mutex<QueueManager>>) {
+        self.queue = Some(queue);
+    }
+
+    /// Report the monitor's latest resource sample on every scrape
+    pub fn set_monitor(&mut self, monitor: Arc<ResourceMonitor>) {
+        self.monitor = Some(monitor);
+    }
+
+    /// Updates counters and histograms for one scheduler event.
+    pub fn observe(&self, event: &SchedulerEvent) {
+        let SchedulerEvent::Job(e) = event else {
+            return;
+        };
+        let function = [e.function.as_str()];
+        match &e.kind {
+            JobEventKind::Added { .. } => self.enqueued.with_label_values(&function).inc(),
+            JobEventKind::Dispatched { lag_secs } => {
+                self.dispatched.with_label_values(&function).inc();
+                self.dispatch_lag
+                    .with_label_values(&function)
+                    .observe((*lag_secs).max(0) as f64);
+            }
+            JobEventKind::Succeeded { duration_ms, .. } => {
+                self.succeeded.with_label_values(&function).inc();
+                self.observe_duration(&function, *duration_ms);
+            }
+            JobEventKind::Failed { duration_ms, .. } => {
+                self.failed.with_label_values(&function).inc();
+                self.observe_duration(&function, *duration_ms);
+            }
+            JobEventKind::Retried { .. } => self.retried.with_label_values(&function).inc(),
+            JobEventKind::Started { .. } | JobEventKind::Cancelled => {}
+        }
+    }
+
+    fn observe_duration(&self, function: &[&str; 1], duration_ms: u64) {
+        self.run_duration
+            .with_label_values(function)
+            .observe(duration_ms as f64 / 1000.0);
+    }
+
+    /// Current values in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        if let Some(queue) = self.queue.as_ref()
+            && let Ok(q) = queue.lock()
+        {
+            self.queue_depth.set(q.len() as i64);
+        }
+        if let Some(sample) = self.monitor.as_ref().and_then(|m| m.latest()) {
+            self.process_cpu.set(sample.process_cpu_percent as f64);
+            self.process_rss.set(sample.process_rss_bytes as f64);
+            self.system_cpu.set(sample.system_cpu_percent as f64);
+            self.system_memory.set(sample.system_memory_used_bytes as f64);
+            self.load_average.set(sample.load_average);
+        }
+
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// Feeds every event from the bus into the metrics.
+pub fn start_collector(metrics: Arc<Metrics>, events: Receiver<SchedulerEvent>) {
+    thread::spawn(move || {
+        for event in events {
+            metrics.observe(&event);
+        }
+    });
+}
+
+/// Serves `GET /metrics` on a background thread; stops when dropped.
+pub struct MetricsServer {
+    addr: SocketAddr,
+    stopped: Arc<AtomicBool>,
+}
+
+impl MetricsServer {
+    pub fn start(addr: &str, metrics: Arc<Metrics>) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let stop = Arc::clone(&stopped);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                match stream {
+                    // One thread per connection, so a slow client doesn't block other scrapes
+                    Ok(stream) => {
+                        let metrics = Arc::clone(&metrics);
+                        thread::spawn(move || {
+                            if let Err(e) = respond(stream, &metrics) {
+                                tracing::debug!("[Metrics] Scrape failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => tracing::warn!("[Metrics] Accept failed: {}", e),
+                }
+            }
+        });
+
+        tracing::info!("[Metrics] Serving http://{}/metrics", addr);
+        Ok(Self { addr, stopped })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn respond(stream: TcpStream, metrics: &Metrics) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers; scrapes have no body
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let (status, content_type, body) = if request_line.starts_with("GET ")
+        && (path == "/metrics" || path.starts_with("/metrics?"))
+    {
+        ("200 OK", "text/plain; version=0.0.4", metrics.render())
+    } else {
+        ("404 Not Found", "text/plain", "Not found.\n".to_string())
+    };
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
@@ -0,0 +1,169 @@
+//! Background sampling of the scheduler's own CPU/memory use and of system
+//! load, used by the TUI sparkline, the metrics endpoint and the engine to
+//! hold back heavy jobs while the machine is busy.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::job::Job;
+
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+/// Samples kept for the sparkline (4 minutes at the default interval)
+pub const SAMPLE_HISTORY: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ResourceSample {
+    /// Unix seconds
+    pub at: i64,
+    /// Can exceed 100 on multi-core machines
+    pub process_cpu_percent: f32,
+    pub process_rss_bytes: u64,
+    pub system_cpu_percent: f32,
+    pub system_memory_used_bytes: u64,
+    /// One-minute load average (0 where the platform has none)
+    pub load_average: f64,
+}
+
+/// Above either limit, jobs tagged `heavy` stay queued.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoadThreshold {
+    pub max_system_cpu_percent: Option<f32>,
+    pub max_load_average: Option<f64>,
+}
+
+impl LoadThreshold {
+    /// Reads `SCHEDULER_HEAVY_MAX_CPU` (percent) and `SCHEDULER_HEAVY_MAX_LOAD`.
+    pub fn from_env() -> Result<Self, String> {
+        let mut threshold = Self::default();
+        threshold.apply_env(|key| std::env::var(key).ok())?;
+        Ok(threshold)
+    }
+
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+        if let Some(cpu) = var("SCHEDULER_HEAVY_MAX_CPU") {
+            self.max_system_cpu_percent = Some(
+                cpu.parse()
+                    .map_err(|_| format!("SCHEDULER_HEAVY_MAX_CPU must be a percentage, got '{}'.", cpu))?,
+            );
+        }
+        if let Some(load) = var("SCHEDULER_HEAVY_MAX_LOAD") {
+            self.max_load_average = Some(
+                load.parse()
+                    .map_err(|_| format!("SCHEDULER_HEAVY_MAX_LOAD must be a number, got '{}'.", load))?,
+            );
+        }
+        Ok(())
+    }
+
+    pub fn exceeded_by(&self, sample: &ResourceSample) -> bool {
+        self.max_system_cpu_percent
+            .is_some_and(|max| sample.system_cpu_percent > max)
+            || self.max_load_average.is_some_and(|max| sample.load_average > max)
+    }
+}
+
+pub struct ResourceMonitor {
+    samples: Mutex<VecDeque<ResourceSample>>,
+    threshold: LoadThreshold,
+}
+
+impl ResourceMonitor {
+    pub fn new(threshold: LoadThreshold) -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::with_capacity(SAMPLE_HISTORY)),
+            threshold,
+        }
+    }
+
+    /// Samples every `interval` on a background thread until the monitor is dropped.
+    pub fn start(self: &Arc<Self>, interval: Duration) {
+        let monitor = Arc::downgrade(self);
+        thread::spawn(move || {
+            let pid = sysinfo::get_current_pid().ok();
+            let mut sys = System::new();
+            // CPU usage is measured between two refreshes
+            refresh(&mut sys, pid);
+            loop {
+                thread::sleep(interval);
+                let Some(monitor) = monitor.upgrade() else {
+                    break;
+                };
+                refresh(&mut sys, pid);
+                let sample = sample(&sys, pid);
+                tracing::debug!(
+                    process_cpu = sample.process_cpu_percent,
+                    process_rss = sample.process_rss_bytes,
+                    system_cpu = sample.system_cpu_percent,
+                    load = sample.load_average,
+                    "Resource sample"
+                );
+                monitor.record(sample);
+            }
+        });
+    }
+
+    pub fn record(&self, sample: ResourceSample) {
+        if let Ok(mut samples) = self.samples.lock() {
+            if samples.len() == SAMPLE_HISTORY {
+                samples.pop_front();
+            }
+            samples.push_back(sample);
+        }
+    }
+
+    pub fn latest(&self) -> Option<ResourceSample> {
+        self.samples.lock().ok().and_then(|s| s.back().copied())
+    }
+
+    /// Oldest first
+    pub fn samples(&self) -> Vec<ResourceSample> {
+        self.samples
+            .lock()
+            .map(|s| s.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn threshold(&self) -> LoadThreshold {
+        self.threshold
+    }
+
+    /// Whether the latest sample is over the configured threshold
+    pub fn overloaded(&self) -> bool {
+        self.latest()
+            .is_some_and(|sample| self.threshold.exceeded_by(&sample))
+    }
+
+    /// Whether the engine should keep this job queued for now
+    pub fn should_hold(&self, job: &Job) -> bool {
+        job.is_heavy() && self.overloaded()
+    }
+}
+
+fn refresh(sys: &mut System, pid: Option<Pid>) {
+    sys.refresh_cpu_usage();
+    sys.refresh_memory();
+    if let Some(pid) = pid {
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+    }
+}
+
+fn sample(sys: &System, pid: Option<Pid>) -> ResourceSample {
+    let process = pid.and_then(|pid| sys.process(pid));
+    ResourceSample {
+        at: Job::now(),
+        process_cpu_percent: process.map(|p| p.cpu_usage()).unwrap_or(0.0),
+        process_rss_bytes: process.map(|p| p.memory()).unwrap_or(0),
+        system_cpu_percent: sys.global_cpu_usage(),
+        system_memory_used_bytes: sys.used_memory(),
+        load_average: System::load_average().one,
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::mpsc::Receiver;
+use std::thread;
+
+use notify_rust::Notification;
+
+use crate::config::NotificationsConfig;
+use crate::events::{JobEventKind, SchedulerEvent};
+
+/// Shows a desktop notification whenever a job finishes or fails, as enabled in `config`.
+pub fn start_desktop_notifier(events: Receiver<SchedulerEvent>, config: NotificationsConfig) {
+    thread::spawn(move || {
+        for event in events {
+            let SchedulerEvent::Job(e) = event else {
+                continue;
+            };
+            let body = match e.kind {
+                JobEventKind::Succeeded { .. } if config.on_success => {
+                    format!("Job '{}' completed successfully.", e.description)
+                }
+                JobEventKind::Retried { attempt, max_retries } if config.on_failure => format!(
+                    "Job '{}' failed. Will retry ({}/{})",
+                    e.description, attempt, max_retries
+                ),
+                JobEventKind::Failed { will_retry: false, .. } if config.on_failure => {
+                    format!("Job '{}' failed permanently.", e.description)
+                }
+                _ => continue,
+            };
+            let _ = Notification::new().summary("Task Scheduler").body(&body).show();
+        }
+    });
+}
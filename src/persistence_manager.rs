@@ -1,6 +1,6 @@
 use std::{
-    fs,
-    path::PathBuf,
+    fs, io,
+    path::{Path, PathBuf},
     sync::mpsc::{self, Sender},
     thread,
 };
@@ -19,24 +19,14 @@ impl PersistenceManager {
     }
 
     pub fn start_memory_snapshot(&self) -> Sender<Vec<Job>> {
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = mpsc::channel::<Vec<Job>>();
         let path = self.storage_path.clone();
 
         thread::spawn(move || {
-            println!("System will snapshot the indexed jobs...");
+            tracing::info!("System will snapshot the indexed jobs...");
             for jobs_snapshot in rx {
-                match serde_json::to_string_pretty(&jobs_snapshot) {
-                    Ok(json) => {
-                        let temp_path = path.with_extension("tmp");
-                        if let Err(e) = fs::write(&temp_path, &json) {
-                            eprintln!("Error: Failed to write to temp disk: {}", e);
-                            continue;
-                        }
-                        if let Err(e) = fs::rename(&temp_path, &path) {
-                            eprintln!("Error: Failed to swap persistent file: {}", e);
-                        }
-                    }
-                    Err(e) => eprintln!("Failed to serialize job: {}", e),
+                if let Err(e) = write_snapshot(&path, &jobs_snapshot) {
+                    tracing::error!("Failed to persist snapshot: {}", e);
                 }
             }
         });
@@ -53,7 +43,20 @@ impl PersistenceManager {
             return Vec::new();
         };
 
-        println!("Successfully loaded {} jobs from disk", jobs.len());
+        tracing::info!("Successfully loaded {} jobs from disk", jobs.len());
         jobs
     }
+
+    /// Writes the jobs to disk synchronously (used by one-shot CLI commands).
+    pub fn save_jobs(&self, jobs: &[Job]) -> io::Result<()> {
+        write_snapshot(&self.storage_path, jobs)
+    }
+}
+
+/// Serializes to a temp file and swaps it in so readers never see a partial file.
+fn write_snapshot(path: &Path, jobs: &[Job]) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(jobs).map_err(io::Error::other)?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, &json)?;
+    fs::rename(&temp_path, path)
 }
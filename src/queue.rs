@@ -1,3 +1,4 @@
+use crate::events::{EventBus, JobEvent, JobEventKind};
 use crate::job::{Job, Status};
 use priority_queue::PriorityQueue;
 use std::cmp::Reverse;
@@ -11,6 +12,7 @@ pub struct QueueManager {
     heap: PriorityQueue<Uuid, JobPriority>,
     jobs: HashMap<Uuid, Job>,
     snapshot_tx: Option<std::sync::mpsc::Sender<Vec<Job>>>,
+    events: Option<EventBus>,
 }
 
 #[allow(dead_code)]
@@ -20,6 +22,7 @@ impl QueueManager {
             heap: PriorityQueue::new(),
             jobs: HashMap::new(),
             snapshot_tx: None,
+            events: None,
         }
     }
 
@@ -27,12 +30,26 @@ impl QueueManager {
         self.snapshot_tx = Some(tx);
     }
 
+    /// Publish `Added` / `Cancelled` events for jobs pushed and removed from now on
+    pub fn set_events(&mut self, events: EventBus) {
+        self.events = Some(events);
+    }
+
+    fn publish(&self, job: &Job, kind: JobEventKind) {
+        if let Some(events) = self.events.as_ref() {
+            events.publish(JobEvent::new(job, kind));
+        }
+    }
+
     pub fn load_from_vec(&mut self, jobs: Vec<Job>) {
         for job in jobs {
             let priority = (Reverse(job.execution_time), job.priority);
             let id = job.id;
+            // Paused jobs are tracked but kept out of the heap until resumed
+            if job.status != Status::Paused {
+                self.heap.push(id, priority);
+            }
             self.jobs.insert(id, job);
-            self.heap.push(id, priority);
         }
 
         self.notify_persistence();
@@ -48,6 +65,12 @@ impl QueueManager {
     pub fn push(&mut self, job: Job) {
         let priority = (Reverse(job.execution_time), job.priority);
         let id = job.id;
+        self.publish(
+            &job,
+            JobEventKind::Added {
+                execution_time: job.execution_time,
+            },
+        );
         self.jobs.insert(id, job);
         self.heap.push(id, priority);
         self.notify_persistence();
@@ -62,33 +85,104 @@ impl QueueManager {
     }
 
     pub fn remove(&mut self, id: Uuid) -> Option<Job> {
-        match self.heap.remove(&id) {
-            Some(_) => {
-                let removed = self.jobs.remove(&id);
+        self.heap.remove(&id);
+        let removed = self.jobs.remove(&id);
+        if let Some(job) = removed.as_ref() {
+            self.publish(job, JobEventKind::Cancelled);
+            self.notify_persistence();
+        }
+        removed
+    }
+
+    /// Takes a job out of the dispatch heap without forgetting it.
+    /// Returns false if the job is unknown or already paused.
+    pub fn pause(&mut self, id: Uuid) -> bool {
+        match self.jobs.get_mut(&id) {
+            Some(job) if job.status != Status::Paused => {
+                job.status = Status::Paused;
+                self.heap.remove(&id);
+                self.notify_persistence();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Puts a paused job back into the dispatch heap at its original schedule.
+    pub fn resume(&mut self, id: Uuid) -> bool {
+        match self.jobs.get_mut(&id) {
+            Some(job) if job.status == Status::Paused => {
+                job.status = Status::Pending;
+                let priority = (Reverse(job.execution_time), job.priority);
+                self.heap.push(id, priority);
                 self.notify_persistence();
-                removed
+                true
             }
-            None => None,
+            _ => false,
         }
     }
 
+    /// Moves a job to a new execution time, re-keying it in the heap.
+    pub fn reschedule(&mut self, id: Uuid, execution_time: i64) -> bool {
+        let Some(job) = self.jobs.get_mut(&id) else {
+            return false;
+        };
+        job.execution_time = execution_time;
+        let priority = (Reverse(job.execution_time), job.priority);
+        // Paused jobs are not in the heap, change_priority is a no-op for them
+        self.heap.change_priority(&id, priority);
+        self.notify_persistence();
+        true
+    }
+
+    /// Replaces a queued job with an edited copy (same id), re-keying it in the heap.
+    pub fn update(&mut self, job: Job) -> bool {
+        let id = job.id;
+        let Some(existing) = self.jobs.get_mut(&id) else {
+            return false;
+        };
+        let priority = (Reverse(job.execution_time), job.priority);
+        *existing = job;
+        // Paused jobs are not in the heap, change_priority is a no-op for them
+        self.heap.change_priority(&id, priority);
+        self.notify_persistence();
+        true
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&Job> {
+        self.jobs.get(&id)
+    }
+
     pub fn peek(&self) -> Option<&Job> {
         self.heap.peek().and_then(|(id, _)| self.jobs.get(id))
     }
 
     pub fn pop_ready(&mut self, now: i64) -> Vec<Job> {
+        self.pop_ready_where(now, |_| false)
+    }
+
+    /// Like `pop_ready`, but due jobs for which `hold` returns true stay queued.
+    pub fn pop_ready_where(&mut self, now: i64, mut hold: impl FnMut(&Job) -> bool) -> Vec<Job> {
         let mut ready = Vec::new();
+        let mut held = Vec::new();
         while let Some((id, _)) = self.heap.peek() {
             // We use the peeked ID to check the actual job time in the HashMap
             if let Some(job) = self.jobs.get(id) {
                 if job.execution_time <= now {
-                    let (removed_id, _) = self.heap.pop().unwrap();
-                    ready.push(self.jobs.remove(&removed_id).unwrap());
+                    let (removed_id, priority) = self.heap.pop().unwrap();
+                    if hold(&self.jobs[&removed_id]) {
+                        held.push((removed_id, priority));
+                    } else {
+                        ready.push(self.jobs.remove(&removed_id).unwrap());
+                    }
                 } else {
                     break;
                 }
             }
         }
+        for (id, priority) in held {
+            self.heap.push(id, priority);
+        }
 
         if !ready.is_empty() {
             self.notify_persistence();
@@ -106,12 +200,13 @@ impl QueueManager {
         }
     }
 
+    /// Number of queued jobs, including paused ones
     pub fn len(&self) -> usize {
-        self.heap.len()
+        self.jobs.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.heap.is_empty()
+        self.jobs.is_empty()
     }
 
     /// Returns all jobs sorted by execution time (for display or persistence)
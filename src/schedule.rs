@@ -0,0 +1,75 @@
+//! Recurring schedules for jobs: fixed intervals (`every 15m`) or cron
+//! expressions (`0 2 * * *`, `@daily`).
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{TimeZone, Utc};
+
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// Runs again this many seconds after the previous run was dispatched
+    Every(i64),
+    /// Standard 5-field cron (minute precision) or 6/7 fields with seconds, in UTC
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    /// The first run strictly after `after` (Unix seconds)
+    pub fn next_after(&self, after: i64) -> Option<i64> {
+        match self {
+            Schedule::Every(secs) => after.checked_add(*secs),
+            Schedule::Cron(schedule) => {
+                let after = Utc.timestamp_opt(after, 0).single()?;
+                schedule.after(&after).next().map(|t| t.timestamp())
+            }
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        if let Some(interval) = input.strip_prefix("every ") {
+            return parse_interval(interval.trim()).map(Schedule::Every);
+        }
+        // The cron crate wants a seconds field; accept the usual 5-field form too
+        let expression = if input.split_whitespace().count() == 5 {
+            format!("0 {}", input)
+        } else {
+            input.to_string()
+        };
+        cron::Schedule::from_str(&expression)
+            .map(|s| Schedule::Cron(Box::new(s)))
+            .map_err(|e| format!("invalid schedule '{}': {}", input, e))
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Every(secs) => write!(f, "every {}s", secs),
+            Schedule::Cron(schedule) => write!(f, "{}", schedule),
+        }
+    }
+}
+
+/// `30s`, `15m`, `2h`, `1d`; a bare number is seconds.
+fn parse_interval(input: &str) -> Result<i64, String> {
+    let (digits, unit) = input.split_at(input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len()));
+    let multiplier = match unit.trim() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        other => return Err(format!("unknown interval unit '{}' (use s, m, h or d)", other)),
+    };
+    match digits.parse::<i64>() {
+        Ok(n) if n > 0 => n
+            .checked_mul(multiplier)
+            .ok_or_else(|| format!("interval '{}' is too long", input)),
+        _ => Err(format!("invalid interval '{}', expected e.g. 'every 15m'", input)),
+    }
+}
@@ -1,11 +1,365 @@
-use std::sync::mpsc::Sender;
-use super::Task;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Instant;
 
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use super::{ParamSpec, Task, TaskContext, TaskResult};
+
+/// Backs up a database to a timestamped, gzip-compressed file, verifies it and
+/// prunes old backups. Settings come from the job payload, falling back to `.env`:
+///
+/// - `BACKUP_SOURCE`: SQLite file, or database name / connection string (required)
+/// - `BACKUP_KIND`: `sqlite` (default), `postgres` or `mysql`
+/// - `BACKUP_DIR`: destination directory (default `backups`)
+/// - `BACKUP_NAME`: file name prefix (default derived from the source)
+/// - `BACKUP_RETENTION`: backups of this name to keep, 0 keeps all (default 7)
+/// - `BACKUP_DUMP_COMMAND`: `sqlite3` / `pg_dump` / `mysqldump` binary to use
 pub struct BackupDbTask;
 
+pub const DEFAULT_BACKUP_DIR: &str = "backups";
+pub const DEFAULT_RETENTION: usize = 7;
+
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+/// Timestamp in backup file names; sorts lexically in time order
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
 impl Task for BackupDbTask {
-    fn run(log_tx: Sender<String>) {
-        let _ = log_tx.send("🗄️ [Task] Backing up database...".to_string());
-        // Logic for DB backup here
+    fn run(ctx: &TaskContext) -> TaskResult {
+        let params = BackupParams::from_ctx(ctx)?;
+        let started = Instant::now();
+        ctx.log(format!("🗄️ Backing up {} database '{}'...", params.kind, params.source));
+
+        fs::create_dir_all(&params.dir)
+            .map_err(|e| format!("Cannot create {}: {}", params.dir.display(), e))?;
+        let stamp = chrono::Utc::now().format(STAMP_FORMAT).to_string();
+        let (path, partial) = backup_paths(&params, &stamp);
+
+        let written = dump(ctx, &params, &partial)
+            .and_then(|()| verify(params.kind, &partial))
+            .and_then(|()| fs::rename(&partial, &path).map_err(|e| format!("Cannot finish {}: {}", path.display(), e)));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let pruned = prune(&params.dir, &params.name, params.kind.extension(), params.retention)?;
+        let summary = format!(
+            "{} ({}) in {} ms, verified; {} old backup(s) removed",
+            path.display(),
+            human_size(size),
+            started.elapsed().as_millis(),
+            pruned
+        );
+        ctx.log(format!("✅ Backup written: {}", summary));
+        ctx.set_output(summary);
+        Ok(())
+    }
+
+    fn params() -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::text("BACKUP_SOURCE", "Source").help("SQLite file or database; defaults to .env"),
+            ParamSpec::choice("BACKUP_KIND", "Kind", &["sqlite", "postgres", "mysql"]).default("sqlite"),
+            ParamSpec::text("BACKUP_DIR", "Destination").default(DEFAULT_BACKUP_DIR),
+            ParamSpec::text("BACKUP_NAME", "File name prefix"),
+            ParamSpec::integer("BACKUP_RETENTION", "Backups to keep").default(&DEFAULT_RETENTION.to_string()).help("0 keeps all"),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DbKind {
+    Sqlite,
+    Postgres,
+    Mysql,
+}
+
+impl DbKind {
+    fn parse(input: &str) -> Result<Self, String> {
+        match input.trim().to_lowercase().as_str() {
+            "sqlite" | "sqlite3" => Ok(DbKind::Sqlite),
+            "postgres" | "postgresql" | "pg" => Ok(DbKind::Postgres),
+            "mysql" | "mariadb" => Ok(DbKind::Mysql),
+            other => Err(format!("BACKUP_KIND must be sqlite, postgres or mysql, got '{}'", other)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            DbKind::Sqlite => "db",
+            DbKind::Postgres | DbKind::Mysql => "sql",
+        }
+    }
+
+    fn default_command(self) -> &'static str {
+        match self {
+            DbKind::Sqlite => "sqlite3",
+            DbKind::Postgres => "pg_dump",
+            DbKind::Mysql => "mysqldump",
+        }
+    }
+}
+
+impl fmt::Display for DbKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DbKind::Sqlite => "SQLite",
+            DbKind::Postgres => "PostgreSQL",
+            DbKind::Mysql => "MySQL",
+        })
+    }
+}
+
+struct BackupParams {
+    kind: DbKind,
+    source: String,
+    dir: PathBuf,
+    name: String,
+    retention: usize,
+    command: String,
+}
+
+impl BackupParams {
+    fn from_ctx(ctx: &TaskContext) -> Result<Self, String> {
+        let kind = match ctx.var("BACKUP_KIND") {
+            Some(kind) => DbKind::parse(&kind)?,
+            None => DbKind::Sqlite,
+        };
+        let source = ctx
+            .var("BACKUP_SOURCE")
+            .filter(|s| !s.trim().is_empty())
+            .ok_or("BACKUP_SOURCE is not set (the SQLite file or database to back up)")?;
+        let retention = match ctx.var("BACKUP_RETENTION") {
+            Some(n) => n
+                .trim()
+                .parse()
+                .map_err(|_| format!("BACKUP_RETENTION must be a number, got '{}'", n))?,
+            None => DEFAULT_RETENTION,
+        };
+        let name = ctx
+            .var("BACKUP_NAME")
+            .unwrap_or_else(|| default_name(kind, &source));
+        Ok(Self {
+            kind,
+            dir: PathBuf::from(ctx.var("BACKUP_DIR").unwrap_or_else(|| DEFAULT_BACKUP_DIR.to_string())),
+            name: sanitize(&name),
+            retention,
+            command: ctx
+                .var("BACKUP_DUMP_COMMAND")
+                .unwrap_or_else(|| kind.default_command().to_string()),
+            source,
+        })
+    }
+}
+
+/// File stem of a SQLite path, or the database name at the end of a connection string
+fn default_name(kind: DbKind, source: &str) -> String {
+    match kind {
+        DbKind::Sqlite => Path::new(source)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "database".to_string()),
+        DbKind::Postgres | DbKind::Mysql => {
+            let without_query = source.split('?').next().unwrap_or(source);
+            without_query.rsplit('/').next().unwrap_or(without_query).to_string()
+        }
+    }
+}
+
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if name.is_empty() { "database".to_string() } else { name }
+}
+
+/// Writes the compressed dump to `dest`.
+fn dump(ctx: &TaskContext, params: &BackupParams, dest: &Path) -> TaskResult {
+    match params.kind {
+        DbKind::Sqlite => dump_sqlite(ctx, params, dest),
+        DbKind::Postgres => dump_command(&params.command, &[format!("--dbname={}", params.source)], dest),
+        DbKind::Mysql => dump_command(
+            &params.command,
+            &["--single-transaction".to_string(), params.source.clone()],
+            dest,
+        ),
+    }
+}
+
+/// Snapshots the database with `VACUUM INTO` so concurrent writers cannot leave it
+/// half-copied. Without the `sqlite3` tool the file is copied as is.
+fn dump_sqlite(ctx: &TaskContext, params: &BackupParams, dest: &Path) -> TaskResult {
+    let source = Path::new(&params.source);
+    check_sqlite_header(&mut File::open(source).map_err(|e| format!("Cannot open {}: {}", source.display(), e))?)
+        .map_err(|e| format!("{} is not a SQLite database: {}", source.display(), e))?;
+
+    let snapshot = dest.with_extension("snapshot");
+    let sql = format!("VACUUM INTO '{}'", snapshot.display().to_string().replace('\'', "''"));
+    let copy_from = match Command::new(&params.command).arg(source).arg(&sql).output() {
+        Ok(output) if output.status.success() => snapshot.as_path(),
+        Ok(output) => {
+            let _ = fs::remove_file(&snapshot);
+            return Err(format!(
+                "{} failed: {}",
+                params.command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            ctx.warn(format!("'{}' not found, copying the database file directly", params.command));
+            source
+        }
+        Err(e) => return Err(format!("Cannot run {}: {}", params.command, e)),
+    };
+
+    let result = File::open(copy_from)
+        .and_then(|mut input| compress(&mut input, dest))
+        .map_err(|e| format!("Cannot write {}: {}", dest.display(), e));
+    let _ = fs::remove_file(&snapshot);
+    result
+}
+
+/// Streams a dump tool's stdout into `dest`.
+fn dump_command(command: &str, args: &[String], dest: &Path) -> TaskResult {
+    let mut child = Command::new(command)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => format!("'{}' not found; install it or set BACKUP_DUMP_COMMAND", command),
+            _ => format!("Cannot run {}: {}", command, e),
+        })?;
+    let mut stdout = child.stdout.take().ok_or("dump output not captured")?;
+    let mut stderr = child.stderr.take().ok_or("dump errors not captured")?;
+
+    // Read stderr alongside so a chatty tool cannot block on a full pipe
+    let (written, errors) = thread::scope(|scope| {
+        let errors = scope.spawn(move || {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text);
+            text
+        });
+        let written = compress(&mut stdout, dest);
+        (written, errors.join().unwrap_or_default())
+    });
+    let status = child.wait().map_err(|e| format!("Cannot wait for {}: {}", command, e))?;
+    if !status.success() {
+        let reason = errors.lines().last().unwrap_or("no error output");
+        return Err(format!("{} failed ({}): {}", command, status, reason));
+    }
+    written.map_err(|e| format!("Cannot write {}: {}", dest.display(), e))
+}
+
+fn compress(input: &mut impl Read, dest: &Path) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(dest)?), Compression::default());
+    io::copy(input, &mut encoder)?;
+    encoder.finish()?.flush()
+}
+
+/// Decompresses the whole backup (checking the gzip CRC) and checks its content.
+fn verify(kind: DbKind, path: &Path) -> TaskResult {
+    let fail = |e: String| format!("Backup verification failed for {}: {}", path.display(), e);
+    let file = File::open(path).map_err(|e| fail(e.to_string()))?;
+    let mut decoder = GzDecoder::new(file);
+    if kind == DbKind::Sqlite {
+        check_sqlite_header(&mut decoder).map_err(fail)?;
+    }
+    let size = io::copy(&mut decoder, &mut io::sink()).map_err(|e| fail(e.to_string()))?;
+    if kind != DbKind::Sqlite && size == 0 {
+        return Err(fail("the dump is empty".to_string()));
+    }
+    Ok(())
+}
+
+fn check_sqlite_header(input: &mut impl Read) -> Result<(), String> {
+    let mut header = [0; 16];
+    input.read_exact(&mut header).map_err(|e| e.to_string())?;
+    if &header == SQLITE_HEADER {
+        Ok(())
+    } else {
+        Err("missing SQLite header".to_string())
+    }
+}
+
+/// The backup file and the partial file it is written to first. A second backup in
+/// the same second gets a `-1`, `-2`, ... suffix after the timestamp.
+fn backup_paths(params: &BackupParams, stamp: &str) -> (PathBuf, PathBuf) {
+    let mut seq = 0;
+    loop {
+        let suffix = if seq == 0 { String::new() } else { format!("-{}", seq) };
+        let file_name = format!("{}-{}{}.{}.gz", params.name, stamp, suffix, params.kind.extension());
+        let path = params.dir.join(&file_name);
+        let partial = params.dir.join(format!("{}.partial", file_name));
+        if !path.exists() && !partial.exists() {
+            return (path, partial);
+        }
+        seq += 1;
+    }
+}
+
+/// Timestamp and same-second suffix of a backup written by this task as
+/// `{name}-{stamp}[-{seq}].{extension}.gz`; `None` for any other file, including
+/// backups of another name that starts with this one (`app-logs-...` for `app`).
+fn backup_key(file_name: &str, name: &str, extension: &str) -> Option<(String, u32)> {
+    let rest = file_name
+        .strip_prefix(name)?
+        .strip_prefix('-')?
+        .strip_suffix(".gz")?
+        .strip_suffix(extension)?
+        .strip_suffix('.')?;
+    let (stamp, seq) = match rest.split_once('-') {
+        Some((stamp, seq)) if !seq.is_empty() && seq.bytes().all(|b| b.is_ascii_digit()) => (stamp, seq.parse().ok()?),
+        Some(_) => return None,
+        None => (rest, 0),
+    };
+    // Formatting again rules out anything chrono would parse loosely
+    let parsed = chrono::NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).ok()?;
+    (parsed.format(STAMP_FORMAT).to_string() == stamp).then(|| (stamp.to_string(), seq))
+}
+
+/// Deletes all but the newest `retention` backups named `name`; returns how many went.
+fn prune(dir: &Path, name: &str, extension: &str, retention: usize) -> Result<usize, String> {
+    if retention == 0 {
+        return Ok(0);
+    }
+    let mut backups: Vec<((String, u32), PathBuf)> = fs::read_dir(dir)
+        .map_err(|e| format!("Cannot list {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let key = backup_key(path.file_name()?.to_str()?, name, extension)?;
+            Some((key, path))
+        })
+        .collect();
+    // Oldest first
+    backups.sort();
+    let excess = backups.len().saturating_sub(retention);
+    for (_, old) in &backups[..excess] {
+        fs::remove_file(old).map_err(|e| format!("Cannot remove old backup {}: {}", old.display(), e))?;
+    }
+    Ok(excess)
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
     }
-}
\ No newline at end of file
+}
@@ -1,10 +1,10 @@
-use std::sync::mpsc::Sender;
-use super::Task;
+use super::{Task, TaskContext, TaskResult};
 
 pub struct HotfixTask;
 
 impl Task for HotfixTask {
-    fn run(log_tx: Sender<String>) {
-        let _ = log_tx.send(" [Task] Applying urgent hotfix...".to_string());
+    fn run(ctx: &TaskContext) -> TaskResult {
+        ctx.log("Applying urgent hotfix...");
+        Ok(())
     }
-}
\ No newline at end of file
+}
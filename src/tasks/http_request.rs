@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+
+use reqwest::Method;
+
+use super::{OUTPUT_SNIPPET_BYTES, ParamSpec, Task, TaskContext, TaskResult};
+
+/// Calls a URL with parameters from the job payload (metadata):
+///
+/// - `url`: request URL (required)
+/// - `method`: `GET` (default), `POST`, `PUT`, ...
+/// - `header.NAME`: request header `NAME`
+/// - `auth_token`: sent as `Authorization: Bearer <token>`
+/// - `body`: request body, sent as is
+/// - `expected_status`: comma-separated codes or classes, e.g. `200,204` or `2xx` (default)
+/// - `timeout_secs`: give up after this long (default 30)
+///
+/// The status line and the start of the response body are kept in the run's history.
+/// Any other status, or no response at all, fails the run.
+pub struct HttpRequestTask;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl Task for HttpRequestTask {
+    fn run(ctx: &TaskContext) -> TaskResult {
+        let params = HttpParams::from_metadata(ctx.job.metadata.as_ref())?;
+        ctx.log(format!("{} {}", params.method, params.url));
+
+        let mut request = super::http_client()
+            .request(params.method.clone(), &params.url)
+            .timeout(params.timeout);
+        for (name, value) in &params.headers {
+            request = request.header(name, value);
+        }
+        if let Some(token) = &params.auth_token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = &params.body {
+            request = request.body(body.clone());
+        }
+
+        let response = request
+            .send()
+            .map_err(|e| format!("{} {} failed: {}", params.method, params.url, e))?;
+        let status = response.status();
+        // Only the start of the body is kept, so read no more than that (plus one byte
+        // to tell whether there was more); the rest is dropped with the connection
+        let mut body = Vec::new();
+        let _ = response.take(OUTPUT_SNIPPET_BYTES as u64 + 1).read_to_end(&mut body);
+        if body.len() > OUTPUT_SNIPPET_BYTES {
+            body.truncate(OUTPUT_SNIPPET_BYTES);
+            ctx.log(format!("Response body is over {} bytes; only the start is kept", OUTPUT_SNIPPET_BYTES));
+        }
+        ctx.set_output(format!("HTTP {}\n{}", status, String::from_utf8_lossy(&body)));
+
+        if params.expects(status.as_u16()) {
+            ctx.log(format!("Response: {}", status));
+            Ok(())
+        } else {
+            Err(format!("Unexpected status {} from {} {}", status, params.method, params.url))
+        }
+    }
+
+    fn params() -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::text("url", "URL").required(),
+            ParamSpec::choice("method", "Method", &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"]).default("GET"),
+            ParamSpec::multiline("body", "Body"),
+            ParamSpec::text("auth_token", "Bearer token").secret(),
+            ParamSpec::text("expected_status", "Expected status").default("2xx").help("e.g. 200,204 or 2xx"),
+            ParamSpec::integer("timeout_secs", "Timeout (s)").default("30"),
+        ]
+    }
+}
+
+#[derive(Debug)]
+struct HttpParams {
+    method: Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    auth_token: Option<String>,
+    body: Option<String>,
+    expected: Vec<StatusMatch>,
+    timeout: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+enum StatusMatch {
+    Code(u16),
+    /// `2xx` is `Class(2)`
+    Class(u16),
+}
+
+impl HttpParams {
+    fn from_metadata(metadata: Option<&HashMap<String, String>>) -> Result<Self, String> {
+        let empty = HashMap::new();
+        let meta = metadata.unwrap_or(&empty);
+        let url = meta
+            .get("url")
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .ok_or("HTTP job needs a 'url' in its payload")?;
+        let method = match meta.get("method") {
+            Some(method) => Method::from_bytes(method.trim().to_uppercase().as_bytes())
+                .map_err(|_| format!("'method' is not a valid HTTP method: '{}'", method))?,
+            None => Method::GET,
+        };
+        let mut headers: Vec<(String, String)> = meta
+            .iter()
+            .filter_map(|(key, value)| key.strip_prefix("header.").map(|name| (name.to_string(), value.clone())))
+            .collect();
+        headers.sort();
+        let expected = match meta.get("expected_status") {
+            Some(list) => list
+                .split(',')
+                .map(parse_status)
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![StatusMatch::Class(2)],
+        };
+        let timeout = match meta.get("timeout_secs") {
+            Some(secs) => Duration::from_secs(
+                secs.trim()
+                    .parse()
+                    .map_err(|_| format!("'timeout_secs' must be a number, got '{}'", secs))?,
+            ),
+            None => DEFAULT_TIMEOUT,
+        };
+        Ok(Self {
+            method,
+            url,
+            headers,
+            auth_token: meta.get("auth_token").cloned().filter(|t| !t.is_empty()),
+            body: meta.get("body").cloned(),
+            expected,
+            timeout,
+        })
+    }
+
+    fn expects(&self, status: u16) -> bool {
+        self.expected.iter().any(|m| match m {
+            StatusMatch::Code(code) => *code == status,
+            StatusMatch::Class(class) => status / 100 == *class,
+        })
+    }
+}
+
+fn parse_status(input: &str) -> Result<StatusMatch, String> {
+    let input = input.trim();
+    let invalid = || format!("'expected_status' entries look like 200 or 2xx, got '{}'", input);
+    match input.to_lowercase().strip_suffix("xx") {
+        Some(class) => class.parse().map(StatusMatch::Class).map_err(|_| invalid()),
+        None => input.parse().map(StatusMatch::Code).map_err(|_| invalid()),
+    }
+}
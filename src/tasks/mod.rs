@@ -1,9 +1,132 @@
-use std::sync::mpsc::Sender;
+use std::sync::{Mutex, OnceLock};
+
+use tracing::Span;
+
+use crate::email::EmailConfig;
+use crate::events::{EventBus, LogEvent, LogLevel, LogSource};
+use crate::job::Job;
+use crate::worker::Worker;
+
+pub use params::{ParamKind, ParamSpec};
+
+/// `Err` fails the run; the job is retried while it has retries left
+pub type TaskResult = Result<(), String>;
 
 pub trait Task {
-    fn run(log_tx: Sender<String>);
+    fn run(ctx: &TaskContext) -> TaskResult;
+
+    /// Payload keys the task reads, so forms can ask for them
+    fn params() -> Vec<ParamSpec> {
+        Vec::new()
+    }
 }
 
 pub mod backup_db;
 pub mod send_email;
-pub mod hotfix;
\ No newline at end of file
+pub mod hotfix;
+pub mod http_request;
+pub mod params;
+pub mod shell;
+
+/// Longest output snippet kept for a run's history
+pub const OUTPUT_SNIPPET_BYTES: usize = 1024;
+
+/// HTTP client shared by all tasks that make requests
+pub fn http_client() -> &'static reqwest::blocking::Client {
+    static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::blocking::Client::new)
+}
+
+/// What a task function gets to work with: the job being run and a way to
+/// report progress on the scheduler's event bus.
+pub struct TaskContext {
+    pub job: Job,
+    events: Option<EventBus>,
+    span: Span,
+    output: Mutex<Option<String>>,
+}
+
+impl TaskContext {
+    /// Task logs are attributed to the span that is current when the context is created
+    pub fn new(job: Job, events: Option<EventBus>) -> Self {
+        Self {
+            job,
+            events,
+            span: Span::current(),
+            output: Mutex::new(None),
+        }
+    }
+
+    /// The job run's span; enter it from threads the task spawns so their
+    /// logs stay nested under the run
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn log(&self, message: impl Into<String>) {
+        self.emit(LogLevel::Info, message.into());
+    }
+
+    pub fn warn(&self, message: impl Into<String>) {
+        self.emit(LogLevel::Warn, message.into());
+    }
+
+    pub fn error(&self, message: impl Into<String>) {
+        self.emit(LogLevel::Error, message.into());
+    }
+
+    /// Records what the run produced in its history entry, cut to `OUTPUT_SNIPPET_BYTES`
+    pub fn set_output(&self, output: impl Into<String>) {
+        let mut output = output.into();
+        if output.len() > OUTPUT_SNIPPET_BYTES {
+            let mut end = OUTPUT_SNIPPET_BYTES;
+            while !output.is_char_boundary(end) {
+                end -= 1;
+            }
+            output.truncate(end);
+            output.push('…');
+        }
+        if let Ok(mut slot) = self.output.lock() {
+            *slot = Some(output);
+        }
+    }
+
+    pub fn take_output(&self) -> Option<String> {
+        self.output.lock().ok().and_then(|mut slot| slot.take())
+    }
+
+    /// Job metadata value for `key`, falling back to the environment (`.env`)
+    pub fn var(&self, key: &str) -> Option<String> {
+        self.job
+            .metadata
+            .as_ref()
+            .and_then(|meta| meta.get(key).cloned())
+            .or_else(|| std::env::var(key).ok())
+    }
+
+    fn emit(&self, level: LogLevel, message: String) {
+        match level {
+            LogLevel::Info => tracing::info!(parent: &self.span, "{}", message),
+            LogLevel::Warn => tracing::warn!(parent: &self.span, "{}", message),
+            LogLevel::Error => tracing::error!(parent: &self.span, "{}", message),
+        }
+        if let Some(events) = self.events.as_ref() {
+            events.publish(LogEvent::new(level, LogSource::Task, Some(self.job.id), message));
+        }
+    }
+}
+
+/// Registers every built-in task under the function name jobs refer to.
+pub fn register_builtin(worker: &mut Worker) {
+    worker.register_task::<backup_db::BackupDbTask>("backup_fn");
+    worker.register_task::<send_email::SendEmailTask>("email_fn");
+    worker.register_task::<hotfix::HotfixTask>("hotfix_fn");
+    worker.register_task::<http_request::HttpRequestTask>("http");
+    worker.register_task::<shell::ShellTask>("shell");
+}
+
+/// Re-registers `email_fn` to send through `config` (the `[email]` section)
+/// instead of reading transport settings per job. Its parameters stay declared.
+pub fn register_email(worker: &mut Worker, config: EmailConfig) {
+    worker.register("email_fn", move |ctx| send_email::SendEmailTask::send(ctx, &config));
+}
@@ -0,0 +1,193 @@
+//! Parameters a task reads from the job payload, declared so front ends (the
+//! TUI add-task form) can ask for them and check the values before a job is queued.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamKind {
+    Text,
+    /// Free text that may span lines, e.g. a message body
+    Multiline,
+    Integer,
+    Bool,
+    /// One of a fixed set of values
+    Choice(Vec<String>),
+    /// Comma-separated email addresses
+    Emails,
+}
+
+/// One payload key of a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamSpec {
+    /// Payload (metadata) key
+    pub name: String,
+    pub label: String,
+    pub kind: ParamKind,
+    pub required: bool,
+    pub default: Option<String>,
+    /// Masked when entered or displayed
+    pub secret: bool,
+    pub help: Option<String>,
+}
+
+impl ParamSpec {
+    pub fn new(name: &str, label: &str, kind: ParamKind) -> Self {
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            kind,
+            required: false,
+            default: None,
+            secret: false,
+            help: None,
+        }
+    }
+
+    pub fn text(name: &str, label: &str) -> Self {
+        Self::new(name, label, ParamKind::Text)
+    }
+
+    pub fn multiline(name: &str, label: &str) -> Self {
+        Self::new(name, label, ParamKind::Multiline)
+    }
+
+    pub fn integer(name: &str, label: &str) -> Self {
+        Self::new(name, label, ParamKind::Integer)
+    }
+
+    pub fn boolean(name: &str, label: &str) -> Self {
+        Self::new(name, label, ParamKind::Bool)
+    }
+
+    pub fn choice(name: &str, label: &str, options: &[&str]) -> Self {
+        Self::new(name, label, ParamKind::Choice(options.iter().map(|o| o.to_string()).collect()))
+    }
+
+    pub fn emails(name: &str, label: &str) -> Self {
+        Self::new(name, label, ParamKind::Emails)
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn default(mut self, value: &str) -> Self {
+        self.default = Some(value.to_string());
+        self
+    }
+
+    pub fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    pub fn help(mut self, text: &str) -> Self {
+        self.help = Some(text.to_string());
+        self
+    }
+
+    /// Values a Bool or Choice parameter can cycle through, empty for free input
+    pub fn options(&self) -> Vec<String> {
+        match &self.kind {
+            ParamKind::Bool => vec!["true".to_string(), "false".to_string()],
+            ParamKind::Choice(options) => options.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Checks one value; an empty value is only an error when the parameter is required.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return if self.required {
+                Err(format!("{} is required", self.label))
+            } else {
+                Ok(())
+            };
+        }
+        match &self.kind {
+            ParamKind::Text | ParamKind::Multiline => Ok(()),
+            ParamKind::Integer => value
+                .parse::<i64>()
+                .map(|_| ())
+                .map_err(|_| format!("{} must be a whole number", self.label)),
+            ParamKind::Bool | ParamKind::Choice(_) => {
+                let options = self.options();
+                if options.iter().any(|o| o.eq_ignore_ascii_case(value)) {
+                    Ok(())
+                } else {
+                    Err(format!("{} must be one of {}", self.label, options.join(", ")))
+                }
+            }
+            ParamKind::Emails => crate::email::parse_addresses(value)
+                .iter()
+                .try_for_each(|address| match address.parse::<lettre::message::Mailbox>() {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(format!("{}: '{}' is not an email address", self.label, address)),
+                }),
+        }
+    }
+}
+
+/// Words of payload keys whose values are hidden even when no spec declares them.
+/// Only whole words count, so `author`, `passenger_count` or `MONKEY` stay visible;
+/// compounds written as one word are listed on their own.
+const SECRET_WORDS: [&str; 17] = [
+    "PASS", "PASSWORD", "PASSWD", "PASSPHRASE", "SECRET", "SECRETS", "TOKEN", "TOKENS", "AUTH", "AUTHORIZATION",
+    "PRIVATE", "PRIVKEY", "CREDENTIAL", "CREDENTIALS", "KEY", "APIKEY", "ACCESSKEY",
+];
+
+/// Whether the value of payload key `key` should be masked when displayed: declared
+/// `secret` in `specs`, or named like a credential (e.g. `SMTP_PASSWORD`,
+/// `header.Authorization`, `apiKey`).
+pub fn is_secret(specs: &[ParamSpec], key: &str) -> bool {
+    if let Some(spec) = specs.iter().find(|s| s.name == key) {
+        return spec.secret;
+    }
+    key_words(key).iter().any(|word| SECRET_WORDS.contains(&word.as_str()))
+}
+
+/// Upper-cased words of a key, split at punctuation and camelCase humps.
+fn key_words(key: &str) -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if !c.is_ascii_alphanumeric() {
+            words.push(String::new());
+        } else if c.is_ascii_uppercase() && previous_lower {
+            words.push(c.to_string());
+        } else if let Some(word) = words.last_mut() {
+            word.push(c.to_ascii_uppercase());
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+    }
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+/// Checks `values` (by payload key) against `specs` and returns the payload to
+/// store: trimmed, with empty optional values left out. Keys without a spec are
+/// passed through. All problems are reported together.
+pub fn validate(specs: &[ParamSpec], values: &HashMap<String, String>) -> Result<HashMap<String, String>, Vec<String>> {
+    let mut errors = Vec::new();
+    for spec in specs {
+        let value = values.get(&spec.name).map(String::as_str).unwrap_or_default();
+        if let Err(e) = spec.check(value) {
+            errors.push(e);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let multiline = |key: &str| specs.iter().any(|s| s.name == key && s.kind == ParamKind::Multiline);
+    Ok(values
+        .iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(key, value)| {
+            // Message bodies keep their leading indentation and trailing newline
+            let value = if multiline(key) { value.clone() } else { value.trim().to_string() };
+            (key.clone(), value)
+        })
+        .collect())
+}
@@ -1,61 +1,97 @@
-use std::sync::mpsc::Sender;
-use super::Task;
+use std::path::Path;
 
+use crate::email::{self, Attachment, Email, EmailConfig, TransportKind};
+
+use super::{ParamSpec, Task, TaskContext, TaskResult};
+
+/// Sends an email through the configured transport (see `crate::email`). The
+/// message comes from the job payload, falling back to `.env`:
+///
+/// - `SMTP_RECIPIENT`, `EMAIL_CC`, `EMAIL_BCC`: comma-separated addresses
+/// - `SMTP_FROM`: sender (default `email.from`)
+/// - `EMAIL_SUBJECT`, `EMAIL_BODY` (plain text) and `EMAIL_HTML`
+/// - `EMAIL_ATTACHMENTS`: comma-separated file paths
 pub struct SendEmailTask;
 
+/// Resend's shared test sender, used when nothing else is configured
+const RESEND_TEST_SENDER: &str = "onboarding@resend.dev";
+
 impl Task for SendEmailTask {
-    fn run(log_tx: Sender<String>) {
-        let _ = log_tx.send("📧 [Task] Sending email...".to_string());
-
-        let api_key = std::env::var("RESEND_API_KEY").unwrap_or_default();
-        let from = std::env::var("SMTP_FROM").unwrap_or_else(|_| "onboarding@resend.dev".to_string());
-        let to = std::env::var("SMTP_RECIPIENT").unwrap_or_default();
-
-        if api_key.is_empty() {
-            let _ = log_tx.send("❌ [Task] Error: RESEND_API_KEY missing in .env!".to_string());
-            return;
-        }
-        if to.is_empty() {
-            let _ = log_tx.send("❌ [Task] Error: SMTP_RECIPIENT missing in .env!".to_string());
-            return;
-        }
-
-        let subject = std::env::var("EMAIL_SUBJECT")
-            .unwrap_or_else(|_| "Termi-Schedule: Job Executed ✅".to_string());
-
-        let timestamp = chrono::Utc::now().to_rfc3339();
-        let default_body = format!(
-            "Hello!\n\nThe automated email task has been successfully processed by your Termi-Schedule worker thread.\n\nTimestamp: {}",
-            timestamp
-        );
-        let body_text = std::env::var("EMAIL_BODY").unwrap_or(default_body);
-
-        let client = reqwest::blocking::Client::new();
-        let body = serde_json::json!({
-            "from": from,
-            "to": [to],
-            "subject": subject,
-            "text": body_text,
-        });
-
-        match client
-            .post("https://api.resend.com/emails")
-            .header("Authorization", format!("Bearer {}", api_key))
-            .json(&body)
-            .send()
-        {
-            Ok(resp) => {
-                if resp.status().is_success() {
-                    let _ = log_tx.send("✅ [Task] Email sent successfully!".to_string());
-                } else {
-                    let status = resp.status();
-                    let text = resp.text().unwrap_or_default();
-                    let _ = log_tx.send(format!("❌ [Task] Resend API error ({}): {}", status, text));
-                }
-            }
-            Err(e) => {
-                let _ = log_tx.send(format!("❌ [Task] HTTP request failed: {}", e));
-            }
-        }
+    /// Without a configured transport, settings are read like the message fields.
+    fn run(ctx: &TaskContext) -> TaskResult {
+        let mut config = EmailConfig::default();
+        config.apply_env(|key| ctx.var(key))?;
+        Self::send(ctx, &config)
     }
-}
\ No newline at end of file
+
+    fn params() -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::emails("SMTP_RECIPIENT", "To").help("Comma-separated; defaults to SMTP_RECIPIENT in .env"),
+            ParamSpec::emails("EMAIL_CC", "Cc"),
+            ParamSpec::emails("EMAIL_BCC", "Bcc"),
+            ParamSpec::text("EMAIL_SUBJECT", "Subject"),
+            ParamSpec::multiline("EMAIL_BODY", "Body"),
+            ParamSpec::multiline("EMAIL_HTML", "HTML body"),
+            ParamSpec::text("EMAIL_ATTACHMENTS", "Attachments").help("Comma-separated file paths"),
+        ]
+    }
+}
+
+impl SendEmailTask {
+    pub fn send(ctx: &TaskContext, config: &EmailConfig) -> TaskResult {
+        let email = build_email(ctx, config)?;
+        let recipients = email.recipients().count();
+        ctx.log(format!("📧 Sending email to {} recipient(s) via {}...", recipients, config.transport));
+
+        let receipt = config.transport()?.send(&email)?;
+        ctx.log(format!("✅ Email sent successfully! ({})", receipt));
+        ctx.set_output(receipt);
+        Ok(())
+    }
+}
+
+fn build_email(ctx: &TaskContext, config: &EmailConfig) -> Result<Email, String> {
+    let addresses = |key: &str| ctx.var(key).map(|list| email::parse_addresses(&list)).unwrap_or_default();
+    let from = ctx
+        .var("SMTP_FROM")
+        .or_else(|| config.from.clone())
+        .or_else(|| (config.transport == TransportKind::Resend).then(|| RESEND_TEST_SENDER.to_string()))
+        .unwrap_or_default();
+
+    let html = ctx.var("EMAIL_HTML");
+    let text = ctx.var("EMAIL_BODY").or_else(|| {
+        // An HTML-only message stays HTML-only
+        html.is_none().then(|| {
+            format!(
+                "Hello!\n\nThe automated email task has been successfully processed by your Termi-Schedule worker thread.\n\nTimestamp: {}",
+                chrono::Utc::now().to_rfc3339()
+            )
+        })
+    });
+    let attachments = ctx
+        .var("EMAIL_ATTACHMENTS")
+        .map(|list| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|path| Attachment::from_file(Path::new(path)))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let email = Email {
+        from,
+        to: addresses("SMTP_RECIPIENT"),
+        cc: addresses("EMAIL_CC"),
+        bcc: addresses("EMAIL_BCC"),
+        subject: ctx
+            .var("EMAIL_SUBJECT")
+            .unwrap_or_else(|| "Termi-Schedule: Job Executed ✅".to_string()),
+        text,
+        html,
+        attachments,
+    };
+    email.validate()?;
+    Ok(email)
+}
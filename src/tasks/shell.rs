@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{ParamSpec, Task, TaskContext, TaskResult};
+
+/// Runs an external command taken from the job payload (metadata):
+///
+/// - `command`: program to run (required)
+/// - `args`: JSON array (`["-c", "echo hi"]`) or whitespace-separated arguments
+/// - `cwd`: working directory
+/// - `env.NAME`: extra environment variables
+/// - `timeout_secs`: kill the command after this long
+///
+/// Output lines go to the job's log (stderr as warnings). Exit code 0 succeeds,
+/// anything else fails the run so the retry logic takes over.
+pub struct ShellTask;
+
+/// How often a running command is checked for exit or timeout
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+impl Task for ShellTask {
+    fn run(ctx: &TaskContext) -> TaskResult {
+        let params = ShellParams::from_metadata(ctx.job.metadata.as_ref())?;
+        ctx.log(format!("$ {}", params.display()));
+
+        let mut command = Command::new(&params.command);
+        command
+            .args(&params.args)
+            .envs(&params.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(cwd) = &params.cwd {
+            command.current_dir(cwd);
+        }
+        // Its own process group, so a timeout also kills whatever the command started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Cannot start '{}': {}", params.command, e))?;
+
+        // Lines come back over a channel: a process the command left running can keep
+        // the pipes open, and waiting on the readers would then outlast the timeout
+        let (tx, lines) = mpsc::channel();
+        let stdout_tx = tx.clone();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        thread::spawn(move || stream(stdout, |line| stdout_tx.send(Output::Stdout(line)).is_ok()));
+        thread::spawn(move || stream(stderr, |line| tx.send(Output::Stderr(line)).is_ok()));
+
+        let mut last_error = None;
+        let started = Instant::now();
+        let mut status = None;
+        let mut open = true;
+        loop {
+            if open {
+                match lines.recv_timeout(POLL_INTERVAL) {
+                    Ok(Output::Stdout(line)) => ctx.log(line),
+                    Ok(Output::Stderr(line)) => {
+                        ctx.warn(line.as_str());
+                        last_error = Some(line);
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => open = false,
+                }
+            } else {
+                thread::sleep(POLL_INTERVAL);
+            }
+            if status.is_none() {
+                status = match child.try_wait() {
+                    Ok(status) => status,
+                    Err(e) => {
+                        kill(&mut child);
+                        return Err(format!("Cannot wait for '{}': {}", params.command, e));
+                    }
+                };
+            }
+            if let Some(status) = status
+                && !open
+            {
+                return check_status(&params.command, status, last_error);
+            }
+            if let Some(timeout) = params.timeout
+                && started.elapsed() >= timeout
+            {
+                kill(&mut child);
+                return Err(format!("'{}' timed out after {}s", params.command, timeout.as_secs()));
+            }
+        }
+    }
+
+    fn params() -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::text("command", "Command").required(),
+            ParamSpec::text("args", "Arguments").help(r#"Space-separated, or a JSON array like ["-c", "echo hi"]"#),
+            ParamSpec::text("cwd", "Working directory"),
+            ParamSpec::integer("timeout_secs", "Timeout (s)"),
+        ]
+    }
+}
+
+enum Output {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// Passes each line of `pipe` to `line` until the pipe closes or `line` returns false.
+fn stream(pipe: Option<impl Read>, mut line: impl FnMut(String) -> bool) {
+    let Some(pipe) = pipe else {
+        return;
+    };
+    for text in BufReader::new(pipe).lines().map_while(Result::ok) {
+        if !line(text) {
+            break;
+        }
+    }
+}
+
+/// Kills the command and, on Unix, every process in its group.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: plain syscall; a negative pid addresses the process group the child leads
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn check_status(command: &str, status: ExitStatus, last_error: Option<String>) -> TaskResult {
+    if status.success() {
+        return Ok(());
+    }
+    let reason = match status.code() {
+        Some(code) => format!("'{}' exited with status {}", command, code),
+        None => format!("'{}' was terminated by a signal", command),
+    };
+    Err(match last_error {
+        Some(line) => format!("{}: {}", reason, line),
+        None => reason,
+    })
+}
+
+#[derive(Debug, Default)]
+struct ShellParams {
+    command: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    env: HashMap<String, String>,
+    timeout: Option<Duration>,
+}
+
+impl ShellParams {
+    fn from_metadata(metadata: Option<&HashMap<String, String>>) -> Result<Self, String> {
+        let empty = HashMap::new();
+        let meta = metadata.unwrap_or(&empty);
+        let command = meta
+            .get("command")
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .ok_or("Shell job needs a 'command' in its payload")?;
+        let args = match meta.get("args").map(|a| a.trim()) {
+            Some(args) if args.starts_with('[') => serde_json::from_str(args)
+                .map_err(|e| format!("'args' must be a JSON array of strings: {}", e))?,
+            Some(args) => args.split_whitespace().map(str::to_string).collect(),
+            None => Vec::new(),
+        };
+        let timeout = match meta.get("timeout_secs") {
+            Some(secs) => Some(Duration::from_secs(
+                secs.trim()
+                    .parse()
+                    .map_err(|_| format!("'timeout_secs' must be a number, got '{}'", secs))?,
+            )),
+            None => None,
+        };
+        let env = meta
+            .iter()
+            .filter_map(|(key, value)| key.strip_prefix("env.").map(|name| (name.to_string(), value.clone())))
+            .collect();
+        Ok(Self {
+            command,
+            args,
+            cwd: meta.get("cwd").cloned(),
+            env,
+            timeout,
+        })
+    }
+
+    fn display(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,232 @@
+pub mod otlp;
+pub mod rotation;
+pub mod tui_layer;
+
+use std::path::PathBuf;
+
+use otlp::{OtlpFileLayer, OtlpTarget};
+use rotation::SizeRotatingWriter;
+use tracing::info;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::{Layer, Registry, layer::SubscriberExt};
+use tui_layer::TuiLogLayer;
+
+use crate::events::{EventBus, LogLevel, SchedulerEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    /// One file per day (`<file_name>.YYYY-MM-DD`)
+    Daily,
+    /// Roll over when the file reaches `max_size_bytes` (`<file_name>.1`, `.2`, ...)
+    Size,
+}
+
+/// How logs are filtered, formatted and where they go.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryConfig {
+    /// `EnvFilter` directives, e.g. `info,scheduler::engine=debug`
+    pub level: String,
+    pub format: LogFormat,
+    pub dir: PathBuf,
+    pub file_name: String,
+    pub rotation: LogRotation,
+    pub max_size_bytes: u64,
+    /// Rotated files kept besides the active one
+    pub max_files: usize,
+    /// Ignored while the TUI owns the terminal
+    pub stdout: bool,
+    /// Most verbose level forwarded to the TUI log panel
+    pub tui_level: LevelFilter,
+    pub otlp_export: Option<OtlpTarget>,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+            dir: PathBuf::from("logs"),
+            file_name: "scheduler.log".to_string(),
+            rotation: LogRotation::Daily,
+            max_size_bytes: 10 * 1024 * 1024,
+            max_files: 7,
+            stdout: true,
+            tui_level: LevelFilter::INFO,
+            otlp_export: None,
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// Defaults overridden by `SCHEDULER_LOG` (or `RUST_LOG`), `SCHEDULER_LOG_FORMAT`,
+    /// `SCHEDULER_LOG_DIR`, `SCHEDULER_LOG_ROTATION`, `SCHEDULER_LOG_MAX_SIZE`,
+    /// `SCHEDULER_LOG_MAX_FILES`, `SCHEDULER_LOG_STDOUT`, `SCHEDULER_TUI_LOG_LEVEL`
+    /// and `SCHEDULER_OTLP_EXPORT`.
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+        config.apply_env(|key| std::env::var(key).ok())?;
+        Ok(config)
+    }
+
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+        if let Some(level) = var("SCHEDULER_LOG").or_else(|| var("RUST_LOG")) {
+            self.level = level;
+        }
+        if let Some(format) = var("SCHEDULER_LOG_FORMAT") {
+            self.format = parse_format(&format)?;
+        }
+        if let Some(dir) = var("SCHEDULER_LOG_DIR") {
+            self.dir = PathBuf::from(dir);
+        }
+        if let Some(rotation) = var("SCHEDULER_LOG_ROTATION") {
+            self.rotation = parse_rotation(&rotation)?;
+        }
+        if let Some(size) = var("SCHEDULER_LOG_MAX_SIZE") {
+            self.max_size_bytes = size
+                .parse()
+                .map_err(|_| format!("SCHEDULER_LOG_MAX_SIZE must be a byte count, got '{}'.", size))?;
+        }
+        if let Some(files) = var("SCHEDULER_LOG_MAX_FILES") {
+            self.max_files = files
+                .parse()
+                .map_err(|_| format!("SCHEDULER_LOG_MAX_FILES must be a number, got '{}'.", files))?;
+        }
+        if let Some(stdout) = var("SCHEDULER_LOG_STDOUT") {
+            self.stdout = parse_bool(&stdout)
+                .ok_or_else(|| format!("SCHEDULER_LOG_STDOUT must be true or false, got '{}'.", stdout))?;
+        }
+        if let Some(level) = var("SCHEDULER_TUI_LOG_LEVEL") {
+            self.tui_level = level
+                .parse()
+                .map_err(|_| format!("Invalid SCHEDULER_TUI_LOG_LEVEL '{}'.", level))?;
+        }
+        if let Some(target) = var("SCHEDULER_OTLP_EXPORT") {
+            self.otlp_export = Some(OtlpTarget::parse(&target));
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_format(value: &str) -> Result<LogFormat, String> {
+    match value.to_lowercase().as_str() {
+        "text" => Ok(LogFormat::Text),
+        "json" => Ok(LogFormat::Json),
+        other => Err(format!("Unknown log format '{}' (expected text or json).", other)),
+    }
+}
+
+pub fn parse_rotation(value: &str) -> Result<LogRotation, String> {
+    match value.to_lowercase().as_str() {
+        "daily" => Ok(LogRotation::Daily),
+        "size" => Ok(LogRotation::Size),
+        other => Err(format!("Unknown log rotation '{}' (expected daily or size).", other)),
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Target of log lines mirrored from the event bus
+pub const EVENTS_TARGET: &str = "scheduler::events";
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Installs the global subscriber. With `tui` set, stdout logging and OTLP export
+/// to stdout are skipped (they would draw over the screen) and events go to the
+/// TUI log panel through the bus instead. Keep the returned guard alive so buffered lines are flushed.
+pub fn init_telemetry(config: &TelemetryConfig, tui: Option<EventBus>) -> Result<WorkerGuard, String> {
+    let filter = EnvFilter::try_new(&config.level)
+        .map_err(|e| format!("Invalid log level '{}': {}", config.level, e))?;
+
+    // 1. File logger
+    std::fs::create_dir_all(&config.dir)
+        .map_err(|e| format!("Cannot create log directory {}: {}", config.dir.display(), e))?;
+    let file_writer: Box<dyn std::io::Write + Send> = match config.rotation {
+        LogRotation::Daily => Box::new(
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(&config.file_name)
+                .max_log_files(config.max_files.max(1))
+                .build(&config.dir)
+                .map_err(|e| format!("Cannot open log directory {}: {}", config.dir.display(), e))?,
+        ),
+        LogRotation::Size => Box::new(
+            SizeRotatingWriter::new(config.dir.join(&config.file_name), config.max_size_bytes, config.max_files)
+                .map_err(|e| format!("Cannot open log file in {}: {}", config.dir.display(), e))?,
+        ),
+    };
+    let (non_blocking, guard) = tracing_appender::non_blocking(file_writer);
+    let mut layers: Vec<BoxedLayer> = vec![fmt_layer(config.format, non_blocking, false)];
+
+    // 2. Terminal logger, or the TUI log panel while the TUI owns the terminal
+    let tui_running = tui.is_some();
+    match tui {
+        Some(events) => layers.push(TuiLogLayer::new(events).with_filter(config.tui_level).boxed()),
+        None if config.stdout => layers.push(fmt_layer(config.format, std::io::stdout, true)),
+        None => {}
+    }
+
+    // 3. Optional OTLP/JSON span export; reported once the subscriber is installed
+    let mut otlp_warning = None;
+    match config.otlp_export.as_ref() {
+        // Like the stdout logger, spans on stdout would draw over the TUI
+        Some(OtlpTarget::Stdout) if tui_running => {
+            otlp_warning = Some("OTLP export to stdout is disabled while the TUI runs; export to a file or run --headless".to_string());
+        }
+        Some(target) => match OtlpFileLayer::new(target) {
+            Ok(layer) => layers.push(layer.boxed()),
+            Err(e) => otlp_warning = Some(format!("OTLP export to {:?} unavailable: {}", target, e)),
+        },
+        None => {}
+    }
+
+    let subscriber = tracing_subscriber::registry().with(layers).with(filter);
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| format!("Telemetry already initialized: {}", e))?;
+    info!("Event System & Telemetry initialized.");
+    if let Some(warning) = otlp_warning {
+        tracing::warn!("{}", warning);
+    }
+    Ok(guard)
+}
+
+fn fmt_layer<W>(format: LogFormat, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(ansi);
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
+
+/// Writes job and engine events to the logs (file, stdout in headless mode).
+/// Task messages are skipped: tasks already log them through `tracing`.
+pub fn start_event_logger(events: std::sync::mpsc::Receiver<SchedulerEvent>) {
+    std::thread::spawn(move || {
+        for event in events {
+            if matches!(event, SchedulerEvent::Log(_)) {
+                continue;
+            }
+            match event.level() {
+                LogLevel::Info => info!(target: EVENTS_TARGET, "{}", event),
+                LogLevel::Warn => tracing::warn!(target: EVENTS_TARGET, "{}", event),
+                LogLevel::Error => tracing::error!(target: EVENTS_TARGET, "{}", event),
+            }
+        }
+    });
+}
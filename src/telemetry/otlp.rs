@@ -0,0 +1,269 @@
+//! Offline span exporter: writes finished spans as OTLP/JSON lines (the
+//! `ExportTraceServiceRequest` shape used by the OpenTelemetry collector's file
+//! exporter), so traces can be replayed into any OTLP backend later.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use uuid::Uuid;
+
+const SERVICE_NAME: &str = "scheduler";
+
+/// Where exported spans go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OtlpTarget {
+    Stdout,
+    File(String),
+}
+
+impl OtlpTarget {
+    /// `stdout` or a file path
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "stdout" | "-" => OtlpTarget::Stdout,
+            path => OtlpTarget::File(path.to_string()),
+        }
+    }
+}
+
+/// `tracing` layer that exports every closed span, including the events
+/// logged inside it.
+pub struct OtlpFileLayer {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+/// What is collected for a span while it is open.
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    name: &'static str,
+    start: u128,
+    attributes: Vec<(String, Value)>,
+    events: Vec<Value>,
+}
+
+impl OtlpFileLayer {
+    pub fn new(target: &OtlpTarget) -> io::Result<Self> {
+        let writer: Box<dyn Write + Send> = match target {
+            OtlpTarget::Stdout => Box::new(io::stdout()),
+            OtlpTarget::File(path) => {
+                if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir)?;
+                }
+                Box::new(OpenOptions::new().create(true).append(true).open(path)?)
+            }
+        };
+        Ok(Self::with_writer(writer))
+    }
+
+    pub fn with_writer(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    fn export(&self, span: SpanData) {
+        let end = now_nanos();
+        let status = span
+            .attributes
+            .iter()
+            .find(|(k, _)| k == "otel.status_code")
+            .and_then(|(_, v)| v.get("stringValue").and_then(Value::as_str))
+            .map(|code| match code {
+                "ERROR" => 2,
+                "OK" => 1,
+                _ => 0,
+            })
+            .unwrap_or(0);
+        let attributes: Vec<Value> = span
+            .attributes
+            .into_iter()
+            .filter(|(k, _)| !k.starts_with("otel."))
+            .map(|(key, value)| json!({ "key": key, "value": value }))
+            .collect();
+
+        let mut otlp_span = json!({
+            "traceId": hex(&span.trace_id),
+            "spanId": hex(&span.span_id),
+            "name": span.name,
+            "kind": 1,
+            "startTimeUnixNano": span.start.to_string(),
+            "endTimeUnixNano": end.to_string(),
+            "attributes": attributes,
+            "events": span.events,
+            "status": { "code": status }
+        });
+        if let Some(parent) = span.parent_span_id {
+            otlp_span["parentSpanId"] = json!(hex(&parent));
+        }
+
+        let request = json!({
+            "resourceSpans": [{
+                "resource": { "attributes": [
+                    { "key": "service.name", "value": { "stringValue": SERVICE_NAME } }
+                ]},
+                "scopeSpans": [{
+                    "scope": { "name": SERVICE_NAME, "version": env!("CARGO_PKG_VERSION") },
+                    "spans": [otlp_span]
+                }]
+            }]
+        });
+
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{}", request);
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl<S> Layer<S> for OtlpFileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanData>()
+                .map(|p| (p.trace_id, p.span_id))
+        });
+        let ids = *Uuid::new_v4().as_bytes();
+        let mut span_id = [0u8; 8];
+        span_id.copy_from_slice(&ids[..8]);
+
+        let mut attributes = Vec::new();
+        attrs.record(&mut AttributeVisitor(&mut attributes));
+        span.extensions_mut().insert(SpanData {
+            trace_id: parent.map(|(t, _)| t).unwrap_or(*Uuid::new_v4().as_bytes()),
+            span_id,
+            parent_span_id: parent.map(|(_, s)| s),
+            name: attrs.metadata().name(),
+            start: now_nanos(),
+            attributes,
+            events: Vec::new(),
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(data) = span.extensions_mut().get_mut::<SpanData>()
+        {
+            values.record(&mut AttributeVisitor(&mut data.attributes));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut attributes = Vec::new();
+        event.record(&mut AttributeVisitor(&mut attributes));
+        let message = attributes
+            .iter()
+            .position(|(k, _)| k == "message")
+            .map(|i| attributes.remove(i).1)
+            .and_then(|v| v.get("stringValue").and_then(Value::as_str).map(str::to_string))
+            .unwrap_or_else(|| event.metadata().name().to_string());
+        attributes.push(("level".to_string(), string_value(level_name(event.metadata().level()))));
+
+        let otlp_event = json!({
+            "timeUnixNano": now_nanos().to_string(),
+            "name": message,
+            "attributes": attributes
+                .into_iter()
+                .map(|(key, value)| json!({ "key": key, "value": value }))
+                .collect::<Vec<_>>()
+        });
+        if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+            data.events.push(otlp_event);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let data = span.extensions_mut().remove::<SpanData>();
+        if let Some(data) = data {
+            self.export(data);
+        }
+    }
+}
+
+/// Collects span/event fields as OTLP `AnyValue`s, replacing earlier values of the same key.
+struct AttributeVisitor<'a>(&'a mut Vec<(String, Value)>);
+
+impl AttributeVisitor<'_> {
+    fn set(&mut self, field: &Field, value: Value) {
+        let key = field.name();
+        match self.0.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.0.push((key.to_string(), value)),
+        }
+    }
+}
+
+impl Visit for AttributeVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, string_value(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, json!({ "doubleValue": value }));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, json!({ "boolValue": value }));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, string_value(&format!("{:?}", value)));
+    }
+}
+
+fn string_value(value: &str) -> Value {
+    json!({ "stringValue": value })
+}
+
+fn level_name(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "ERROR",
+        Level::WARN => "WARN",
+        Level::INFO => "INFO",
+        Level::DEBUG => "DEBUG",
+        Level::TRACE => "TRACE",
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
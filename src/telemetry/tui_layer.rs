@@ -0,0 +1,82 @@
+use std::fmt;
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+use crate::events::{EventBus, LogEvent, LogLevel, LogSource};
+
+/// Modules whose activity already reaches the TUI as typed bus events;
+/// forwarding their tracing output too would show every line twice.
+const BUS_TARGETS: &[&str] = &[
+    "scheduler::tasks",
+    "scheduler::worker",
+    "scheduler::job",
+    super::EVENTS_TARGET,
+];
+
+/// Forwards tracing events into the event bus as `LogEvent`s, so warnings from
+/// persistence, the control socket, etc. show up in the TUI log panel instead
+/// of being written over the screen.
+pub struct TuiLogLayer {
+    events: EventBus,
+}
+
+impl TuiLogLayer {
+    pub fn new(events: EventBus) -> Self {
+        Self { events }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for TuiLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let target = event.metadata().target();
+        if BUS_TARGETS.iter().any(|t| target.starts_with(t)) {
+            return;
+        }
+        let level = match *event.metadata().level() {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            _ => LogLevel::Info,
+        };
+        let source = if target.starts_with("scheduler::engine") {
+            LogSource::Engine
+        } else if target.starts_with("scheduler::queue") {
+            LogSource::Queue
+        } else {
+            LogSource::System
+        };
+
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+        self.events.publish(LogEvent::new(level, source, None, message.0));
+    }
+}
+
+/// Formats an event as its message followed by any other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl MessageVisitor {
+    fn push(&mut self, field: &Field, value: String) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() != "message" {
+            self.0.push_str(field.name());
+            self.0.push('=');
+        }
+        self.0.push_str(&value);
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, format!("{:?}", value));
+    }
+}
@@ -0,0 +1,446 @@
+//! Keybindings of the TUI: which key triggers which action in which part of the
+//! screen. Built from a preset (`default` or `vim`) and the `[tui.keys]` overrides
+//! in the configuration. Text entry in forms keeps its fixed keys.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+
+/// Part of the screen a key is pressed in; an action only applies in some of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    List,
+    Detail,
+    Timeline,
+    Help,
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 4] = [KeyContext::List, KeyContext::Detail, KeyContext::Timeline, KeyContext::Help];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            KeyContext::List => "Job list",
+            KeyContext::Detail => "Job details",
+            KeyContext::Timeline => "Timeline",
+            KeyContext::Help => "Help",
+        }
+    }
+}
+
+macro_rules! actions {
+    ($($action:ident => $name:literal, $label:literal, $description:literal, [$($context:ident),+];)+) => {
+        /// Something a key can be bound to.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Action {
+            $($action,)+
+        }
+
+        impl Action {
+            pub const ALL: &'static [Action] = &[$(Action::$action,)+];
+
+            /// Name in the `[tui.keys]` configuration
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Action::$action => $name,)+
+                }
+            }
+
+            /// Short label for the hint bar and buttons
+            pub fn label(self) -> &'static str {
+                match self {
+                    $(Action::$action => $label,)+
+                }
+            }
+
+            pub fn description(self) -> &'static str {
+                match self {
+                    $(Action::$action => $description,)+
+                }
+            }
+
+            pub fn applies_in(self, context: KeyContext) -> bool {
+                match self {
+                    $(Action::$action => [$(KeyContext::$context),+].contains(&context),)+
+                }
+            }
+        }
+    };
+}
+
+actions! {
+    Up => "up", "Previous", "Select the previous job", [List, Detail, Help];
+    Down => "down", "Next", "Select the next job", [List, Detail, Help];
+    AddJob => "add_job", "Add", "Add a job", [List];
+    Edit => "edit", "Edit", "Edit the selected job", [List, Detail];
+    Details => "details", "Details", "Show or hide the selected job's details", [List, Detail];
+    Delete => "delete", "Delete", "Remove the selected job", [List];
+    RunNow => "run_now", "Run now", "Run the selected job now, keeping its schedule", [List, Detail];
+    Duplicate => "duplicate", "Duplicate", "Copy the selected job into the add form", [List, Detail];
+    CopyId => "copy_id", "Copy id", "Copy the selected job's id to the clipboard", [List, Detail];
+    Search => "search", "Search", "Search the job list", [List];
+    FilterFunction => "filter_function", "By function", "Filter the job list by function", [List];
+    FilterTag => "filter_tag", "By tag", "Filter the job list by tag", [List];
+    FilterStatus => "filter_status", "By status", "Filter the job list by status", [List];
+    Sort => "sort", "Sort", "Switch the sort order of the job list", [List];
+    LogPageUp => "log_page_up", "Log up", "Scroll the log a page back", [List];
+    LogPageDown => "log_page_down", "Log down", "Scroll the log a page forward", [List];
+    LogTop => "log_top", "Oldest", "Jump to the oldest log line", [List];
+    LogFollow => "log_follow", "Follow", "Follow new log lines", [List];
+    LogSource => "log_source", "Log source", "Show one log source, or all of them", [List];
+    LogJob => "log_job", "Log job", "Show the selected job's log lines, or all jobs", [List];
+    ExportLog => "export_log", "Export log", "Write the shown log lines to a file", [List];
+    Timeline => "timeline", "Timeline", "Open or close the timeline", [List, Timeline];
+    ZoomIn => "zoom_in", "Zoom in", "Show a shorter time span", [Timeline];
+    ZoomOut => "zoom_out", "Zoom out", "Show a longer time span", [Timeline];
+    Earlier => "earlier", "Earlier", "Move the timeline earlier", [Timeline];
+    Later => "later", "Later", "Move the timeline later", [Timeline];
+    Now => "now", "Now", "Move the timeline back to now", [Timeline];
+    Help => "help", "Help", "Show or hide this help", [List, Detail, Timeline, Help];
+    Close => "close", "Close", "Close the popup or view", [Detail, Timeline, Help];
+    Back => "back", "Clear filters", "Clear the search and filters, quit when there are none", [List];
+    Quit => "quit", "Quit", "Quit the TUI and stop the engine", [List];
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        let name = input.trim().to_lowercase().replace('-', "_");
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|a| a.name() == name)
+            .ok_or_else(|| format!("unknown action '{}'", input))
+    }
+}
+
+/// A key with its modifiers, written like `ctrl+a`, `shift+tab`, `pageup` or `?`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self::normalized(code, modifiers)
+    }
+
+    /// Shift is part of the character (`G` rather than `shift+g`), and of BackTab.
+    fn normalized(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        *self == Key::normalized(event.code, event.modifiers)
+    }
+
+    /// A key press event, for mouse clicks that stand in for this key
+    pub fn event(&self) -> KeyEvent {
+        KeyEvent {
+            code: self.code,
+            modifiers: self.modifiers,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        let text = input.trim();
+        // "+" itself, alone or after modifiers ("ctrl++")
+        let (modifier_text, key) = if text == "+" {
+            ("", "+")
+        } else if let Some(mods) = text.strip_suffix("++") {
+            (mods, "+")
+        } else {
+            match text.rsplit_once('+') {
+                Some((mods, key)) => (mods, key),
+                None => ("", text),
+            }
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in modifier_text.split('+').filter(|p| !p.is_empty()) {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "option" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", part, input)),
+            };
+        }
+
+        let lower = key.to_lowercase();
+        let code = match lower.as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "space" => KeyCode::Char(' '),
+            f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok_and(|n| (1..=12).contains(&n)) => {
+                KeyCode::F(f[1..].parse().unwrap_or(1))
+            }
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    // Letters with Ctrl/Alt are reported in lower case
+                    (Some(c), None) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                        KeyCode::Char(c.to_ascii_lowercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key '{}'", input)),
+                }
+            }
+        };
+        Ok(Key::normalized(code, modifiers))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if c.is_ascii_uppercase() => write!(f, "Shift+{}", c),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Delete"),
+            KeyCode::Insert => f.write_str("Insert"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Up => f.write_str("\u{2191}"),
+            KeyCode::Down => f.write_str("\u{2193}"),
+            KeyCode::Left => f.write_str("\u{2190}"),
+            KeyCode::Right => f.write_str("\u{2192}"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Preset {
+    #[default]
+    Default,
+    /// hjkl movement, g/G for the ends of the log, Ctrl+U/Ctrl+D to page
+    Vim,
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.trim().to_lowercase().as_str() {
+            "default" => Ok(Preset::Default),
+            "vim" => Ok(Preset::Vim),
+            other => Err(format!("expected default or vim, got '{}'", other)),
+        }
+    }
+}
+
+const DEFAULT_KEYS: &[(Action, &[&str])] = &[
+    (Action::Up, &["up"]),
+    (Action::Down, &["down"]),
+    (Action::AddJob, &["ctrl+a"]),
+    (Action::Edit, &["e", "enter"]),
+    (Action::Details, &["i"]),
+    (Action::Delete, &["d", "delete"]),
+    (Action::RunNow, &["r"]),
+    (Action::Duplicate, &["c"]),
+    (Action::CopyId, &["y"]),
+    (Action::Search, &["/"]),
+    (Action::FilterFunction, &["f"]),
+    (Action::FilterTag, &["t"]),
+    (Action::FilterStatus, &["s"]),
+    (Action::Sort, &["o"]),
+    (Action::LogPageUp, &["pageup"]),
+    (Action::LogPageDown, &["pagedown"]),
+    (Action::LogTop, &["home"]),
+    (Action::LogFollow, &["end"]),
+    (Action::LogSource, &["l"]),
+    (Action::LogJob, &["j"]),
+    (Action::ExportLog, &["x"]),
+    (Action::Timeline, &["w"]),
+    (Action::ZoomIn, &["+", "="]),
+    (Action::ZoomOut, &["-"]),
+    (Action::Earlier, &["left"]),
+    (Action::Later, &["right"]),
+    (Action::Now, &["home", "0"]),
+    (Action::Help, &["?", "f1"]),
+    (Action::Close, &["esc", "q"]),
+    (Action::Back, &["esc"]),
+    (Action::Quit, &["q"]),
+];
+
+/// Differences of the vim preset from the default one
+const VIM_KEYS: &[(Action, &[&str])] = &[
+    (Action::Up, &["k", "up"]),
+    (Action::Down, &["j", "down"]),
+    (Action::AddJob, &["a", "ctrl+a"]),
+    (Action::LogPageUp, &["ctrl+u", "pageup"]),
+    (Action::LogPageDown, &["ctrl+d", "pagedown"]),
+    (Action::LogTop, &["g", "home"]),
+    (Action::LogFollow, &["G", "end"]),
+    (Action::LogSource, &["L"]),
+    (Action::LogJob, &["J"]),
+    (Action::Earlier, &["h", "left"]),
+    (Action::Later, &["l", "right"]),
+];
+
+/// The keys bound to every action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<Key>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::preset(Preset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn preset(preset: Preset) -> Self {
+        let mut keymap = Keymap { bindings: HashMap::new() };
+        let overrides = match preset {
+            Preset::Default => &[][..],
+            Preset::Vim => VIM_KEYS,
+        };
+        for (action, keys) in DEFAULT_KEYS.iter().chain(overrides) {
+            let keys = keys.iter().filter_map(|k| k.parse().ok()).collect();
+            keymap.bindings.insert(*action, keys);
+        }
+        keymap
+    }
+
+    /// Replaces the keys of `action`; an empty list unbinds it.
+    pub fn bind(&mut self, action: Action, keys: Vec<Key>) {
+        self.bindings.insert(action, keys);
+    }
+
+    /// Rebinds actions from `[tui.keys]`-style names and key lists, then checks the result.
+    pub fn apply(&mut self, keys: &HashMap<String, Vec<String>>) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut names: Vec<&String> = keys.keys().collect();
+        names.sort();
+        for name in names {
+            let action = match name.parse::<Action>() {
+                Ok(action) => action,
+                Err(e) => {
+                    errors.push(format!("tui.keys: {}", e));
+                    continue;
+                }
+            };
+            match keys[name].iter().map(|k| k.parse::<Key>()).collect::<Result<Vec<_>, _>>() {
+                Ok(parsed) => self.bind(action, parsed),
+                Err(e) => errors.push(format!("tui.keys.{}: {}", name, e)),
+            }
+        }
+        if errors.is_empty() { self.validate() } else { Err(errors) }
+    }
+
+    /// Fails when one key triggers two actions in the same context.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for context in KeyContext::ALL {
+            let mut seen: HashMap<Key, Action> = HashMap::new();
+            for action in Action::ALL.iter().filter(|a| a.applies_in(context)) {
+                for key in self.keys(*action) {
+                    match seen.get(key) {
+                        Some(other) if other != action => errors.push(format!(
+                            "tui.keys: {} is bound to both {} and {} ({})",
+                            key,
+                            other.name(),
+                            action.name(),
+                            context.title().to_lowercase()
+                        )),
+                        _ => {
+                            seen.insert(*key, *action);
+                        }
+                    }
+                }
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The action `event` triggers in `context`, if any.
+    pub fn action(&self, context: KeyContext, event: &KeyEvent) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .filter(|a| a.applies_in(context))
+            .find(|a| self.keys(*a).iter().any(|k| k.matches(event)))
+    }
+
+    /// `Key: Label` pairs for the bound ones of `actions`, separated like the hint bar.
+    pub fn hints(&self, actions: &[Action]) -> String {
+        let hints: Vec<String> = actions
+            .iter()
+            .filter_map(|a| self.keys(*a).first().map(|k| format!("{}: {}", k, a.label())))
+            .collect();
+        format!(" {} ", hints.join(" \u{2502} "))
+    }
+
+    /// All keys of `action`, e.g. `E, Enter`
+    pub fn describe(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(Key::to_string).collect();
+        keys.join(", ")
+    }
+
+    /// Every action of `context` with its keys (`-` when unbound), for the help overlay.
+    pub fn help(&self, context: KeyContext) -> Vec<(String, Action)> {
+        Action::ALL
+            .iter()
+            .filter(|a| a.applies_in(context))
+            .map(|a| match self.describe(*a) {
+                keys if keys.is_empty() => ("-".to_string(), *a),
+                keys => (keys, *a),
+            })
+            .collect()
+    }
+}
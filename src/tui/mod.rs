@@ -0,0 +1,2377 @@
+//! Terminal UI for the time-based task scheduler using ratatui.
+
+use crate::engine::TimePriorityEngine;
+use crate::events::{JobEventKind, LogLevel, LogSource, SchedulerEvent};
+use crate::history::{HistoryStore, RunOutcome, RunRecord};
+use crate::job::{Job, Status};
+use crate::monitor::ResourceMonitor;
+use crate::queue::QueueManager;
+use crate::schedule::Schedule;
+use crate::tasks::{ParamKind, ParamSpec, params};
+use chrono::{TimeZone, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Margin, Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Sparkline, Wrap},
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
+use std::path::Path;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+pub mod keymap;
+pub mod theme;
+
+pub use keymap::{Action, Key, KeyContext, Keymap, Preset};
+pub use theme::{Theme, ThemeName};
+
+pub const DEFAULT_LOG_LINES: usize = 1000;
+
+/// The `[tui]` section of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TuiConfig {
+    /// Log pane lines kept in memory; the oldest are dropped first
+    pub log_lines: usize,
+    /// Keybindings the `[tui.keys]` overrides start from
+    pub preset: Preset,
+    /// `[tui.keys]`: action name to the keys that trigger it
+    pub keys: HashMap<String, Vec<String>>,
+    /// Select, click and scroll with the mouse
+    pub mouse: bool,
+    /// From `tui.theme` or `SCHEDULER_TUI_THEME`, else monochrome when `NO_COLOR` is set;
+    /// dark when none of them is
+    pub theme: Option<ThemeName>,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            log_lines: DEFAULT_LOG_LINES,
+            preset: Preset::Default,
+            keys: HashMap::new(),
+            mouse: true,
+            theme: None,
+        }
+    }
+}
+
+impl TuiConfig {
+    /// The preset with the `[tui.keys]` overrides applied.
+    pub fn keymap(&self) -> Result<Keymap, Vec<String>> {
+        let mut keymap = Keymap::preset(self.preset);
+        keymap.apply(&self.keys)?;
+        Ok(keymap)
+    }
+}
+
+pub struct AppState {
+    pub queue: Arc<Mutex<QueueManager>>,
+    pub log_rx: Receiver<SchedulerEvent>,
+    pub worker_tx: Sender<Job>,
+    pub monitor: Option<Arc<ResourceMonitor>>,
+    /// Run history, for the last outcome shown in the job details
+    pub history: Option<Arc<Mutex<HistoryStore>>>,
+    /// Engine whose state the dashboard shows
+    pub engine: Option<Arc<TimePriorityEngine>>,
+    /// Worker threads, for the dashboard's occupancy
+    pub pool_size: Option<usize>,
+    /// Running jobs and dispatch lags seen on the event stream
+    pub stats: LiveStats,
+    pub log: LogBuffer,
+    /// Rows of the log pane at the last draw, the PageUp/PageDown step
+    pub log_height: usize,
+    pub list_state: ListState,
+    pub input_mode: InputMode,
+    pub input_buffer: String,
+    pub input_field: InputField,
+    pub message: Option<(String, std::time::Instant)>,
+    pub available_functions: Vec<String>,
+    /// Parameters declared by each function, asked for after the add-task fields
+    pub params: HashMap<String, Vec<ParamSpec>>,
+    pub function_index: usize,
+    /// Job being changed in the edit dialog
+    pub editing: Option<Uuid>,
+    /// Search, filters and sort order of the job list
+    pub view: JobView,
+    /// Window of the timeline view
+    pub timeline: TimelineView,
+    /// Keys of the job list, details, timeline and help
+    pub keymap: Keymap,
+    pub theme: Theme,
+    /// Mouse capture is on
+    mouse: bool,
+    /// Mode the help overlay returns to
+    help_from: InputMode,
+    help_scroll: u16,
+    /// Where the last frame put things, for mouse clicks
+    layout: ScreenLayout,
+}
+
+/// Areas of the last frame the mouse can act on.
+#[derive(Default)]
+struct ScreenLayout {
+    list: Rect,
+    log: Rect,
+    /// Fields of the open form and their index
+    fields: Vec<(Rect, usize)>,
+    /// Buttons and the key a click stands for
+    buttons: Vec<(Rect, KeyEvent)>,
+}
+
+#[derive(Clone, Copy, Default)]
+pub enum InputMode {
+    #[default]
+    Normal,
+    AddTask,
+    /// Task-specific parameters of the job being added or edited
+    Params,
+    /// Settings of an existing job
+    EditJob,
+    /// Details of the selected job
+    Detail,
+    /// Typing the job list search
+    Search,
+    /// Upcoming jobs and recent runs on a time axis
+    Timeline,
+    /// Keys of the active keymap
+    Help,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Next due first, higher priority first on ties (the dispatch order)
+    #[default]
+    Time,
+    /// Highest priority first, then by time
+    Priority,
+    /// By function name, then by time
+    Function,
+}
+
+impl SortOrder {
+    pub fn next(self) -> Self {
+        match self {
+            SortOrder::Time => SortOrder::Priority,
+            SortOrder::Priority => SortOrder::Function,
+            SortOrder::Function => SortOrder::Time,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortOrder::Time => "time",
+            SortOrder::Priority => "priority",
+            SortOrder::Function => "function",
+        }
+    }
+}
+
+/// Which queued jobs the list shows, and in what order.
+#[derive(Clone, Debug, Default)]
+pub struct JobView {
+    /// Case-insensitive words that must all occur in the description, function or payload
+    pub query: String,
+    pub status: Option<Status>,
+    pub function: Option<String>,
+    pub tag: Option<String>,
+    pub sort: SortOrder,
+}
+
+impl JobView {
+    pub fn is_filtered(&self) -> bool {
+        !self.query.trim().is_empty() || self.status.is_some() || self.function.is_some() || self.tag.is_some()
+    }
+
+    pub fn matches(&self, job: &Job) -> bool {
+        if self.status.as_ref().is_some_and(|s| *s != job.status)
+            || self.function.as_ref().is_some_and(|f| *f != job.function)
+            || self.tag.as_ref().is_some_and(|t| !job.has_tag(t))
+        {
+            return false;
+        }
+        let mut haystack = format!("{}\n{}", job.description, job.function).to_lowercase();
+        for (key, value) in job.metadata.iter().flatten() {
+            haystack.push('\n');
+            haystack.push_str(&key.to_lowercase());
+            // Secret values are not searchable, or a match would give them away
+            if !params::is_secret(&[], key) {
+                haystack.push('=');
+                haystack.push_str(&value.to_lowercase());
+            }
+        }
+        self.query.to_lowercase().split_whitespace().all(|word| haystack.contains(word))
+    }
+
+    /// Filters `jobs` (as returned by `QueueManager::snapshot`, in time order) and sorts them.
+    pub fn apply(&self, mut jobs: Vec<Job>) -> Vec<Job> {
+        jobs.retain(|j| self.matches(j));
+        match self.sort {
+            SortOrder::Time => {}
+            SortOrder::Priority => jobs.sort_by_key(|j| std::cmp::Reverse(j.priority)),
+            SortOrder::Function => jobs.sort_by(|a, b| a.function.cmp(&b.function)),
+        }
+        jobs
+    }
+
+    /// Active search, filters and sort order for the list title, empty by default.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.query.trim().is_empty() {
+            parts.push(format!("/{}", self.query.trim()));
+        }
+        if let Some(status) = &self.status {
+            parts.push(format!("status {:?}", status));
+        }
+        if let Some(function) = &self.function {
+            parts.push(format!("function {}", function));
+        }
+        if let Some(tag) = &self.tag {
+            parts.push(format!("tag {}", tag));
+        }
+        if self.sort != SortOrder::default() {
+            parts.push(format!("by {}", self.sort.label()));
+        }
+        parts.iter().map(|p| format!(" \u{2502} {}", p)).collect()
+    }
+}
+
+/// Widths of the timeline window, from minutes to days
+pub const TIMELINE_ZOOM: [(i64, &str); 7] = [
+    (300, "5m"),
+    (900, "15m"),
+    (3600, "1h"),
+    (6 * 3600, "6h"),
+    (86400, "1d"),
+    (3 * 86400, "3d"),
+    (7 * 86400, "7d"),
+];
+
+/// Distances between timeline ticks, the smallest that leaves room for the labels is used
+const TIMELINE_TICKS: [i64; 12] = [60, 300, 600, 900, 1800, 3600, 3 * 3600, 6 * 3600, 12 * 3600, 86400, 2 * 86400, 7 * 86400];
+
+/// Time window shown by the timeline. Unless panned it moves along with the clock,
+/// with a quarter of it in the past for the recent runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelineView {
+    /// Index into `TIMELINE_ZOOM`
+    pub zoom: usize,
+    /// Seconds panned away from the default window
+    pub offset: i64,
+}
+
+impl Default for TimelineView {
+    fn default() -> Self {
+        Self { zoom: 2, offset: 0 }
+    }
+}
+
+impl TimelineView {
+    /// Width of the window in seconds
+    pub fn span(&self) -> i64 {
+        TIMELINE_ZOOM[self.zoom].0
+    }
+
+    pub fn start(&self, now: i64) -> i64 {
+        now - self.span() / 4 + self.offset
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.rezoom(self.zoom.saturating_sub(1));
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.rezoom((self.zoom + 1).min(TIMELINE_ZOOM.len() - 1));
+    }
+
+    /// Keeps the middle of the window in place while its width changes.
+    fn rezoom(&mut self, zoom: usize) {
+        let middle = self.offset - self.span() / 4 + self.span() / 2;
+        self.zoom = zoom;
+        self.offset = middle + self.span() / 4 - self.span() / 2;
+    }
+
+    /// Moves the window by a quarter of its width.
+    pub fn pan(&mut self, forward: bool) {
+        let step = self.span() / 4;
+        self.offset += if forward { step } else { -step };
+    }
+
+    /// Column of `time` on an axis `width` columns wide, if it is inside the window.
+    pub fn column(&self, now: i64, width: usize, time: i64) -> Option<usize> {
+        let start = self.start(now);
+        if width == 0 || time < start || time >= start + self.span() {
+            return None;
+        }
+        Some(((time - start) as i128 * width as i128 / self.span() as i128) as usize)
+    }
+
+    /// Seconds between labelled ticks on an axis `width` columns wide.
+    pub fn tick_step(&self, width: usize) -> i64 {
+        // Labels like "12-31 23:00" need about a dozen columns each
+        let max_ticks = (width / 12).max(1) as i64;
+        TIMELINE_TICKS
+            .iter()
+            .copied()
+            .find(|step| self.span() / step <= max_ticks)
+            .unwrap_or(TIMELINE_TICKS[TIMELINE_TICKS.len() - 1])
+    }
+}
+
+/// Seconds of history the dashboard's rates and average lag cover
+pub const STATS_WINDOW_SECS: i64 = 3600;
+
+/// What the dashboard needs from the event stream, which the queue and history don't keep.
+#[derive(Debug, Default)]
+pub struct LiveStats {
+    /// Jobs a worker started and has not finished yet
+    running: HashSet<Uuid>,
+    /// When each recent job was dispatched and how late (seconds)
+    lags: VecDeque<(i64, i64)>,
+}
+
+impl LiveStats {
+    pub fn observe(&mut self, event: &SchedulerEvent) {
+        let SchedulerEvent::Job(e) = event else {
+            return;
+        };
+        match e.kind {
+            JobEventKind::Started { .. } => {
+                self.running.insert(e.job_id);
+            }
+            JobEventKind::Succeeded { .. } | JobEventKind::Failed { .. } => {
+                self.running.remove(&e.job_id);
+            }
+            JobEventKind::Dispatched { lag_secs } => {
+                self.lags.push_back((e.at, lag_secs.max(0)));
+                while self.lags.front().is_some_and(|(at, _)| *at < e.at - STATS_WINDOW_SECS) {
+                    self.lags.pop_front();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn running(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Mean dispatch lag over the last `STATS_WINDOW_SECS`, None without dispatches
+    pub fn average_lag(&self, now: i64) -> Option<f64> {
+        let recent: Vec<i64> = self
+            .lags
+            .iter()
+            .filter(|(at, _)| *at >= now - STATS_WINDOW_SECS)
+            .map(|(_, lag)| *lag)
+            .collect();
+        (!recent.is_empty()).then(|| recent.iter().sum::<i64>() as f64 / recent.len() as f64)
+    }
+}
+
+/// Figures shown in the dashboard header.
+#[derive(Debug, Default)]
+struct Dashboard {
+    queued: usize,
+    paused: usize,
+    due_minute: usize,
+    due_hour: usize,
+    running: usize,
+    succeeded: usize,
+    failed: usize,
+    average_lag: Option<f64>,
+}
+
+/// One line of the log pane.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// Unix seconds when the TUI received the event
+    pub at: i64,
+    pub event: SchedulerEvent,
+}
+
+impl LogEntry {
+    /// `HH:MM:SS [Source] message` (UTC)
+    pub fn line(&self) -> String {
+        let time = Utc
+            .timestamp_opt(self.at, 0)
+            .single()
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        format!("{} {}", time, self.event)
+    }
+}
+
+/// Which events the log pane shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    pub source: Option<LogSource>,
+    pub job: Option<Uuid>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, event: &SchedulerEvent) -> bool {
+        self.source.is_none_or(|s| s == event.source()) && self.job.is_none_or(|id| event.job_id() == Some(id))
+    }
+
+    /// Active filters for the pane title, empty when everything is shown.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(source) = self.source {
+            parts.push(source.to_string());
+        }
+        if let Some(id) = self.job {
+            parts.push(format!("job {}", &id.to_string()[..8]));
+        }
+        parts.iter().map(|p| format!(" \u{2502} {}", p)).collect()
+    }
+}
+
+/// Ring buffer behind the log pane, with a scroll position that stays on the same
+/// lines while new ones arrive unless it is following the end.
+#[derive(Debug)]
+pub struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    filter: LogFilter,
+    /// Matching lines below the bottom of the view; 0 follows new lines
+    scroll: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_LOG_LINES)),
+            capacity,
+            filter: LogFilter::default(),
+            scroll: 0,
+        }
+    }
+
+    pub fn push(&mut self, event: SchedulerEvent) {
+        if self.entries.len() == self.capacity
+            && let Some(dropped) = self.entries.pop_front()
+            && self.filter.matches(&dropped.event)
+        {
+            // The dropped line was above the view; only the total shrinks
+            self.scroll = self.scroll.min(self.visible_len().saturating_sub(1));
+        }
+        if self.scroll > 0 && self.filter.matches(&event) {
+            self.scroll += 1;
+        }
+        self.entries.push_back(LogEntry { at: Job::now(), event });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn filter(&self) -> LogFilter {
+        self.filter
+    }
+
+    /// Changes the filter and jumps back to following the end.
+    pub fn set_filter(&mut self, filter: LogFilter) {
+        self.filter = filter;
+        self.scroll = 0;
+    }
+
+    /// Lines passing the filter, oldest first
+    pub fn visible(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.entries.iter().filter(|e| self.filter.matches(&e.event))
+    }
+
+    fn visible_len(&self) -> usize {
+        self.visible().count()
+    }
+
+    /// Matching lines hidden below the view
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.scroll == 0
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.visible_len().saturating_sub(1));
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = self.visible_len().saturating_sub(1);
+    }
+
+    pub fn follow(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// Writes the lines passing the filter to `path`, returning how many.
+    pub fn export(&self, path: &Path) -> std::io::Result<usize> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut lines = 0;
+        for entry in self.visible() {
+            writeln!(file, "{}", entry.line())?;
+            lines += 1;
+        }
+        file.flush()?;
+        Ok(lines)
+    }
+}
+
+/// Steps a filter through "all" (None) and each of `options` in turn.
+fn cycle_filter<T: Clone + PartialEq>(current: &Option<T>, options: &[T]) -> Option<T> {
+    match current.as_ref().and_then(|c| options.iter().position(|o| o == c)) {
+        Some(i) => options.get(i + 1).cloned(),
+        None => options.first().cloned(),
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub enum InputField {
+    #[default]
+    Time,
+    Priority,
+    Description,
+    Function,
+}
+
+/// Temporary state for the "add task" form (one set per submission).
+#[derive(Default)]
+pub struct AddTaskForm {
+    pub time: String,
+    pub priority: String,
+    pub description: String,
+    pub function: String,
+    /// Job being duplicated: its retries, tags, schedule and other payload carry over
+    pub template: Option<Job>,
+}
+
+/// Form generated from the chosen function's parameters, pre-filled with defaults.
+#[derive(Default)]
+pub struct ParamForm {
+    pub specs: Vec<ParamSpec>,
+    pub values: Vec<String>,
+    pub active: usize,
+    /// Problem with each value, filled in when the form is submitted
+    pub errors: Vec<Option<String>>,
+}
+
+impl ParamForm {
+    pub fn new(specs: Vec<ParamSpec>) -> Self {
+        let values = specs.iter().map(|s| s.default.clone().unwrap_or_default()).collect();
+        let errors = vec![None; specs.len()];
+        Self {
+            specs,
+            values,
+            active: 0,
+            errors,
+        }
+    }
+
+    fn next(&mut self) {
+        self.active = (self.active + 1) % self.specs.len().max(1);
+    }
+
+    fn prev(&mut self) {
+        let len = self.specs.len().max(1);
+        self.active = (self.active + len - 1) % len;
+    }
+
+    fn active_spec(&self) -> Option<&ParamSpec> {
+        self.specs.get(self.active)
+    }
+
+    /// Bool and choice fields are changed with ←/→ instead of typing
+    fn has_options(&self) -> bool {
+        self.active_spec().is_some_and(|s| !s.options().is_empty())
+    }
+
+    fn is_multiline(&self) -> bool {
+        self.active_spec().is_some_and(|s| s.kind == ParamKind::Multiline)
+    }
+
+    /// Replaces the values with `values`, by payload key; missing keys become empty.
+    fn prefill(&mut self, values: &HashMap<String, String>) {
+        self.values = self
+            .specs
+            .iter()
+            .map(|s| values.get(&s.name).cloned().unwrap_or_default())
+            .collect();
+    }
+
+    /// Marks field `index` as invalid and moves to it; None for early returns.
+    fn fail<T>(&mut self, index: usize, error: String) -> Option<T> {
+        self.errors[index] = Some(error);
+        self.active = index;
+        None
+    }
+
+    fn edit(&mut self, change: impl FnOnce(&mut String)) {
+        if let Some(value) = self.values.get_mut(self.active) {
+            change(value);
+            self.errors[self.active] = None;
+        }
+    }
+
+    /// Moves to the next (or previous) option; an empty value starts at the first.
+    fn cycle(&mut self, forward: bool) {
+        let Some(options) = self.active_spec().map(ParamSpec::options).filter(|o| !o.is_empty()) else {
+            return;
+        };
+        self.edit(|value| {
+            let len = options.len();
+            let next = match options.iter().position(|o| o.eq_ignore_ascii_case(value)) {
+                Some(i) if forward => (i + 1) % len,
+                Some(i) => (i + len - 1) % len,
+                None => 0,
+            };
+            *value = options[next].clone();
+        });
+    }
+
+    /// The job's payload, or None after marking the invalid fields and moving to the first.
+    fn metadata(&mut self) -> Option<HashMap<String, String>> {
+        self.errors = self
+            .specs
+            .iter()
+            .zip(&self.values)
+            .map(|(spec, value)| spec.check(value).err())
+            .collect();
+        if let Some(first) = self.errors.iter().position(Option::is_some) {
+            self.active = first;
+            return None;
+        }
+        let values = self
+            .specs
+            .iter()
+            .map(|s| s.name.clone())
+            .zip(self.values.iter().cloned())
+            .collect();
+        params::validate(&self.specs, &values).ok()
+    }
+}
+
+/// Field navigation and editing shared by every form; false if `key` is not one of them.
+fn handle_form_key(form: &mut ParamForm, key: &event::KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Tab | KeyCode::Down => form.next(),
+        KeyCode::BackTab | KeyCode::Up => form.prev(),
+        KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) && form.is_multiline() => {
+            form.edit(|value| value.push('\n'))
+        }
+        KeyCode::Left if form.has_options() => form.cycle(false),
+        KeyCode::Right | KeyCode::Char(' ') if form.has_options() => form.cycle(true),
+        KeyCode::Backspace if !form.has_options() => form.edit(|value| {
+            value.pop();
+        }),
+        KeyCode::Char(c) if !form.has_options() => form.edit(|value| value.push(c)),
+        _ => return false,
+    }
+    true
+}
+
+/// Positions of the edit dialog's fields
+const EDIT_TIME: usize = 0;
+const EDIT_PRIORITY: usize = 1;
+const EDIT_RETRIES: usize = 4;
+const EDIT_PAYLOAD: usize = 5;
+
+/// Validated first page of the edit dialog, kept while the parameters are entered.
+struct JobEdit {
+    execution_time: i64,
+    priority: u8,
+    description: String,
+    function: String,
+    max_retries: u32,
+    /// Payload keys the function does not declare
+    extra: HashMap<String, String>,
+}
+
+/// Undeclared payload entries as `KEY=value` lines, with `\` and newlines escaped.
+fn format_payload(payload: &HashMap<String, String>) -> String {
+    let mut lines: Vec<String> = payload
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value.replace('\\', "\\\\").replace('\n', "\\n")))
+        .collect();
+    lines.sort();
+    lines.join("\n")
+}
+
+fn parse_payload(text: &str) -> Result<HashMap<String, String>, String> {
+    let mut payload = HashMap::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("Payload line '{}' must look like KEY=value", line));
+        };
+        let mut unescaped = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some('n')) => {
+                    unescaped.push('\n');
+                    chars.next();
+                }
+                ('\\', Some('\\')) => {
+                    unescaped.push('\\');
+                    chars.next();
+                }
+                _ => unescaped.push(c),
+            }
+        }
+        payload.insert(key.trim().to_string(), unescaped);
+    }
+    Ok(payload)
+}
+
+impl AppState {
+    pub fn new(
+        queue: Arc<Mutex<QueueManager>>,
+        log_rx: Receiver<SchedulerEvent>,
+        worker_tx: Sender<Job>,
+        params: HashMap<String, Vec<ParamSpec>>,
+        monitor: Option<Arc<ResourceMonitor>>,
+        history: Option<Arc<Mutex<HistoryStore>>>,
+    ) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        let mut available_functions: Vec<String> = params.keys().cloned().collect();
+        available_functions.sort();
+        Self {
+            queue,
+            log_rx,
+            worker_tx,
+            monitor,
+            history,
+            engine: None,
+            pool_size: None,
+            stats: LiveStats::default(),
+            log: LogBuffer::new(DEFAULT_LOG_LINES),
+            log_height: 10,
+            list_state,
+            input_mode: InputMode::Normal,
+            input_buffer: String::new(),
+            input_field: InputField::Time,
+            message: None,
+            available_functions,
+            params,
+            function_index: 0,
+            editing: None,
+            view: JobView::default(),
+            timeline: TimelineView::default(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            mouse: false,
+            help_from: InputMode::Normal,
+            help_scroll: 0,
+            layout: ScreenLayout::default(),
+        }
+    }
+
+    fn drain_log(&mut self) {
+        while let Ok(event) = self.log_rx.try_recv() {
+            self.stats.observe(&event);
+            self.log.push(event);
+        }
+    }
+
+    pub fn set_engine(&mut self, engine: Arc<TimePriorityEngine>) {
+        self.engine = Some(engine);
+    }
+
+    pub fn set_pool_size(&mut self, pool_size: usize) {
+        self.pool_size = Some(pool_size);
+    }
+
+    /// Figures for the dashboard header, as of `now`.
+    fn dashboard(&self, now: i64) -> Dashboard {
+        let mut dashboard = Dashboard {
+            running: self.stats.running(),
+            average_lag: self.stats.average_lag(now),
+            ..Dashboard::default()
+        };
+        if let Ok(q) = self.queue.lock() {
+            for job in q.snapshot() {
+                dashboard.queued += 1;
+                if job.status == Status::Paused {
+                    dashboard.paused += 1;
+                } else if job.execution_time <= now + 60 {
+                    dashboard.due_minute += 1;
+                    dashboard.due_hour += 1;
+                } else if job.execution_time <= now + 3600 {
+                    dashboard.due_hour += 1;
+                }
+            }
+        }
+        if let Some(history) = self.history.as_ref().and_then(|h| h.lock().ok()) {
+            for run in history.between(now - STATS_WINDOW_SECS, now + 1) {
+                match run.outcome {
+                    RunOutcome::Success => dashboard.succeeded += 1,
+                    RunOutcome::Failed => dashboard.failed += 1,
+                }
+            }
+        }
+        dashboard
+    }
+
+    /// Narrows the log to the selected job, or shows all jobs again.
+    fn toggle_log_job_filter(&mut self) {
+        let mut filter = self.log.filter();
+        filter.job = match filter.job {
+            Some(_) => None,
+            None => match self.selected_job() {
+                Some(job) => Some(job.id),
+                None => {
+                    self.message = Some(("No job selected.".to_string(), std::time::Instant::now()));
+                    return;
+                }
+            },
+        };
+        self.log.set_filter(filter);
+    }
+
+    fn cycle_log_source(&mut self) {
+        let mut filter = self.log.filter();
+        let sources = [LogSource::Queue, LogSource::Engine, LogSource::Worker, LogSource::Task, LogSource::System];
+        filter.source = cycle_filter(&filter.source, &sources);
+        self.log.set_filter(filter);
+    }
+
+    /// Writes the log lines passing the current filter to a timestamped file.
+    fn export_log(&mut self) {
+        let path = format!("scheduler-log-{}.txt", Utc::now().format("%Y%m%d-%H%M%S"));
+        let message = match self.log.export(Path::new(&path)) {
+            Ok(lines) => format!("Exported {} log line(s) to {}.", lines, path),
+            Err(e) => format!("Could not export the log to {}: {}", path, e),
+        };
+        self.message = Some((message, std::time::Instant::now()));
+    }
+
+    fn pending_jobs(&mut self) -> Vec<Job> {
+        if let Ok(q) = self.queue.lock() {
+            q.snapshot()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// The job list as shown: searched, filtered and sorted by `view`.
+    fn visible_jobs(&mut self) -> Vec<Job> {
+        let jobs = self.pending_jobs();
+        self.view.apply(jobs)
+    }
+
+    /// Moves a filter on to its next value among the queued jobs and says which it is.
+    fn cycle_filter(&mut self, filter: char) {
+        let jobs = self.pending_jobs();
+        let distinct = |values: Vec<String>| {
+            let mut values = values;
+            values.sort();
+            values.dedup();
+            values
+        };
+        let shown = match filter {
+            'f' => {
+                let functions = distinct(jobs.iter().map(|j| j.function.clone()).collect());
+                self.view.function = cycle_filter(&self.view.function, &functions);
+                self.view.function.clone()
+            }
+            't' => {
+                let tags = distinct(jobs.iter().flat_map(|j| j.tags.iter().map(|t| t.to_lowercase())).collect());
+                self.view.tag = cycle_filter(&self.view.tag, &tags);
+                self.view.tag.clone()
+            }
+            _ => {
+                self.view.status = cycle_filter(&self.view.status, &[Status::Pending, Status::Paused]);
+                self.view.status.as_ref().map(|s| format!("{:?}", s))
+            }
+        };
+        let name = match filter {
+            'f' => "Function",
+            't' => "Tag",
+            _ => "Status",
+        };
+        self.list_state.select(Some(0));
+        self.message = Some((
+            format!("{} filter: {}", name, shown.as_deref().unwrap_or("all")),
+            std::time::Instant::now(),
+        ));
+    }
+
+    fn selected_job_id(&self, jobs: &[Job]) -> Option<Uuid> {
+        let i = self.list_state.selected()?;
+        jobs.get(i).map(|j| j.id)
+    }
+
+    fn selected_job(&mut self) -> Option<Job> {
+        let i = self.list_state.selected()?;
+        self.visible_jobs().into_iter().nth(i)
+    }
+
+    fn move_selection(&mut self, forward: bool) {
+        let len = self.visible_jobs().len();
+        if len > 0 {
+            let i = self.list_state.selected().unwrap_or(0);
+            let next = if forward { (i + 1) % len } else { (i + len - 1) % len };
+            self.list_state.select(Some(next));
+        }
+    }
+
+    /// Opens the edit dialog for the selected job, returning its first page.
+    fn start_edit(&mut self) -> Option<ParamForm> {
+        let Some(job) = self.selected_job() else {
+            self.message = Some(("No job selected.".to_string(), std::time::Instant::now()));
+            return None;
+        };
+        self.editing = Some(job.id);
+        self.input_mode = InputMode::EditJob;
+        Some(self.edit_form(&job))
+    }
+
+    fn open_details(&mut self) {
+        if self.selected_job().is_some() {
+            self.input_mode = InputMode::Detail;
+        } else {
+            self.message = Some(("No job selected.".to_string(), std::time::Instant::now()));
+        }
+    }
+
+    /// Opens the help overlay over the current view, or closes it again.
+    fn toggle_help(&mut self) {
+        if matches!(self.input_mode, InputMode::Help) {
+            self.input_mode = self.help_from;
+        } else {
+            self.help_from = self.input_mode;
+            self.help_scroll = 0;
+            self.input_mode = InputMode::Help;
+        }
+    }
+
+    /// Clears the search and the list and log filters; false when none were set.
+    fn clear_filters(&mut self) -> bool {
+        if !self.view.is_filtered() && self.log.filter() == LogFilter::default() {
+            return false;
+        }
+        self.view = JobView { sort: self.view.sort, ..JobView::default() };
+        self.log.set_filter(LogFilter::default());
+        self.list_state.select(Some(0));
+        self.message = Some(("Filters cleared.".to_string(), std::time::Instant::now()));
+        true
+    }
+
+    /// Selects clicked jobs, focuses clicked form fields and scrolls the pane under the
+    /// wheel. A click on a button comes back as the key it stands for, to be handled
+    /// like that key press.
+    fn mouse(&mut self, mouse: MouseEvent, form: Option<&mut ParamForm>) -> Option<KeyEvent> {
+        let at = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some((_, key)) = self.layout.buttons.iter().find(|(r, _)| r.contains(at)) {
+                    return Some(*key);
+                }
+                if let Some(form) = form {
+                    if let Some((_, i)) = self.layout.fields.iter().find(|(r, _)| r.contains(at)) {
+                        form.active = *i;
+                    }
+                    return None;
+                }
+                let rows = self.layout.list.inner(Margin::new(1, 1));
+                if matches!(self.input_mode, InputMode::Normal | InputMode::Search) && rows.contains(at) {
+                    let index = self.list_state.offset() + (at.y - rows.y) as usize;
+                    if index < self.visible_jobs().len() {
+                        // A click on the selected job shows its details
+                        if self.list_state.selected() == Some(index) && matches!(self.input_mode, InputMode::Normal) {
+                            self.open_details();
+                        }
+                        self.list_state.select(Some(index));
+                    }
+                }
+                None
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let down = mouse.kind == MouseEventKind::ScrollDown;
+                match self.input_mode {
+                    InputMode::Help if down => self.help_scroll = self.help_scroll.saturating_add(3),
+                    InputMode::Help => self.help_scroll = self.help_scroll.saturating_sub(3),
+                    InputMode::Timeline => self.timeline.pan(down),
+                    InputMode::Detail => self.move_selection(down),
+                    InputMode::Params | InputMode::EditJob => match form {
+                        Some(form) if down => form.next(),
+                        Some(form) => form.prev(),
+                        None => {}
+                    },
+                    InputMode::Normal | InputMode::Search if self.layout.log.contains(at) => {
+                        if down {
+                            self.log.scroll_down(3);
+                        } else {
+                            self.log.scroll_up(3);
+                        }
+                    }
+                    InputMode::Normal | InputMode::Search if self.layout.list.contains(at) => self.move_selection(down),
+                    _ => {}
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Latest finished attempt of `job`. Recurring jobs get a new id for every
+    /// run, so for them an earlier run of the same job counts too.
+    fn last_run(&self, job: &Job) -> Option<RunRecord> {
+        let history = self.history.as_ref()?.lock().ok()?;
+        history.latest(|r| r.job_id == job.id).or_else(|| {
+            job.schedule.as_ref()?;
+            history.latest(|r| r.function == job.function && r.description == job.description)
+        })
+    }
+
+    /// Dispatches a copy of the selected job right away; the queued job keeps its
+    /// execution time and schedule, like `scheduler run-now`.
+    fn run_selected_now(&mut self) {
+        let Some(job) = self.selected_job() else {
+            self.message = Some(("No job selected.".to_string(), std::time::Instant::now()));
+            return;
+        };
+        let message = match self.worker_tx.send(job.run_now_copy()) {
+            Ok(()) => format!("Running '{}' now; it stays queued for its scheduled time.", job.description),
+            Err(e) => format!("Dispatch error: {}", e),
+        };
+        self.message = Some((message, std::time::Instant::now()));
+    }
+
+    /// Opens the add form pre-filled from the selected job.
+    fn duplicate_selected(&mut self) -> Option<AddTaskForm> {
+        let Some(job) = self.selected_job() else {
+            self.message = Some(("No job selected.".to_string(), std::time::Instant::now()));
+            return None;
+        };
+        let form = AddTaskForm {
+            // An overdue time would be rejected, so the user picks a new one
+            time: if job.execution_time > Job::now() { job.execution_time.to_string() } else { String::new() },
+            priority: job.priority.to_string(),
+            description: job.description.clone(),
+            function: job.function.clone(),
+            template: Some(job),
+        };
+        if let Some(i) = self.available_functions.iter().position(|f| *f == form.function) {
+            self.function_index = i;
+        }
+        self.input_mode = InputMode::AddTask;
+        self.input_field = InputField::Time;
+        self.input_buffer = form.time.clone();
+        Some(form)
+    }
+
+    /// Puts the selected job's id on the clipboard through the terminal (OSC 52).
+    fn copy_selected_id(&mut self) {
+        let Some(job) = self.selected_job() else {
+            self.message = Some(("No job selected.".to_string(), std::time::Instant::now()));
+            return;
+        };
+        let id = job.id.to_string();
+        let message = match copy_to_clipboard(&id) {
+            Ok(()) => format!("Copied {} to the clipboard.", id),
+            Err(e) => format!("Could not copy {}: {}", id, e),
+        };
+        self.message = Some((message, std::time::Instant::now()));
+    }
+
+    fn remove_selected(&mut self) {
+        let jobs = self.visible_jobs();
+        if let Some(id) = self.selected_job_id(&jobs) {
+            if let Ok(mut q) = self.queue.lock() {
+                q.remove(id);
+                self.message = Some(("Job removed.".to_string(), std::time::Instant::now()));
+            }
+            if let Some(sel) = self.list_state.selected() {
+                let len = jobs.len();
+                if len <= 1 {
+                    self.list_state.select(None);
+                } else {
+                    self.list_state
+                        .select(Some((sel + len - 1) % (len - 1).max(1)));
+                }
+            }
+        } else {
+            self.message = Some(("No job selected.".to_string(), std::time::Instant::now()));
+        }
+    }
+
+    /// First page of the edit dialog: the job's settings and its undeclared payload.
+    fn edit_form(&self, job: &Job) -> ParamForm {
+        let mut functions = self.available_functions.clone();
+        if !functions.contains(&job.function) {
+            functions.push(job.function.clone());
+        }
+        let functions: Vec<&str> = functions.iter().map(String::as_str).collect();
+        let when = Utc
+            .timestamp_opt(job.execution_time, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_default();
+        let mut form = ParamForm::new(vec![
+            ParamSpec::text("time", "Time")
+                .required()
+                .help(&format!("Unix sec or secs from now; now {}", when)),
+            ParamSpec::integer("priority", "Priority (0-255)").required(),
+            ParamSpec::text("description", "Description").required(),
+            ParamSpec::choice("function", "Function", &functions),
+            ParamSpec::integer("max_retries", "Retries").required(),
+            ParamSpec::multiline("payload", "Other payload")
+                .help("KEY=value per line (Alt+Enter); the function's parameters come next"),
+        ]);
+        let declared = self.params.get(&job.function).cloned().unwrap_or_default();
+        let extra: HashMap<String, String> = job
+            .metadata
+            .iter()
+            .flatten()
+            .filter(|(key, _)| !declared.iter().any(|s| &s.name == *key))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        form.values = vec![
+            job.execution_time.to_string(),
+            job.priority.to_string(),
+            job.description.clone(),
+            job.function.clone(),
+            job.max_retries.to_string(),
+            format_payload(&extra),
+        ];
+        form
+    }
+
+    /// Checks the first page of the edit dialog, marking the first bad field.
+    fn parse_edit(&mut self, form: &mut ParamForm) -> Option<JobEdit> {
+        let values = form.metadata()?;
+        let value = |key: &str| values.get(key).cloned().unwrap_or_default();
+        let execution_time = Job::parse_execution_time(&value("time"));
+        let priority = value("priority").parse::<u8>();
+        let max_retries = value("max_retries").parse::<u32>();
+        let extra = parse_payload(&value("payload"));
+        let (execution_time, priority, max_retries, extra) = match (execution_time, priority, max_retries, extra) {
+            (Ok(t), Ok(p), Ok(r), Ok(extra)) => (t, p, r, extra),
+            (Err(e), ..) => return form.fail(EDIT_TIME, e),
+            (_, Err(_), ..) => return form.fail(EDIT_PRIORITY, "Priority must be 0\u{2013}255.".to_string()),
+            (_, _, Err(_), _) => return form.fail(EDIT_RETRIES, "Retries must not be negative.".to_string()),
+            (.., Err(e)) => return form.fail(EDIT_PAYLOAD, e),
+        };
+        Some(JobEdit {
+            execution_time,
+            priority,
+            description: value("description"),
+            function: value("function"),
+            max_retries,
+            extra,
+        })
+    }
+
+    /// Applies the edit to the queued job (which re-keys and persists it).
+    /// `params` are the values for the parameters the new function declares.
+    fn save_edit(&mut self, edit: &JobEdit, params: HashMap<String, String>) {
+        let Some(id) = self.editing.take() else {
+            return;
+        };
+        let declared = self.params.get(&edit.function).cloned().unwrap_or_default();
+        let mut metadata = edit.extra.clone();
+        metadata.retain(|key, _| !declared.iter().any(|s| &s.name == key));
+        metadata.extend(params);
+
+        let saved = match self.queue.lock() {
+            Ok(mut q) => match q.get(id).cloned() {
+                Some(mut job) => {
+                    job.execution_time = edit.execution_time;
+                    job.priority = edit.priority;
+                    job.description = edit.description.clone();
+                    job.function = edit.function.clone();
+                    job.max_retries = edit.max_retries;
+                    job.metadata = (!metadata.is_empty()).then_some(metadata);
+                    q.update(job)
+                }
+                None => false,
+            },
+            Err(_) => false,
+        };
+        let message = if saved {
+            "Job updated."
+        } else {
+            "Job is no longer queued (already dispatched or removed)."
+        };
+        self.message = Some((message.to_string(), std::time::Instant::now()));
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn submit_add_task(&mut self, form: &AddTaskForm, metadata: Option<HashMap<String, String>>) -> bool {
+        let time_str = form.time.trim();
+        let priority_str = form.priority.trim();
+        let desc = form.description.trim().to_string();
+        let func = form.function.trim().to_string();
+
+        if time_str.is_empty() || priority_str.is_empty() || desc.is_empty() || func.is_empty() {
+            self.message = Some((
+                "All fields required.".to_string(),
+                std::time::Instant::now(),
+            ));
+            return false;
+        }
+
+        let execution_time = match Job::parse_execution_time(time_str) {
+            Ok(t) => t,
+            Err(e) => {
+                self.message = Some((e, std::time::Instant::now()));
+                return false;
+            }
+        };
+        let priority: u8 = match priority_str.parse() {
+            Ok(p) => p,
+            Err(_) => {
+                self.message = Some((
+                    "Priority must be 0\u{2013}255.".to_string(),
+                    std::time::Instant::now(),
+                ));
+                return false;
+            }
+        };
+
+        let max_retries = form.template.as_ref().map_or(3, |t| t.max_retries);
+        match Job::new(execution_time, priority, desc, func, max_retries) {
+            Ok(mut job) => {
+                job.metadata = metadata;
+                if let Some(template) = &form.template {
+                    // Keep the payload keys the (possibly changed) function does not ask for
+                    let declared = self.params.get(&job.function).cloned().unwrap_or_default();
+                    let mut merged: HashMap<String, String> = template
+                        .metadata
+                        .iter()
+                        .flatten()
+                        .filter(|(key, _)| !declared.iter().any(|s| &s.name == *key))
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .collect();
+                    merged.extend(job.metadata.take().unwrap_or_default());
+                    job.metadata = (!merged.is_empty()).then_some(merged);
+                    job.tags = template.tags.clone();
+                    job.schedule = template.schedule.clone();
+                }
+                if let Ok(mut q) = self.queue.lock() {
+                    q.push(job);
+                    self.message = Some(("Job added.".to_string(), std::time::Instant::now()));
+                }
+                self.input_mode = InputMode::Normal;
+                true
+            }
+            Err(e) => {
+                self.message = Some((e.to_string(), std::time::Instant::now()));
+                false
+            }
+        }
+    }
+}
+
+pub fn run_tui(mut app: AppState, config: &TuiConfig) -> std::io::Result<()> {
+    use crossterm::terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    };
+    use ratatui::Terminal;
+    use ratatui::backend::CrosstermBackend;
+
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    crossterm::execute!(stdout, EnterAlternateScreen, crossterm::event::EnableBracketedPaste)?;
+    if config.mouse {
+        crossterm::execute!(stdout, crossterm::event::EnableMouseCapture)?;
+    }
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    app.log = LogBuffer::new(config.log_lines);
+    // Config::load has already reported a broken keymap
+    app.keymap = config.keymap().unwrap_or_default();
+    app.mouse = config.mouse;
+    app.theme = Theme::new(config.theme.unwrap_or_default());
+    let mut form = AddTaskForm::default();
+    let mut param_form = ParamForm::default();
+    let mut edit_form = ParamForm::default();
+    let mut pending_edit: Option<JobEdit> = None;
+
+    loop {
+        app.drain_log();
+        terminal.draw(|f| ui(f, &mut app, &form, &param_form, &edit_form))?;
+
+        if event::poll(Duration::from_millis(100))? {
+            let ev = event::read()?;
+
+            // Handle pasted text
+            if let Event::Paste(text) = &ev {
+                let target = match app.input_mode {
+                    InputMode::Params => Some(&mut param_form),
+                    InputMode::EditJob => Some(&mut edit_form),
+                    InputMode::Search => {
+                        app.view.query.push_str(&text.replace(['\n', '\r'], " "));
+                        continue;
+                    }
+                    _ => None,
+                };
+                let multiline = target.as_ref().is_some_and(|f| f.is_multiline());
+                let sanitized = if multiline {
+                    // Preserve newlines in message bodies
+                    text.replace('\r', "")
+                } else {
+                    // Collapse newlines for other fields
+                    text.replace('\n', " ").replace('\r', "")
+                };
+                match target {
+                    Some(form) if !form.has_options() => form.edit(|value| value.push_str(&sanitized)),
+                    Some(_) => {}
+                    None => app.input_buffer.push_str(&sanitized),
+                }
+                continue;
+            }
+
+            let key = match ev {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key,
+                Event::Mouse(mouse) if config.mouse => {
+                    let form = match app.input_mode {
+                        InputMode::Params => Some(&mut param_form),
+                        InputMode::EditJob => Some(&mut edit_form),
+                        _ => None,
+                    };
+                    match app.mouse(mouse, form) {
+                        Some(key) => key,
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            // Handle Ctrl+C to quit from any mode
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                break;
+            }
+
+            let context = match app.input_mode {
+                InputMode::Normal => Some(KeyContext::List),
+                InputMode::Detail => Some(KeyContext::Detail),
+                InputMode::Timeline => Some(KeyContext::Timeline),
+                InputMode::Help => Some(KeyContext::Help),
+                _ => None,
+            };
+            if let Some(context) = context {
+                let Some(action) = app.keymap.action(context, &key) else {
+                    continue;
+                };
+                match action {
+                    Action::Up | Action::Down if matches!(app.input_mode, InputMode::Help) => {
+                        app.help_scroll = if action == Action::Down {
+                            app.help_scroll.saturating_add(1)
+                        } else {
+                            app.help_scroll.saturating_sub(1)
+                        };
+                    }
+                    Action::Up => app.move_selection(false),
+                    Action::Down => app.move_selection(true),
+                    Action::AddJob => {
+                        app.input_mode = InputMode::AddTask;
+                        form = AddTaskForm::default();
+                        app.input_field = InputField::Time;
+                        app.input_buffer = form.time.clone();
+                    }
+                    Action::Edit => {
+                        if let Some(first_page) = app.start_edit() {
+                            edit_form = first_page;
+                        }
+                    }
+                    Action::Details if matches!(app.input_mode, InputMode::Detail) => app.input_mode = InputMode::Normal,
+                    Action::Details => app.open_details(),
+                    Action::Delete => app.remove_selected(),
+                    Action::RunNow => app.run_selected_now(),
+                    Action::Duplicate => {
+                        if let Some(duplicate) = app.duplicate_selected() {
+                            form = duplicate;
+                        }
+                    }
+                    Action::CopyId => app.copy_selected_id(),
+                    Action::Search => {
+                        app.input_mode = InputMode::Search;
+                        app.list_state.select(Some(0));
+                    }
+                    Action::FilterFunction => app.cycle_filter('f'),
+                    Action::FilterTag => app.cycle_filter('t'),
+                    Action::FilterStatus => app.cycle_filter('s'),
+                    Action::Sort => {
+                        app.view.sort = app.view.sort.next();
+                        app.list_state.select(Some(0));
+                    }
+                    Action::LogPageUp => app.log.scroll_up(app.log_height),
+                    Action::LogPageDown => app.log.scroll_down(app.log_height),
+                    Action::LogTop => app.log.scroll_to_top(),
+                    Action::LogFollow => app.log.follow(),
+                    Action::LogSource => app.cycle_log_source(),
+                    Action::LogJob => app.toggle_log_job_filter(),
+                    Action::ExportLog => app.export_log(),
+                    Action::Timeline if matches!(app.input_mode, InputMode::Timeline) => app.input_mode = InputMode::Normal,
+                    Action::Timeline => app.input_mode = InputMode::Timeline,
+                    Action::ZoomIn => app.timeline.zoom_in(),
+                    Action::ZoomOut => app.timeline.zoom_out(),
+                    Action::Earlier => app.timeline.pan(false),
+                    Action::Later => app.timeline.pan(true),
+                    Action::Now => app.timeline.offset = 0,
+                    Action::Help => app.toggle_help(),
+                    Action::Close if matches!(app.input_mode, InputMode::Help) => app.toggle_help(),
+                    Action::Close => app.input_mode = InputMode::Normal,
+                    Action::Back => {
+                        if !app.clear_filters() {
+                            break;
+                        }
+                    }
+                    Action::Quit => break,
+                }
+                continue;
+            }
+
+            match app.input_mode {
+                InputMode::AddTask => match key.code {
+                    KeyCode::Esc => {
+                        app.input_mode = InputMode::Normal;
+                        app.message =
+                            Some(("Cancelled.".to_string(), std::time::Instant::now()));
+                    }
+                    KeyCode::Enter => match app.input_field {
+                        InputField::Time => {
+                            form.time = app.input_buffer.clone();
+                            app.input_field = InputField::Priority;
+                            app.input_buffer = form.priority.clone();
+                        }
+                        InputField::Priority => {
+                            form.priority = app.input_buffer.clone();
+                            app.input_field = InputField::Description;
+                            app.input_buffer = form.description.clone();
+                        }
+                        InputField::Description => {
+                            form.description = app.input_buffer.clone();
+                            app.input_field = InputField::Function;
+                            if !app.available_functions.is_empty() {
+                                app.input_buffer =
+                                    app.available_functions[app.function_index].clone();
+                            } else {
+                                app.input_buffer = form.function.clone();
+                            }
+                        }
+                        InputField::Function => {
+                            form.function = app.input_buffer.clone();
+                            // Ask for the function's parameters before adding the job
+                            let specs = app.params.get(form.function.trim()).cloned().unwrap_or_default();
+                            if !specs.is_empty() {
+                                param_form = ParamForm::new(specs);
+                                // A duplicate starts from the original's values
+                                if let Some(template) = form.template.as_ref().filter(|t| t.function == form.function.trim()) {
+                                    param_form.prefill(&template.metadata.clone().unwrap_or_default());
+                                }
+                                app.input_mode = InputMode::Params;
+                            } else if app.submit_add_task(&form, None) {
+                                app.input_buffer.clear();
+                            }
+                        }
+                    },
+                    KeyCode::Backspace
+                        if !matches!(app.input_field, InputField::Function)
+                            || app.available_functions.is_empty() =>
+                    {
+                        app.input_buffer.pop();
+                    }
+                    KeyCode::Char(c)
+                        if !matches!(app.input_field, InputField::Function)
+                            || app.available_functions.is_empty() =>
+                    {
+                        app.input_buffer.push(c);
+                    }
+                    KeyCode::Up
+                        if matches!(app.input_field, InputField::Function)
+                            && !app.available_functions.is_empty() =>
+                    {
+                        if app.function_index == 0 {
+                            app.function_index = app.available_functions.len() - 1;
+                        } else {
+                            app.function_index -= 1;
+                        }
+                        app.input_buffer = app.available_functions[app.function_index].clone();
+                    }
+                    KeyCode::Down
+                        if matches!(app.input_field, InputField::Function)
+                            && !app.available_functions.is_empty() =>
+                    {
+                        app.function_index =
+                            (app.function_index + 1) % app.available_functions.len();
+                        app.input_buffer = app.available_functions[app.function_index].clone();
+                    }
+                    _ => {}
+                },
+                InputMode::Params => match key.code {
+                    KeyCode::Esc if app.editing.is_some() => app.input_mode = InputMode::EditJob,
+                    KeyCode::Esc => {
+                        app.input_mode = InputMode::AddTask;
+                        app.input_field = InputField::Function;
+                        app.input_buffer = form.function.clone();
+                    }
+                    KeyCode::Enter if !key.modifiers.contains(KeyModifiers::ALT) => match param_form.metadata() {
+                        Some(meta) => match pending_edit.as_ref() {
+                            Some(edit) if app.editing.is_some() => app.save_edit(edit, meta),
+                            _ => {
+                                let metadata = if meta.is_empty() { None } else { Some(meta) };
+                                if app.submit_add_task(&form, metadata) {
+                                    app.input_buffer.clear();
+                                }
+                            }
+                        },
+                        None => {
+                            app.message =
+                                Some(("Fix the highlighted fields.".to_string(), std::time::Instant::now()));
+                        }
+                    },
+                    _ => {
+                        handle_form_key(&mut param_form, &key);
+                    }
+                },
+                InputMode::EditJob => match key.code {
+                    KeyCode::Esc => {
+                        app.editing = None;
+                        app.input_mode = InputMode::Normal;
+                        app.message = Some(("Edit cancelled.".to_string(), std::time::Instant::now()));
+                    }
+                    KeyCode::Enter if !key.modifiers.contains(KeyModifiers::ALT) => {
+                        let Some(edit) = app.parse_edit(&mut edit_form) else {
+                            app.message =
+                                Some(("Fix the highlighted fields.".to_string(), std::time::Instant::now()));
+                            continue;
+                        };
+                        let specs = app.params.get(&edit.function).cloned().unwrap_or_default();
+                        if specs.is_empty() {
+                            app.save_edit(&edit, HashMap::new());
+                        } else {
+                            // Current values of the parameters, whichever function declared them
+                            let current = app
+                                .editing
+                                .and_then(|id| app.queue.lock().ok()?.get(id).and_then(|j| j.metadata.clone()))
+                                .unwrap_or_default();
+                            param_form = ParamForm::new(specs);
+                            param_form.prefill(&current);
+                            pending_edit = Some(edit);
+                            app.input_mode = InputMode::Params;
+                        }
+                    }
+                    _ => {
+                        handle_form_key(&mut edit_form, &key);
+                    }
+                },
+                InputMode::Search => match key.code {
+                    KeyCode::Esc => {
+                        app.view.query.clear();
+                        app.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Enter => app.input_mode = InputMode::Normal,
+                    KeyCode::Up => app.move_selection(false),
+                    KeyCode::Down => app.move_selection(true),
+                    KeyCode::Backspace => {
+                        app.view.query.pop();
+                        app.list_state.select(Some(0));
+                    }
+                    KeyCode::Char(c) => {
+                        app.view.query.push(c);
+                        app.list_state.select(Some(0));
+                    }
+                    _ => {}
+                },
+                InputMode::Normal | InputMode::Detail | InputMode::Timeline | InputMode::Help => {}
+            }
+        }
+
+        if let Some((_, instant)) = &app.message
+            && instant.elapsed() > Duration::from_secs(3)
+        {
+            app.message = None;
+        }
+    }
+
+    disable_raw_mode()?;
+    if config.mouse {
+        crossterm::execute!(terminal.backend_mut(), crossterm::event::DisableMouseCapture)?;
+    }
+    crossterm::execute!(terminal.backend_mut(), crossterm::event::DisableBracketedPaste, LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+fn ui(f: &mut Frame, app: &mut AppState, form: &AddTaskForm, param_form: &ParamForm, edit_form: &ParamForm) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(3)])
+        .split(f.area());
+    app.layout = ScreenLayout::default();
+    // The help overlay is drawn over the view it was opened from
+    let view_mode = match app.input_mode {
+        InputMode::Help => app.help_from,
+        mode => mode,
+    };
+    render_dashboard(f, app, chunks[0]);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+
+    let total = app.pending_jobs().len();
+    let jobs = app.visible_jobs();
+    let count = if app.view.is_filtered() {
+        format!("{} of {}", jobs.len(), total)
+    } else {
+        total.to_string()
+    };
+    let cursor = if matches!(app.input_mode, InputMode::Search) { "\u{258f}" } else { "" };
+    let title = format!(" Pending tasks ({}){}{} ", count, app.view.describe(), cursor);
+    let theme = app.theme;
+    let list_items: Vec<ListItem> = jobs
+        .iter()
+        .map(|j| {
+            let ts = Utc
+                .timestamp_opt(j.execution_time, 0)
+                .single()
+                .unwrap_or_else(Utc::now);
+            let time_str = ts.format("%H:%M:%S %Y-%m-%d").to_string();
+            let mut spans = vec![
+                Span::raw(format!("{} \u{2502} P{} \u{2502} ", time_str, j.priority)),
+                Span::styled(j.description.as_str(), theme.accent),
+            ];
+            if j.status == Status::Paused {
+                spans.push(Span::styled(" [paused]", theme.muted));
+            }
+            if j.is_heavy() {
+                spans.push(Span::styled(" [heavy]", theme.heavy));
+            }
+            if let Some(schedule) = &j.schedule {
+                spans.push(Span::styled(format!(" [{}]", schedule), theme.schedule));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(list_items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .highlight_style(theme.selected);
+
+    let mut list_state = std::mem::take(&mut app.list_state);
+    if !jobs.is_empty() && list_state.selected().is_none_or(|i| i >= jobs.len()) {
+        list_state.select(Some(list_state.selected().unwrap_or(0).min(jobs.len() - 1)));
+    }
+    f.render_stateful_widget(list, main_chunks[0], &mut list_state);
+    app.list_state = list_state;
+    app.layout.list = main_chunks[0];
+
+    match app.monitor.clone() {
+        Some(monitor) => {
+            let right = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(5)])
+                .split(main_chunks[1]);
+            render_log(f, app, right[0]);
+            render_resources(f, &app.theme, &monitor, right[1]);
+        }
+        None => render_log(f, app, main_chunks[1]),
+    }
+    if matches!(view_mode, InputMode::Timeline) {
+        f.render_widget(Clear, chunks[1]);
+        render_timeline(f, app, chunks[1]);
+    }
+
+    let help = match app.input_mode {
+        InputMode::Normal => app.keymap.hints(&[
+            Action::Help,
+            Action::AddJob,
+            Action::Search,
+            Action::FilterFunction,
+            Action::FilterTag,
+            Action::FilterStatus,
+            Action::Sort,
+            Action::Details,
+            Action::Edit,
+            Action::RunNow,
+            Action::Duplicate,
+            Action::Delete,
+            Action::Timeline,
+            Action::Quit,
+        ]),
+        InputMode::Search => " Type to search description, function and payload \u{2502} \u{2191}/\u{2193}: Select \u{2502} Enter: Keep \u{2502} Esc: Clear ".to_string(),
+        InputMode::Timeline => app.keymap.hints(&[
+            Action::ZoomIn,
+            Action::ZoomOut,
+            Action::Earlier,
+            Action::Later,
+            Action::Now,
+            Action::Close,
+            Action::Help,
+        ]),
+        InputMode::Detail => app.keymap.hints(&[
+            Action::Up,
+            Action::Down,
+            Action::Edit,
+            Action::RunNow,
+            Action::Duplicate,
+            Action::CopyId,
+            Action::Close,
+            Action::Help,
+        ]),
+        InputMode::Help => app.keymap.hints(&[Action::Up, Action::Down, Action::Close]),
+        InputMode::AddTask => {
+            if matches!(app.input_field, InputField::Function)
+                && !app.available_functions.is_empty()
+            {
+                " Enter: Submit \u{2502} Esc: Cancel \u{2502} \u{2191}/\u{2193}: Select Function ".to_string()
+            } else {
+                " Enter: Next field \u{2502} Esc: Cancel \u{2502} Time = Secs from now OR Unix sec ".to_string()
+            }
+        }
+        InputMode::Params if app.editing.is_some() => {
+            " Tab/\u{2191}\u{2193}: Switch field \u{2502} \u{2190}/\u{2192}: Change option \u{2502} Enter: Save \u{2502} Esc: Back ".to_string()
+        }
+        InputMode::Params => {
+            " Tab/\u{2191}\u{2193}: Switch field \u{2502} \u{2190}/\u{2192}: Change option \u{2502} Enter: Add job \u{2502} Esc: Back ".to_string()
+        }
+        InputMode::EditJob => {
+            " Tab/\u{2191}\u{2193}: Switch field \u{2502} \u{2190}/\u{2192}: Function \u{2502} Alt+Enter: New line \u{2502} Enter: Next \u{2502} Esc: Cancel ".to_string()
+        }
+    };
+    let help_para = Paragraph::new(help)
+        .block(Block::default().borders(Borders::ALL))
+        .style(theme.hint);
+
+    let input_area = chunks[2];
+    if matches!(app.input_mode, InputMode::AddTask) {
+        let field_name = match app.input_field {
+            InputField::Time => " Time (Secs from now or Unix sec) ",
+            InputField::Priority => " Priority (0-255) ",
+            InputField::Description => " Description ",
+            InputField::Function => {
+                if app.available_functions.is_empty() {
+                    " Function name "
+                } else {
+                    " Function name (\u{2191}/\u{2193} to select) "
+                }
+            }
+        };
+        let prompt = format!("{}: {}", field_name, app.input_buffer);
+        let input_block = Paragraph::new(prompt.as_str())
+            .block(
+                Block::default()
+                    .title(if form.template.is_some() { " Duplicate task " } else { " Add task " })
+                    .borders(Borders::ALL),
+            )
+            .style(theme.input);
+        f.render_widget(input_block, input_area);
+    } else {
+        f.render_widget(help_para, input_area);
+    }
+
+    if let Some((msg, _)) = &app.message {
+        let area = centered_rect(60, 20, f.area());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(theme.popup);
+        let para = Paragraph::new(msg.as_str())
+            .block(block)
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, area);
+        f.render_widget(para, area);
+    }
+
+    let enter = Key::new(KeyCode::Enter, KeyModifiers::NONE);
+    let esc = Key::new(KeyCode::Esc, KeyModifiers::NONE);
+    match view_mode {
+        InputMode::Params => {
+            let (function, save) = if app.editing.is_some() {
+                (edit_form.values.get(3).map(String::as_str).unwrap_or_default(), "Save")
+            } else {
+                (form.function.as_str(), "Add job")
+            };
+            let buttons = [(save, enter), ("Back", esc)];
+            let title = format!(" Parameters for {} ", function.trim());
+            render_param_form(f, &theme, &title, param_form, &buttons, &mut app.layout);
+        }
+        InputMode::EditJob => {
+            render_param_form(f, &theme, " Edit job ", edit_form, &[("Next", enter), ("Cancel", esc)], &mut app.layout)
+        }
+        InputMode::Detail => match app.selected_job() {
+            Some(job) => render_job_detail(f, app, &job),
+            None => app.input_mode = InputMode::Normal,
+        },
+        _ => {}
+    }
+    if matches!(app.input_mode, InputMode::Help) {
+        render_help(f, app);
+    }
+}
+
+/// Row of buttons along the bottom of `area`, each showing the key it stands for.
+fn render_buttons(f: &mut Frame, theme: &Theme, area: Rect, buttons: &[(&str, Key)], layout: &mut ScreenLayout) {
+    let y = area.bottom().saturating_sub(1);
+    let mut x = area.x + 1;
+    for (label, key) in buttons {
+        let text = format!(" {}: {} ", key, label);
+        let width = (text.chars().count() as u16).min(area.right().saturating_sub(x));
+        if width == 0 {
+            break;
+        }
+        let rect = Rect::new(x, y, width, 1);
+        f.render_widget(Paragraph::new(text).style(theme.button), rect);
+        layout.buttons.push((rect, key.event()));
+        x += width + 2;
+    }
+}
+
+/// Keys, description and the action's name in `[tui.keys]`
+type HelpRow = (String, &'static str, &'static str);
+
+/// Overlay listing the keys of the active keymap, plus the fixed ones of forms and search.
+fn render_help(f: &mut Frame, app: &mut AppState) {
+    let area = centered_rect(70, 80, f.area());
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" Keys ")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let heading = app.theme.heading;
+    let mut sections: Vec<(&str, Vec<HelpRow>)> = [KeyContext::List, KeyContext::Detail, KeyContext::Timeline]
+        .into_iter()
+        .map(|context| {
+            let rows = app.keymap.help(context).into_iter().map(|(keys, a)| (keys, a.description(), a.name())).collect();
+            (context.title(), rows)
+        })
+        .collect();
+    let fixed = |rows: &[(&str, &'static str)]| rows.iter().map(|(k, d)| (k.to_string(), *d, "")).collect::<Vec<_>>();
+    sections.push((
+        "Forms",
+        fixed(&[
+            ("Tab, \u{2193} / Shift+Tab, \u{2191}", "Next / previous field"),
+            ("\u{2190}, \u{2192}, Space", "Change an option"),
+            ("Alt+Enter", "New line in a multi-line field"),
+            ("Enter", "Next page, or add or save the job"),
+            ("Esc", "Back, or cancel"),
+        ]),
+    ));
+    sections.push((
+        "Search",
+        fixed(&[("Enter", "Keep the search and go back to the list"), ("Esc", "Clear the search")]),
+    ));
+    if app.mouse {
+        sections.push((
+            "Mouse",
+            fixed(&[
+                ("Click", "Select a job (again for its details), focus a field, press a button"),
+                ("Wheel", "Scroll the list, log, timeline or this help; switch form fields"),
+            ]),
+        ));
+    }
+    sections.push(("Anywhere", fixed(&[("Ctrl+C", "Quit")])));
+
+    let width = sections.iter().flat_map(|(_, rows)| rows.iter().map(|(keys, _, _)| keys.chars().count())).max().unwrap_or(0);
+    let name_style = app.theme.muted;
+    let mut lines = Vec::new();
+    for (title, rows) in sections {
+        lines.push(Line::from(Span::styled(format!(" {}", title), heading)));
+        for (keys, description, name) in rows {
+            lines.push(Line::from(vec![
+                Span::raw(format!("   {:<width$}  {}", keys, description)),
+                Span::styled(if name.is_empty() { String::new() } else { format!("  ({})", name) }, name_style),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    let height = inner.height.saturating_sub(1);
+    app.help_scroll = app.help_scroll.min((lines.len() as u16).saturating_sub(height));
+    let paragraph = Paragraph::new(lines).scroll((app.help_scroll, 0));
+    f.render_widget(paragraph, Rect { height, ..inner });
+    let close: Vec<(&str, Key)> = app.keymap.keys(Action::Close).first().map(|k| ("Close", *k)).into_iter().collect();
+    render_buttons(f, &app.theme, inner, &close, &mut app.layout);
+}
+
+/// Popup with everything known about a queued job; secret payload values are masked.
+fn render_job_detail(f: &mut Frame, app: &mut AppState, job: &Job) {
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(format!(" Job {} ", &job.id.to_string()[..8]))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    let label = app.theme.muted;
+    let row = |name: &str, value: String| {
+        Line::from(vec![Span::styled(format!("  {:<13}", name), label), Span::raw(value)])
+    };
+    let when = Utc
+        .timestamp_opt(job.execution_time, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| job.execution_time.to_string());
+    let due = match job.execution_time - Job::now() {
+        secs if secs > 0 => format!("in {}s", secs),
+        secs => format!("due {}s ago", -secs),
+    };
+
+    let mut lines = vec![
+        row("ID", job.id.to_string()),
+        row("Description", job.description.clone()),
+        row("Function", job.function.clone()),
+        row("Status", format!("{:?}", job.status)),
+        row("Priority", job.priority.to_string()),
+        row("Retries", format!("{} used of {}", job.retry_count, job.max_retries)),
+        row("Runs at", format!("{} ({})", when, due)),
+        row("Schedule", job.schedule.clone().unwrap_or_else(|| "one-off".to_string())),
+    ];
+    if !job.tags.is_empty() {
+        lines.push(row("Tags", job.tags.join(", ")));
+    }
+    if let Some(definition) = &job.definition {
+        lines.push(row("Definition", definition.clone()));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("  Payload", label)));
+    let specs = app.params.get(&job.function).map(Vec::as_slice).unwrap_or_default();
+    let mut payload: Vec<(&String, &String)> = job.metadata.iter().flatten().collect();
+    payload.sort();
+    if payload.is_empty() {
+        lines.push(Line::from("    (none)"));
+    }
+    for (key, value) in payload {
+        // Fixed-width mask so the length of a secret is not shown either
+        let shown = if params::is_secret(specs, key) { "\u{2022}".repeat(8) } else { value.replace('\n', " \u{21b5} ") };
+        lines.push(Line::from(vec![Span::styled(format!("    {} = ", key), label), Span::raw(shown)]));
+    }
+
+    lines.push(Line::from(""));
+    match app.last_run(job) {
+        Some(run) => {
+            let at = Utc
+                .timestamp_opt(run.started_at, 0)
+                .single()
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                .unwrap_or_default();
+            let (outcome, style) = match run.outcome {
+                RunOutcome::Success => ("\u{2714} Succeeded", app.theme.success),
+                RunOutcome::Failed => ("\u{2718} Failed", app.theme.failure),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<13}", "Last run"), label),
+                Span::styled(outcome, style),
+                Span::raw(format!(" {} (attempt {}, {} ms)", at, run.attempt, run.duration_ms)),
+            ]));
+            if let Some(detail) = run.message.or(run.output) {
+                lines.push(row("", detail.replace('\n', " ")));
+            }
+        }
+        None => lines.push(row("Last run", "never".to_string())),
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, Rect { height: inner.height.saturating_sub(1), ..inner });
+    let buttons: Vec<(&str, Key)> = [Action::Edit, Action::RunNow, Action::Duplicate, Action::CopyId, Action::Close]
+        .into_iter()
+        .filter_map(|a| app.keymap.keys(a).first().map(|k| (a.label(), *k)))
+        .collect();
+    let theme = app.theme;
+    render_buttons(f, &theme, inner, &buttons, &mut app.layout);
+}
+
+/// Sets the system clipboard with an OSC 52 escape sequence, which most terminal
+/// emulators (and tmux with `set-clipboard on`) honour, also over SSH.
+fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    use base64::Engine as _;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)?;
+    stdout.flush()
+}
+
+/// Popup with one entry per declared parameter; scrolls to keep the active one visible.
+fn render_param_form(
+    f: &mut Frame,
+    theme: &Theme,
+    title: &str,
+    param_form: &ParamForm,
+    buttons: &[(&str, Key)],
+    layout: &mut ScreenLayout,
+) {
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(Clear, area);
+    let block = Block::default().title(title).borders(Borders::ALL).style(theme.popup);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let active_style = theme.active;
+    let inactive_style = theme.muted;
+    let mut lines = Vec::new();
+    let mut active_lines = (0, 0);
+    let mut field_lines = Vec::new();
+    for (i, spec) in param_form.specs.iter().enumerate() {
+        let active = i == param_form.active;
+        let style = if active { active_style } else { inactive_style };
+        let start = lines.len();
+
+        let mut label = vec![Span::styled(
+            // Marked by more than its color
+            format!("{}{}{}:", if active { "\u{25b8} " } else { "  " }, spec.label, if spec.required { " *" } else { "" }),
+            style,
+        )];
+        if let Some(help) = &spec.help {
+            label.push(Span::styled(format!("  {}", help), inactive_style));
+        }
+        lines.push(Line::from(label));
+
+        let value = &param_form.values[i];
+        let shown = if spec.secret {
+            "\u{2022}".repeat(value.chars().count())
+        } else if !spec.options().is_empty() {
+            format!("\u{2039} {} \u{203a}", if value.is_empty() { "(none)" } else { value })
+        } else {
+            value.clone()
+        };
+        let cursor = if active && spec.options().is_empty() { "\u{258f}" } else { "" };
+        let mut value_lines: Vec<&str> = shown.split('\n').collect();
+        if spec.kind == ParamKind::Multiline {
+            // Room for a few lines even while empty
+            value_lines.resize(value_lines.len().max(3), "");
+        }
+        let last = value_lines.len() - 1;
+        for (n, text) in value_lines.into_iter().enumerate() {
+            let prefix = if n == 0 { "  > " } else { "    " };
+            let tail = if n == last { cursor } else { "" };
+            lines.push(Line::from(Span::styled(format!("{}{}{}", prefix, text, tail), style)));
+        }
+        if let Some(error) = &param_form.errors[i] {
+            lines.push(Line::from(Span::styled(format!("    \u{2718} {}", error), theme.failure)));
+        }
+        lines.push(Line::from(""));
+        if active {
+            active_lines = (start, lines.len());
+        }
+        field_lines.push((start, lines.len()));
+    }
+
+    // The last row holds the buttons
+    let height = inner.height.saturating_sub(1) as usize;
+    let scroll = active_lines.1.saturating_sub(height).min(active_lines.0);
+    let paragraph = Paragraph::new(lines).scroll((scroll as u16, 0));
+    f.render_widget(paragraph, Rect { height: height as u16, ..inner });
+    for (i, (start, end)) in field_lines.into_iter().enumerate() {
+        let (top, bottom) = (start.max(scroll), end.min(scroll + height));
+        if top < bottom {
+            let y = inner.y + (top - scroll) as u16;
+            layout.fields.push((Rect::new(inner.x, y, inner.width, (bottom - top) as u16), i));
+        }
+    }
+    render_buttons(f, theme, inner, buttons, layout);
+}
+
+/// One-line summary of the queue, workers and recent runs above everything else.
+fn render_dashboard(f: &mut Frame, app: &AppState, area: Rect) {
+    let d = app.dashboard(Job::now());
+    let theme = &app.theme;
+    let sep = || Span::styled(" \u{2502} ", theme.muted);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+
+    let mut spans = vec![
+        Span::raw(" Queue "),
+        Span::styled(d.queued.to_string(), bold),
+    ];
+    if d.paused > 0 {
+        spans.push(Span::raw(format!(" ({} paused)", d.paused)));
+    }
+    spans.extend([
+        sep(),
+        Span::raw("Due \u{2264}1m "),
+        Span::styled(d.due_minute.to_string(), bold),
+        Span::raw(" \u{2264}1h "),
+        Span::styled(d.due_hour.to_string(), bold),
+        sep(),
+        Span::raw("Running "),
+        Span::styled(d.running.to_string(), bold),
+    ]);
+    if let Some(pool) = app.pool_size.filter(|p| *p > 0) {
+        spans.push(Span::raw(format!("/{} workers ({}%)", pool, d.running.min(pool) * 100 / pool)));
+    }
+
+    spans.extend([sep(), Span::raw("Last hour ")]);
+    match (d.succeeded * 100).checked_div(d.succeeded + d.failed) {
+        None => spans.push(Span::styled("no runs", theme.muted)),
+        Some(ok_percent) => spans.extend([
+            Span::styled(format!("\u{2714} {}", d.succeeded), theme.success),
+            Span::raw(" "),
+            Span::styled(format!("\u{2718} {}", d.failed), if d.failed > 0 { theme.failure } else { theme.muted }),
+            Span::raw(format!(" ({}% ok)", ok_percent)),
+        ]),
+    }
+
+    spans.extend([
+        sep(),
+        Span::raw("Avg lag "),
+        Span::styled(d.average_lag.map_or("-".to_string(), |lag| format!("{:.1}s", lag)), bold),
+    ]);
+    if let Some(engine) = &app.engine {
+        let (state, style) = if engine.is_running() {
+            ("\u{25cf} running", theme.success)
+        } else {
+            ("\u{25cb} stopped", theme.failure)
+        };
+        spans.extend([sep(), Span::raw("Engine "), Span::styled(state, style)]);
+    }
+
+    let header = Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL).title(" Dashboard "));
+    f.render_widget(header, area);
+}
+
+/// Timeline view: one row per function, upcoming jobs as ◆ at their execution time
+/// (◇ for later runs of recurring jobs), finished runs as bars as long as they took.
+fn render_timeline(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let now = Job::now();
+    let view = app.timeline;
+    let (start, end) = (view.start(now), view.start(now) + view.span());
+    let jobs = app.visible_jobs();
+    let runs: Vec<RunRecord> = match app.history.as_ref().and_then(|h| h.lock().ok()) {
+        Some(history) => history
+            .between(start, end)
+            .into_iter()
+            .filter(|r| app.view.function.as_ref().is_none_or(|f| *f == r.function))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut functions: Vec<&str> = jobs
+        .iter()
+        .map(|j| j.function.as_str())
+        .chain(runs.iter().map(|r| r.function.as_str()))
+        .collect();
+    functions.sort();
+    functions.dedup();
+
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let label_width = functions.iter().map(|f| f.chars().count()).max().unwrap_or(0).clamp(8, 20) + 1;
+    let width = inner_width.saturating_sub(label_width).max(1);
+    let column = |time: i64| view.column(now, width, time);
+    let step = view.tick_step(width);
+    let first_tick = start.div_euclid(step) * step + if start % step == 0 { 0 } else { step };
+    let ticks: Vec<i64> = (0..).map(|i| first_tick + i * step).take_while(|t| *t < end).collect();
+
+    // Axis: a label at every tick that has room for it
+    let format = if view.span() <= 86400 { "%H:%M" } else { "%m-%d %H:%M" };
+    let mut axis: Vec<char> = vec![' '; width];
+    for tick in &ticks {
+        let (Some(col), Some(time)) = (column(*tick), Utc.timestamp_opt(*tick, 0).single()) else {
+            continue;
+        };
+        let label: Vec<char> = time.format(format).to_string().chars().collect();
+        if col + label.len() <= width && axis[col.saturating_sub(1)..col + label.len()].iter().all(|c| *c == ' ') {
+            axis[col..col + label.len()].copy_from_slice(&label);
+        }
+    }
+    let theme = app.theme;
+    let dim = theme.muted;
+    let now_style = theme.now;
+    let mut lines = vec![Line::from(vec![
+        Span::raw(" ".repeat(label_width)),
+        Span::styled(axis.into_iter().collect::<String>(), dim),
+    ])];
+
+    for function in &functions {
+        let mut cells: Vec<(char, Style)> = vec![(' ', dim); width];
+        for col in ticks.iter().filter_map(|t| column(*t)) {
+            cells[col] = ('\u{00b7}', dim);
+        }
+        if let Some(col) = column(now) {
+            cells[col] = ('\u{2502}', now_style);
+        }
+        for run in runs.iter().filter(|r| r.function == *function) {
+            // Failed runs differ in shape too, not only in color
+            let (bar, style) = match run.outcome {
+                RunOutcome::Success => ('\u{2588}', theme.success),
+                RunOutcome::Failed => ('\u{2592}', theme.failure),
+            };
+            let finished = run.started_at + (run.duration_ms as i64 + 999) / 1000;
+            let from = column(run.started_at.max(start)).unwrap_or(0);
+            let to = column(finished.min(end - 1)).unwrap_or(width - 1).max(from);
+            for cell in &mut cells[from..=to] {
+                *cell = (bar, style);
+            }
+        }
+        for job in jobs.iter().filter(|j| j.function == *function) {
+            let style = if job.status == Status::Paused { dim } else { theme.accent };
+            // Later runs of a recurring job, as far as the window reaches
+            if let Some(schedule) = job.schedule.as_deref().and_then(|s| s.parse::<Schedule>().ok()) {
+                let mut next = schedule.next_after(job.execution_time.max(start - 1));
+                // More runs than columns would only draw over each other
+                for _ in 0..width {
+                    let Some(time) = next.filter(|t| *t < end) else {
+                        break;
+                    };
+                    if let Some(col) = column(time)
+                        && !matches!(cells[col].0, '\u{25c6}' | '2'..='9' | '+')
+                    {
+                        cells[col] = ('\u{25c7}', style);
+                    }
+                    next = schedule.next_after(time);
+                }
+            }
+            if let Some(col) = column(job.execution_time) {
+                // Jobs sharing a column are counted
+                let mark = match cells[col].0 {
+                    '\u{25c6}' => '2',
+                    c @ '2'..='8' => (c as u8 + 1) as char,
+                    '9' | '+' => '+',
+                    _ => '\u{25c6}',
+                };
+                cells[col] = (mark, style.add_modifier(Modifier::BOLD));
+            }
+        }
+        let label: String = function.chars().take(label_width - 1).collect();
+        let mut spans = vec![Span::styled(format!("{:<w$}", label, w = label_width), theme.text)];
+        spans.extend(cells.into_iter().map(|(c, style)| Span::styled(c.to_string(), style)));
+        lines.push(Line::from(spans));
+    }
+    if functions.is_empty() {
+        lines.push(Line::from(Span::styled("  Nothing scheduled or run in this window.", dim)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::raw(" ".repeat(label_width)),
+        Span::styled("\u{25c6}", theme.accent),
+        Span::styled(" queued (2-9, + when several)  ", dim),
+        Span::styled("\u{25c7}", theme.accent),
+        Span::styled(" later runs  ", dim),
+        Span::styled("\u{2588}", theme.success),
+        Span::styled(" run succeeded  ", dim),
+        Span::styled("\u{2592}", theme.failure),
+        Span::styled(" failed  ", dim),
+        Span::styled("\u{2502}", now_style),
+        Span::styled(" now", dim),
+    ]));
+
+    let upcoming = jobs.iter().filter(|j| (start..end).contains(&j.execution_time)).count();
+    let window = |t: i64| {
+        Utc.timestamp_opt(t, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    };
+    let title = format!(
+        " Timeline {} \u{2192} {} UTC \u{2502} zoom {}{} \u{2502} {} upcoming, {} run(s) ",
+        window(start),
+        window(end),
+        TIMELINE_ZOOM[view.zoom].1,
+        app.view.describe(),
+        upcoming,
+        runs.len()
+    );
+    let paragraph = Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(paragraph, area);
+}
+
+/// Log pane: the newest lines at the bottom, wrapped to the pane width, colored by level.
+fn render_log(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let height = area.height.saturating_sub(2) as usize;
+    let width = area.width.saturating_sub(2).max(1) as usize;
+    app.log_height = height.max(1);
+    app.layout.log = area;
+
+    // Wrap from the bottom of the view up until the pane is full
+    let mut rows: Vec<Line> = Vec::new();
+    for entry in app.log.visible().rev().skip(app.log.scroll()) {
+        // Warnings and errors are marked, not only colored
+        let (marker, style) = match entry.event.level() {
+            LogLevel::Error => ("\u{2718} ", app.theme.failure),
+            LogLevel::Warn => ("\u{26a0} ", app.theme.warning),
+            LogLevel::Info => ("", app.theme.muted),
+        };
+        let chars: Vec<char> = format!("{}{}", marker, entry.line()).chars().collect();
+        for chunk in chars.chunks(width).rev() {
+            rows.push(Line::from(Span::styled(chunk.iter().collect::<String>(), style)));
+        }
+        if rows.len() >= height {
+            break;
+        }
+    }
+    rows.truncate(height);
+    rows.reverse();
+
+    let position = if app.log.is_following() {
+        String::new()
+    } else {
+        format!(" \u{2502} {} newer below, End to follow", app.log.scroll())
+    };
+    let title = format!(" Log{}{} ", app.log.filter().describe(), position);
+    let log = Paragraph::new(rows).block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(log, area);
+}
+
+/// System CPU sparkline with the latest process and load figures in the title.
+fn render_resources(f: &mut Frame, theme: &Theme, monitor: &ResourceMonitor, area: Rect) {
+    let samples = monitor.samples();
+    let title = match samples.last() {
+        Some(s) => format!(
+            " CPU {:.0}% \u{2502} scheduler {:.1}% / {} MB \u{2502} load {:.2}{} ",
+            s.system_cpu_percent,
+            s.process_cpu_percent,
+            s.process_rss_bytes / 1024 / 1024,
+            s.load_average,
+            if monitor.overloaded() { " \u{2502} holding heavy jobs" } else { "" }
+        ),
+        None => " Resources (sampling...) ".to_string(),
+    };
+    // Newest samples on the right, as many as fit inside the borders
+    let width = area.width.saturating_sub(2) as usize;
+    let data: Vec<u64> = samples
+        .iter()
+        .skip(samples.len().saturating_sub(width))
+        .map(|s| s.system_cpu_percent.round() as u64)
+        .collect();
+    let style = if monitor.overloaded() { theme.failure } else { theme.success };
+    let sparkline = Sparkline::default()
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .data(&data)
+        .max(100)
+        .style(style);
+    f.render_widget(sparkline, area);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+    let vertical = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1]);
+    vertical[1]
+}
+
@@ -0,0 +1,184 @@
+//! Styles of the TUI. Every color comes from the active theme; states that matter
+//! (failed runs, warnings, paused jobs, the active field) also have a symbol or text,
+//! so no theme relies on color alone.
+
+use std::fmt;
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeName {
+    /// For dark terminal backgrounds
+    #[default]
+    Dark,
+    /// For light terminal backgrounds
+    Light,
+    /// Bright colors and bold text on black
+    HighContrast,
+    /// No colors, only bold, dim, underline and reverse; picked when `NO_COLOR` is set
+    Monochrome,
+}
+
+impl ThemeName {
+    pub const ALL: [ThemeName; 4] = [ThemeName::Dark, ThemeName::Light, ThemeName::HighContrast, ThemeName::Monochrome];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ThemeName::Dark => "dark",
+            ThemeName::Light => "light",
+            ThemeName::HighContrast => "high-contrast",
+            ThemeName::Monochrome => "monochrome",
+        }
+    }
+}
+
+impl fmt::Display for ThemeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.trim().to_lowercase().replace('_', "-").as_str() {
+            "dark" => Ok(ThemeName::Dark),
+            "light" => Ok(ThemeName::Light),
+            "high-contrast" => Ok(ThemeName::HighContrast),
+            "monochrome" | "mono" | "none" => Ok(ThemeName::Monochrome),
+            other => Err(format!("expected dark, light, high-contrast or monochrome, got '{}'", other)),
+        }
+    }
+}
+
+/// Styles for each kind of thing on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Plain text that should stand out from the background, like timeline rows
+    pub text: Style,
+    /// Labels, separators, info log lines, paused jobs
+    pub muted: Style,
+    /// Job descriptions and queued jobs on the timeline
+    pub accent: Style,
+    pub heavy: Style,
+    pub schedule: Style,
+    /// Selected row of the job list
+    pub selected: Style,
+    /// Key hints along the bottom
+    pub hint: Style,
+    pub heading: Style,
+    /// Popups and dialogs
+    pub popup: Style,
+    /// The add task prompt
+    pub input: Style,
+    /// Focused field of a form
+    pub active: Style,
+    pub button: Style,
+    pub success: Style,
+    pub warning: Style,
+    /// Errors, failed runs, a stopped engine
+    pub failure: Style,
+    /// The current time on the timeline
+    pub now: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(ThemeName::Dark)
+    }
+}
+
+impl Theme {
+    pub fn new(name: ThemeName) -> Self {
+        let fg = |color| Style::default().fg(color);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let reversed = Style::default().add_modifier(Modifier::REVERSED);
+        match name {
+            ThemeName::Dark => Theme {
+                text: fg(Color::White),
+                muted: fg(Color::DarkGray),
+                accent: fg(Color::Cyan),
+                heavy: fg(Color::Magenta),
+                schedule: fg(Color::Blue),
+                selected: reversed,
+                hint: fg(Color::Yellow),
+                heading: fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                popup: Style::default().bg(Color::Black).fg(Color::White),
+                input: fg(Color::Green),
+                active: fg(Color::Green).add_modifier(Modifier::BOLD),
+                button: reversed,
+                success: fg(Color::Green),
+                warning: fg(Color::Yellow),
+                failure: fg(Color::Red),
+                now: fg(Color::Yellow),
+            },
+            // Yellow, cyan and light grey wash out on white; use the darker colors
+            ThemeName::Light => Theme {
+                text: fg(Color::Black),
+                muted: fg(Color::DarkGray),
+                accent: fg(Color::Blue),
+                heavy: fg(Color::Magenta),
+                schedule: fg(Color::Blue).add_modifier(Modifier::ITALIC),
+                selected: reversed,
+                hint: fg(Color::Blue),
+                heading: fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                popup: Style::default().bg(Color::White).fg(Color::Black),
+                input: fg(Color::Green).add_modifier(Modifier::BOLD),
+                active: fg(Color::Blue).add_modifier(Modifier::BOLD),
+                button: reversed,
+                success: fg(Color::Green),
+                warning: fg(Color::Magenta),
+                failure: fg(Color::Red).add_modifier(Modifier::BOLD),
+                now: fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            },
+            ThemeName::HighContrast => Theme {
+                text: fg(Color::White).add_modifier(Modifier::BOLD),
+                muted: fg(Color::Gray),
+                accent: fg(Color::LightCyan).add_modifier(Modifier::BOLD),
+                heavy: fg(Color::LightMagenta),
+                schedule: fg(Color::LightBlue),
+                selected: reversed.add_modifier(Modifier::BOLD),
+                hint: fg(Color::LightYellow),
+                heading: fg(Color::LightYellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                popup: Style::default().bg(Color::Black).fg(Color::White),
+                input: fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+                active: fg(Color::LightYellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                button: reversed.add_modifier(Modifier::BOLD),
+                success: fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+                warning: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+                failure: fg(Color::LightRed).add_modifier(Modifier::BOLD),
+                now: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            },
+            ThemeName::Monochrome => Theme {
+                text: Style::default(),
+                muted: Style::default().add_modifier(Modifier::DIM),
+                accent: bold,
+                heavy: Style::default(),
+                schedule: Style::default().add_modifier(Modifier::ITALIC),
+                selected: reversed,
+                hint: Style::default(),
+                heading: bold.add_modifier(Modifier::UNDERLINED),
+                popup: Style::default(),
+                input: bold,
+                active: bold.add_modifier(Modifier::UNDERLINED),
+                button: reversed,
+                success: Style::default(),
+                warning: bold,
+                failure: bold,
+                now: bold,
+            },
+        }
+    }
+
+    /// Whether any style sets a color
+    pub fn uses_color(&self) -> bool {
+        [
+            self.text, self.muted, self.accent, self.heavy, self.schedule, self.selected, self.hint, self.heading,
+            self.popup, self.input, self.active, self.button, self.success, self.warning, self.failure, self.now,
+        ]
+        .iter()
+        .any(|s| s.fg.is_some() || s.bg.is_some())
+    }
+}
@@ -1,14 +1,27 @@
 use std::collections::HashMap;
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
 
+use crate::events::{EventBus, JobEvent, JobEventKind};
 use crate::job::Job;
-use notify_rust::Notification;
+use crate::queue::QueueManager;
+use crate::tasks::{ParamSpec, Task, TaskContext, TaskResult};
 
-/// Type alias for a function pointer that takes log_tx and returns nothing
-// type JobFn = fn(Sender<String>);
+/// Boxed task function that receives the context of the job being run
+type JobFn = Box<dyn Fn(&TaskContext) -> TaskResult + Send>;
 
+/// Seconds before a failed job runs again, multiplied by the retry number
+pub const RETRY_BACKOFF_SECS: i64 = 5;
+
+#[derive(Default)]
 pub struct Worker {
-    registry: HashMap<String, Box<dyn Fn(Sender<String>) + Send>>,
+    registry: HashMap<String, JobFn>,
+    /// Parameters declared by tasks registered with `register_task`
+    params: HashMap<String, Vec<ParamSpec>>,
+    events: Option<EventBus>,
+    queue: Option<Arc<Mutex<QueueManager>>>,
 }
 
 impl Worker {
@@ -16,64 +29,166 @@ impl Worker {
     pub fn new() -> Self {
         Self {
             registry: HashMap::new(),
+            params: HashMap::new(),
+            events: None,
+            queue: None,
+        }
+    }
+
+    /// Publish lifecycle events (started, succeeded, failed, retried) of every run,
+    /// along with whatever the tasks themselves log
+    pub fn set_events(&mut self, events: EventBus) {
+        self.events = Some(events);
+    }
+
+    /// Put jobs that failed with retries left back into this queue, after a backoff
+    pub fn set_queue(&mut self, queue: Arc<Mutex<QueueManager>>) {
+        self.queue = Some(queue);
+    }
+
+    fn publish(&self, job: &Job, kind: JobEventKind) {
+        match self.events.as_ref() {
+            Some(events) => events.publish(JobEvent::new(job, kind)),
+            None => tracing::debug!("[Worker] {} '{}'", job.function, job.description),
         }
     }
 
+    /// Names of all registered functions, sorted
+    pub fn functions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.registry.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Drops every registered function not named in `names`.
+    /// Fails without changing anything if a name is not registered.
+    pub fn restrict_to(&mut self, names: &[String]) -> Result<(), String> {
+        if let Some(unknown) = names.iter().find(|n| !self.registry.contains_key(*n)) {
+            return Err(format!("No function registered for '{}'", unknown));
+        }
+        self.registry.retain(|name, _| names.contains(name));
+        self.params.retain(|name, _| names.contains(name));
+        Ok(())
+    }
+
     /// Register a function string to a concrete function
     pub fn register<F>(&mut self, name: &str, f: F)
     where
-        F: Fn(Sender<String>) + Send + 'static,
+        F: Fn(&TaskContext) -> TaskResult + Send + 'static,
     {
         self.registry.insert(name.to_string(), Box::new(f));
     }
 
+    /// Registers a `Task` along with the parameters it declares
+    pub fn register_task<T: Task + 'static>(&mut self, name: &str) {
+        self.register(name, T::run);
+        self.params.insert(name.to_string(), T::params());
+    }
+
+    /// Parameters declared for `name`; empty for plain functions
+    pub fn params(&self, name: &str) -> &[ParamSpec] {
+        self.params.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Declared parameters of every registered function
+    pub fn param_schemas(&self) -> HashMap<String, Vec<ParamSpec>> {
+        self.registry
+            .keys()
+            .map(|name| (name.clone(), self.params(name).to_vec()))
+            .collect()
+    }
+
     /// The execution engine: looks up the string in the map and calls the function
-    pub fn run_job(&self, job: &mut Job, log_tx: Sender<String>) {
-        if let Some(func) = self.registry.get(&job.function) {
-            // Inject job metadata as env vars so task functions can read them
-            if let Some(ref meta) = job.metadata {
-                for (key, val) in meta {
-                    // SAFETY: single-threaded worker processes jobs sequentially
-                    unsafe { std::env::set_var(key, val); }
+    pub fn run_job(&self, job: &mut Job) {
+        let timer = Instant::now();
+        let attempt = job.retry_count + 1;
+        let span = tracing::info_span!(
+            "job_run",
+            job.id = %job.id,
+            job.function = %job.function,
+            job.description = %job.description,
+            job.priority = job.priority,
+            job.attempt = attempt,
+            otel.status_code = tracing::field::Empty,
+        );
+        let _entered = span.enter();
+
+        let (result, output) = match self.registry.get(&job.function) {
+            Some(func) => {
+                job.start();
+                self.publish(job, JobEventKind::Started { attempt });
+                let ctx = TaskContext::new(job.clone(), self.events.clone());
+                let result = func(&ctx); // Execute the function
+                (result, ctx.take_output())
+            }
+            None => (Err(format!("No function registered for '{}'", job.function)), None),
+        };
+        let duration_ms = timer.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(()) => {
+                job.complete();
+                span.record("otel.status_code", "OK");
+                self.publish(job, JobEventKind::Succeeded { attempt, duration_ms, output });
+            }
+            Err(error) => {
+                let will_retry = job.fail_and_retry();
+                tracing::error!("{}", error);
+                span.record("otel.status_code", "ERROR");
+                self.publish(job, JobEventKind::Failed { attempt, duration_ms, error, will_retry, output });
+                if will_retry {
+                    let max_retries = job.max_retries;
+                    self.publish(job, JobEventKind::Retried { attempt: job.retry_count, max_retries });
+                    self.requeue(job);
                 }
             }
-            job.start();
-            let _ = log_tx.send(format!("[Worker] Executing '{}'", job.description));
-            func(log_tx.clone()); // Execute the function
-            job.complete();
-            let _ = log_tx.send(format!("[Worker] Done '{}'", job.description));
-
-            let _ = Notification::new()
-                .summary("Task Scheduler")
-                .body(&format!("Job '{}' completed successfully.", job.description))
-                .show();
-        } else {
-            let _ = log_tx.send(format!(
-                "[Worker] Error: No function registered for '{}'",
-                job.function
-            ));
-            let will_retry = job.fail_and_retry();
-
-            let msg = if will_retry {
-                format!(
-                    "Job '{}' failed. Will retry ({}/{})",
-                    job.description, job.retry_count, job.max_retries
-                )
-            } else {
-                format!("Job '{}' failed permanently.", job.description)
-            };
-
-            let _ = Notification::new()
-               .summary("Task Scheduler")
-               .body(&msg)
-               .show();
+        }
+    }
+
+    fn requeue(&self, job: &Job) {
+        let Some(queue) = self.queue.as_ref() else {
+            return;
+        };
+        let mut retry = job.clone();
+        retry.execution_time = Job::now() + RETRY_BACKOFF_SECS * i64::from(job.retry_count);
+        // The engine queued the next occurrence when it dispatched this run; a retry
+        // that kept the schedule would queue another one
+        retry.schedule = None;
+        if let Ok(mut q) = queue.lock() {
+            q.push(retry);
         }
     }
 
     /// Starts a simple blocking loop to process jobs from the channel
-    pub fn start(&self, rx: std::sync::mpsc::Receiver<Job>, log_tx: Sender<String>) {
+    pub fn start(&self, rx: Receiver<Job>) {
         for mut job in rx {
-            self.run_job(&mut job, log_tx.clone());
+            self.run_job(&mut job);
         }
     }
 }
+
+/// Runs `size` workers on their own threads, each taking the next job from the
+/// shared channel as soon as it is idle. `make_worker` builds one worker per thread.
+pub fn start_pool(
+    size: usize,
+    rx: Receiver<Job>,
+    make_worker: impl Fn() -> Worker,
+) -> Vec<JoinHandle<()>> {
+    let rx = Arc::new(Mutex::new(rx));
+    (0..size.max(1))
+        .map(|_| {
+            let worker = make_worker();
+            let rx = Arc::clone(&rx);
+            thread::spawn(move || {
+                loop {
+                    // Hold the lock only while waiting for the next job
+                    let next = rx.lock().map(|rx| rx.recv());
+                    match next {
+                        Ok(Ok(mut job)) => worker.run_job(&mut job),
+                        _ => break,
+                    }
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,272 @@
+use scheduler::events::{EventBus, SchedulerEvent};
+use scheduler::history::{RunOutcome, RunRecord};
+use scheduler::job::Job;
+use scheduler::tasks;
+use scheduler::worker::Worker;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use flate2::read::GzDecoder;
+use uuid::Uuid;
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("scheduler_backup_{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A file that passes for a SQLite database when `sqlite3` is not used
+fn fake_sqlite(dir: &Path) -> PathBuf {
+    let path = dir.join("app.db");
+    let mut content = b"SQLite format 3\0".to_vec();
+    content.extend_from_slice(&[7; 4096]);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn backup_job(payload: &[(&str, &str)]) -> Job {
+    let mut job = Job::new(Job::now(), 1, "Backup", "backup_fn", 0).unwrap();
+    job.metadata = Some(
+        payload
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+    );
+    job
+}
+
+fn run(job: &mut Job) -> RunRecord {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let mut worker = Worker::new();
+    tasks::register_builtin(&mut worker);
+    worker.set_events(bus);
+    worker.run_job(job);
+    rx.try_iter()
+        .find_map(|e: SchedulerEvent| RunRecord::from_event(&e))
+        .expect("finished attempt")
+}
+
+fn backups(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+fn decompress(path: &Path) -> Vec<u8> {
+    let mut content = Vec::new();
+    GzDecoder::new(fs::File::open(path).unwrap())
+        .read_to_end(&mut content)
+        .unwrap();
+    content
+}
+
+#[test]
+fn copies_sqlite_file_without_sqlite3() {
+    let source_dir = temp_dir();
+    let source = fake_sqlite(&source_dir);
+    let dest = temp_dir();
+    let mut job = backup_job(&[
+        ("BACKUP_SOURCE", source.to_str().unwrap()),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_DUMP_COMMAND", "scheduler-missing-sqlite3"),
+    ]);
+    let record = run(&mut job);
+    assert_eq!(record.outcome, RunOutcome::Success, "{:?}", record.message);
+
+    let files = backups(&dest);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].starts_with("app-") && files[0].ends_with(".db.gz"), "{:?}", files);
+    assert_eq!(decompress(&dest.join(&files[0])), fs::read(&source).unwrap());
+    let output = record.output.unwrap();
+    assert!(output.contains(&files[0]) && output.contains("verified"), "{}", output);
+}
+
+#[test]
+fn snapshots_sqlite_with_sqlite3() {
+    let source_dir = temp_dir();
+    let source = source_dir.join("live.db");
+    let created = Command::new("sqlite3")
+        .arg(&source)
+        .arg("CREATE TABLE t (x); INSERT INTO t VALUES (42);")
+        .status();
+    if !created.is_ok_and(|s| s.success()) {
+        eprintln!("sqlite3 not available, skipping");
+        return;
+    }
+    let dest = temp_dir();
+    let mut job = backup_job(&[
+        ("BACKUP_SOURCE", source.to_str().unwrap()),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_NAME", "nightly"),
+    ]);
+    assert_eq!(run(&mut job).outcome, RunOutcome::Success);
+
+    let files = backups(&dest);
+    assert_eq!(files.len(), 1, "snapshot leftovers: {:?}", files);
+    assert!(files[0].starts_with("nightly-"));
+    let restored = source_dir.join("restored.db");
+    fs::write(&restored, decompress(&dest.join(&files[0]))).unwrap();
+    let query = Command::new("sqlite3").arg(&restored).arg("SELECT x FROM t").output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&query.stdout).trim(), "42");
+}
+
+#[test]
+fn rejects_files_that_are_not_sqlite() {
+    let source_dir = temp_dir();
+    let source = source_dir.join("notes.txt");
+    fs::write(&source, "not a database").unwrap();
+    let dest = temp_dir();
+    let mut job = backup_job(&[
+        ("BACKUP_SOURCE", source.to_str().unwrap()),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+    ]);
+    let record = run(&mut job);
+    assert_eq!(record.outcome, RunOutcome::Failed);
+    assert!(record.message.unwrap().contains("not a SQLite database"));
+    assert!(backups(&dest).is_empty());
+}
+
+#[test]
+fn keeps_only_the_newest_backups() {
+    let source_dir = temp_dir();
+    let source = fake_sqlite(&source_dir);
+    let dest = temp_dir();
+    for stamp in ["20240101T000000Z", "20240102T000000Z", "20240103T000000Z"] {
+        fs::write(dest.join(format!("app-{}.db.gz", stamp)), b"old").unwrap();
+    }
+    fs::write(dest.join("other-20240101T000000Z.db.gz"), b"old").unwrap();
+
+    let mut job = backup_job(&[
+        ("BACKUP_SOURCE", source.to_str().unwrap()),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_RETENTION", "2"),
+        ("BACKUP_DUMP_COMMAND", "scheduler-missing-sqlite3"),
+    ]);
+    let record = run(&mut job);
+    assert_eq!(record.outcome, RunOutcome::Success);
+    assert!(record.output.unwrap().contains("2 old backup(s) removed"));
+
+    let files = backups(&dest);
+    assert_eq!(files.len(), 3, "{:?}", files);
+    assert_eq!(files[0], "app-20240103T000000Z.db.gz");
+    assert!(files[1].starts_with("app-2") && files[1] != files[0]);
+    assert_eq!(files[2], "other-20240101T000000Z.db.gz");
+}
+
+#[test]
+fn prunes_only_backups_of_its_own_name() {
+    let source_dir = temp_dir();
+    let source = fake_sqlite(&source_dir);
+    let dest = temp_dir();
+    for stamp in ["20240101T000000Z", "20240102T000000Z"] {
+        fs::write(dest.join(format!("app-{}.db.gz", stamp)), b"old").unwrap();
+        fs::write(dest.join(format!("app-logs-{}.db.gz", stamp)), b"old").unwrap();
+    }
+    fs::write(dest.join("app-notes.db.gz"), b"keep").unwrap();
+
+    let mut job = backup_job(&[
+        ("BACKUP_SOURCE", source.to_str().unwrap()),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_NAME", "app"),
+        ("BACKUP_RETENTION", "1"),
+        ("BACKUP_DUMP_COMMAND", "scheduler-missing-sqlite3"),
+    ]);
+    let record = run(&mut job);
+    assert_eq!(record.outcome, RunOutcome::Success);
+    assert!(record.output.unwrap().contains("2 old backup(s) removed"));
+
+    let files = backups(&dest);
+    assert_eq!(files.len(), 4, "{:?}", files);
+    assert!(files[0].starts_with("app-2") && files[0].ends_with(".db.gz"), "{:?}", files);
+    assert_eq!(&files[1..], ["app-logs-20240101T000000Z.db.gz", "app-logs-20240102T000000Z.db.gz", "app-notes.db.gz"]);
+}
+
+#[test]
+fn backups_in_the_same_second_get_a_suffix() {
+    let source_dir = temp_dir();
+    let source = fake_sqlite(&source_dir);
+    let dest = temp_dir();
+    // Taken names for this second and the next, in case the clock ticks over
+    let now = chrono::Utc::now();
+    for at in [now, now + chrono::Duration::seconds(1)] {
+        fs::write(dest.join(format!("app-{}.db.gz", at.format("%Y%m%dT%H%M%SZ"))), b"taken").unwrap();
+    }
+
+    let mut job = backup_job(&[
+        ("BACKUP_SOURCE", source.to_str().unwrap()),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_NAME", "app"),
+        ("BACKUP_RETENTION", "0"),
+        ("BACKUP_DUMP_COMMAND", "scheduler-missing-sqlite3"),
+    ]);
+    assert_eq!(run(&mut job).outcome, RunOutcome::Success);
+
+    let files = backups(&dest);
+    assert_eq!(files.len(), 3, "{:?}", files);
+    let new = files.iter().find(|f| f.ends_with("-1.db.gz")).expect("suffixed backup");
+    assert_eq!(decompress(&dest.join(new)), fs::read(&source).unwrap());
+}
+
+#[test]
+fn missing_settings_and_tools_fail_clearly() {
+    let record = run(&mut backup_job(&[("BACKUP_SOURCE", "x"), ("BACKUP_KIND", "oracle")]));
+    assert!(record.message.unwrap().contains("BACKUP_KIND"));
+
+    let record = run(&mut backup_job(&[("BACKUP_SOURCE", "x"), ("BACKUP_RETENTION", "many")]));
+    assert!(record.message.unwrap().contains("BACKUP_RETENTION"));
+
+    let dest = temp_dir();
+    let mut job = backup_job(&[
+        ("BACKUP_KIND", "mysql"),
+        ("BACKUP_SOURCE", "shop"),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_DUMP_COMMAND", "scheduler-missing-mysqldump"),
+    ]);
+    let record = run(&mut job);
+    assert_eq!(record.outcome, RunOutcome::Failed);
+    assert!(record.message.unwrap().contains("'scheduler-missing-mysqldump' not found"));
+    assert!(backups(&dest).is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn streams_dump_tool_output() {
+    let dest = temp_dir();
+    // `echo` stands in for pg_dump and prints the arguments it was given
+    let mut job = backup_job(&[
+        ("BACKUP_KIND", "postgres"),
+        ("BACKUP_SOURCE", "postgres://app@db.internal/shop"),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_DUMP_COMMAND", "echo"),
+    ]);
+    assert_eq!(run(&mut job).outcome, RunOutcome::Success);
+
+    let files = backups(&dest);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].starts_with("shop-") && files[0].ends_with(".sql.gz"), "{:?}", files);
+    let dump = String::from_utf8(decompress(&dest.join(&files[0]))).unwrap();
+    assert_eq!(dump.trim(), "--dbname=postgres://app@db.internal/shop");
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_dump_tool_leaves_no_backup() {
+    let dest = temp_dir();
+    let mut job = backup_job(&[
+        ("BACKUP_KIND", "postgres"),
+        ("BACKUP_SOURCE", "shop"),
+        ("BACKUP_DIR", dest.to_str().unwrap()),
+        ("BACKUP_DUMP_COMMAND", "false"),
+    ]);
+    let record = run(&mut job);
+    assert_eq!(record.outcome, RunOutcome::Failed);
+    assert!(record.message.unwrap().starts_with("false failed"));
+    assert!(backups(&dest).is_empty());
+}
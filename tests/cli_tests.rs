@@ -0,0 +1,139 @@
+use clap::Parser;
+use scheduler::cli::{self, Cli};
+use scheduler::job::{Job, Status};
+use scheduler::persistence_manager::PersistenceManager;
+use std::fs;
+use uuid::Uuid;
+
+struct TempFiles {
+    queue: String,
+    history: String,
+}
+
+impl TempFiles {
+    fn new() -> Self {
+        let id = Uuid::new_v4();
+        Self {
+            queue: format!("scheduler_cli_{}.json", id),
+            history: format!("scheduler_cli_{}.jsonl", id),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, String> {
+        // No scheduler listens on this socket, so commands work on the files
+        let socket = format!("{}.sock", self.queue);
+        let mut argv = vec![
+            "scheduler", "--queue", &self.queue, "--history", &self.history, "--socket", &socket,
+        ];
+        argv.extend_from_slice(args);
+        let cli = Cli::try_parse_from(argv).map_err(|e| e.to_string())?;
+        let mut out = Vec::new();
+        cli::execute(&cli, cli.command.as_ref().unwrap(), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    fn jobs(&self) -> Vec<Job> {
+        PersistenceManager::new(&self.queue).load_jobs()
+    }
+}
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.queue);
+        let _ = fs::remove_file(&self.history);
+    }
+}
+
+#[test]
+fn add_then_list_as_json() {
+    let files = TempFiles::new();
+    files
+        .run(&[
+            "add", "-t", "600", "-p", "4", "-d", "Nightly", "-f", "backup_fn", "-m", "REGION=v=1", "--tag",
+            "heavy",
+        ])
+        .unwrap();
+
+    let out = files.run(&["-o", "json", "list"]).unwrap();
+    let jobs: Vec<Job> = serde_json::from_str(&out).unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].description, "Nightly");
+    assert_eq!(jobs[0].priority, 4);
+    assert_eq!(jobs[0].metadata.as_ref().unwrap()["REGION"], "v=1");
+    assert!(jobs[0].is_heavy());
+}
+
+#[test]
+fn pause_resume_and_remove_by_prefix() {
+    let files = TempFiles::new();
+    files.run(&["add", "-t", "600", "-d", "Report", "-f", "hotfix_fn"]).unwrap();
+    let id = files.jobs()[0].id.to_string();
+    let prefix = &id[..8];
+
+    files.run(&["pause", prefix]).unwrap();
+    assert_eq!(files.jobs()[0].status, Status::Paused);
+    assert!(files.run(&["pause", prefix]).is_err());
+
+    files.run(&["resume", prefix]).unwrap();
+    assert_eq!(files.jobs()[0].status, Status::Pending);
+
+    let table = files.run(&["list"]).unwrap();
+    assert!(table.starts_with("ID"));
+    assert!(table.contains("Report"));
+
+    files.run(&["remove", prefix]).unwrap();
+    assert!(files.jobs().is_empty());
+}
+
+#[test]
+fn run_now_records_history_and_keeps_job() {
+    let files = TempFiles::new();
+    files.run(&["add", "-t", "600", "-d", "Hotfix", "-f", "hotfix_fn"]).unwrap();
+    let id = files.jobs()[0].id.to_string();
+
+    files.run(&["run-now", &id]).unwrap();
+    assert_eq!(files.jobs().len(), 1);
+    assert_eq!(files.jobs()[0].id.to_string(), id);
+
+    // The run is recorded under the copy's own id, apart from the queued job's runs
+    let out = files.run(&["-o", "json", "history"]).unwrap();
+    let runs: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0]["outcome"], "Success");
+    assert_eq!(runs[0]["description"], "Hotfix");
+    assert_ne!(runs[0]["job_id"], id.as_str());
+}
+
+#[test]
+fn show_masks_secret_metadata() {
+    let files = TempFiles::new();
+    files
+        .run(&["add", "-t", "600", "-d", "Mail", "-f", "email_fn", "-m", "SMTP_PASSWORD=hunter2", "-m", "TO=ops@example.com"])
+        .unwrap();
+    let id = files.jobs()[0].id.to_string();
+
+    let out = files.run(&["show", &id]).unwrap();
+    assert!(out.contains("SMTP_PASSWORD = \u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}"), "{}", out);
+    assert!(!out.contains("hunter2"), "{}", out);
+    assert!(out.contains("TO = ops@example.com"), "{}", out);
+
+    for args in [&["-o", "json", "show", &id][..], &["-o", "json", "list"], &["-o", "json", "pause", &id]] {
+        let out = files.run(args).unwrap();
+        assert!(!out.contains("hunter2"), "{}", out);
+        assert!(out.contains("ops@example.com"), "{}", out);
+    }
+    // Only the output is masked; the queued job keeps its password
+    assert_eq!(files.jobs()[0].metadata.as_ref().unwrap()["SMTP_PASSWORD"], "hunter2");
+}
+
+#[test]
+fn unknown_id_is_an_error() {
+    let files = TempFiles::new();
+    assert!(files.run(&["show", "deadbeef"]).is_err());
+}
+
+#[test]
+fn engine_commands_need_a_running_scheduler() {
+    let files = TempFiles::new();
+    assert!(files.run(&["engine", "status"]).is_err());
+}
@@ -0,0 +1,415 @@
+use scheduler::config::{Config, DEFAULT_POLL_INTERVAL};
+use scheduler::email::{SmtpSecurity, TransportKind};
+use scheduler::job::Job;
+use scheduler::telemetry::{LogFormat, LogRotation};
+use scheduler::worker::{self, Worker};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+fn no_env(_: &str) -> Option<String> {
+    None
+}
+
+fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |key| vars.get(key).cloned()
+}
+
+#[test]
+fn defaults_without_a_file() {
+    let config = Config::from_toml("", no_env).unwrap();
+    assert_eq!(config.storage.queue, "queue.json");
+    assert_eq!(config.storage.history, "history.jsonl");
+    assert_eq!(config.poll_interval, DEFAULT_POLL_INTERVAL);
+    assert_eq!(config.worker.pool_size, 1);
+    assert!(config.worker.functions.is_none());
+    assert!(config.notifications.desktop);
+    assert!(config.metrics_addr.is_none());
+    assert_eq!(config.jobs.len(), 3, "demo jobs are seeded by default");
+}
+
+#[test]
+fn parses_every_section() {
+    let text = r#"
+        [storage]
+        queue = "data/queue.json"
+        history = "data/history.jsonl"
+        socket = "/tmp/scheduler-test.sock"
+
+        [engine]
+        poll_interval_ms = 250
+
+        [worker]
+        pool_size = 4
+        functions = ["backup_fn", "email_fn"]
+
+        [telemetry]
+        level = "debug"
+        format = "json"
+        rotation = "size"
+        max_size_bytes = 1024
+        max_files = 2
+        stdout = false
+        metrics_addr = "127.0.0.1:9100"
+
+        [monitor]
+        sample_interval_secs = 5
+        heavy_max_cpu = 75.0
+        heavy_max_load = 3.5
+
+        [notifications]
+        desktop = true
+        on_success = false
+
+        [http]
+        addr = "127.0.0.1:8080"
+        token = "secret"
+
+        [[jobs]]
+        description = "Nightly backup"
+        function = "backup_fn"
+        delay_secs = 60
+        priority = 5
+        tags = ["heavy"]
+        metadata = { DB_NAME = "prod" }
+    "#;
+    let config = Config::from_toml(text, no_env).unwrap();
+    assert_eq!(config.storage.queue, "data/queue.json");
+    assert_eq!(config.storage.socket, "/tmp/scheduler-test.sock");
+    assert_eq!(config.poll_interval, Duration::from_millis(250));
+    assert_eq!(config.worker.pool_size, 4);
+    assert_eq!(
+        config.worker.functions,
+        Some(vec!["backup_fn".to_string(), "email_fn".to_string()])
+    );
+    assert_eq!(config.telemetry.level, "debug");
+    assert_eq!(config.telemetry.format, LogFormat::Json);
+    assert_eq!(config.telemetry.rotation, LogRotation::Size);
+    assert_eq!(config.telemetry.max_size_bytes, 1024);
+    assert!(!config.telemetry.stdout);
+    assert_eq!(config.metrics_addr.as_deref(), Some("127.0.0.1:9100"));
+    assert_eq!(config.monitor.sample_interval, Duration::from_secs(5));
+    assert_eq!(config.monitor.threshold.max_system_cpu_percent, Some(75.0));
+    assert_eq!(config.monitor.threshold.max_load_average, Some(3.5));
+    assert!(!config.notifications.on_success);
+    assert!(config.notifications.on_failure);
+    assert_eq!(config.http.token.as_deref(), Some("secret"));
+    assert_eq!(config.jobs.len(), 1);
+    assert_eq!(config.jobs[0].priority, 5);
+    assert_eq!(config.jobs[0].max_retries, 3, "retries default to 3");
+}
+
+#[test]
+fn environment_overrides_the_file() {
+    let text = r#"
+        [storage]
+        queue = "from-file.json"
+
+        [worker]
+        pool_size = 2
+    "#;
+    let vars = env(&[
+        ("SCHEDULER_QUEUE", "from-env.json"),
+        ("SCHEDULER_WORKERS", "8"),
+        ("SCHEDULER_NOTIFICATIONS", "off"),
+        ("SCHEDULER_HEAVY_MAX_LOAD", "2"),
+    ]);
+    let config = Config::from_toml(text, vars).unwrap();
+    assert_eq!(config.storage.queue, "from-env.json");
+    assert_eq!(config.worker.pool_size, 8);
+    assert!(!config.notifications.desktop);
+    assert_eq!(config.monitor.threshold.max_load_average, Some(2.0));
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let err = Config::from_toml("[worker]\npool_sise = 2\n", no_env).unwrap_err();
+    assert!(err.contains("pool_sise"), "{}", err);
+}
+
+#[test]
+fn reports_all_invalid_values_together() {
+    let text = r#"
+        [engine]
+        poll_interval_ms = 1
+
+        [worker]
+        pool_size = 0
+
+        [telemetry]
+        format = "xml"
+
+        [http]
+        addr = "not-an-address"
+    "#;
+    let err = Config::from_toml(text, no_env).unwrap_err();
+    assert!(err.contains("engine.poll_interval_ms"), "{}", err);
+    assert!(err.contains("worker.pool_size"), "{}", err);
+    assert!(err.contains("xml"), "{}", err);
+    assert!(err.contains("http.addr 'not-an-address'"), "{}", err);
+    assert!(err.contains("http.token"), "{}", err);
+}
+
+#[test]
+fn rejects_invalid_jobs() {
+    let text = r#"
+        [[jobs]]
+        description = "Past"
+        function = "backup_fn"
+        at = 1
+
+        [[jobs]]
+        description = "Negative"
+        function = "backup_fn"
+        delay_secs = -5
+
+        [[jobs]]
+        description = "Forever"
+        function = "backup_fn"
+        delay_secs = 9223372036854775807
+    "#;
+    let err = Config::from_toml(text, no_env).unwrap_err();
+    assert!(err.contains("'Past'): at 1 is in the past"), "{}", err);
+    assert!(err.contains("'Negative'): delay_secs"), "{}", err);
+    assert!(err.contains("'Forever'): delay_secs 9223372036854775807 is out of range"), "{}", err);
+}
+
+#[test]
+fn job_spec_with_overflowing_delay_is_an_error() {
+    let config = Config::from_toml("[[jobs]]\ndescription = \"Ok\"\nfunction = \"backup_fn\"\n", no_env).unwrap();
+    let mut spec = config.jobs[0].clone();
+    spec.delay_secs = Some(i64::MAX);
+    let err = spec.to_job().unwrap_err();
+    assert!(err.contains("delay_secs 9223372036854775807 is out of range"), "{}", err);
+}
+
+#[test]
+fn validates_functions_against_the_worker() {
+    let registered = vec!["backup_fn".to_string(), "email_fn".to_string()];
+
+    let config = Config::from_toml("", no_env).unwrap();
+    let err = config.validate_functions(&registered).unwrap_err();
+    assert!(err.contains("'hotfix_fn' is not enabled"), "{}", err);
+    assert!(err.contains("available functions: backup_fn, email_fn"), "{}", err);
+
+    let text = r#"
+        jobs = []
+
+        [worker]
+        functions = ["backup_fn", "missing_fn"]
+    "#;
+    let config = Config::from_toml(text, no_env).unwrap();
+    let err = config.validate_functions(&registered).unwrap_err();
+    assert!(err.contains("'missing_fn' is not a registered function"), "{}", err);
+
+    let config = Config::from_toml("jobs = []\n[worker]\nfunctions = [\"email_fn\"]\n", no_env).unwrap();
+    assert!(config.validate_functions(&registered).is_ok());
+}
+
+#[test]
+fn job_spec_builds_a_job() {
+    let text = r#"
+        [[jobs]]
+        description = "Report"
+        function = "email_fn"
+        delay_secs = 30
+        max_retries = 1
+        tags = ["heavy"]
+        metadata = { SMTP_RECIPIENT = "ops@example.com" }
+    "#;
+    let config = Config::from_toml(text, no_env).unwrap();
+    let job = config.jobs[0].to_job().unwrap();
+    assert_eq!(job.description, "Report");
+    assert_eq!(job.max_retries, 1);
+    assert!(job.is_heavy());
+    assert!((job.execution_time - (Job::now() + 30)).abs() <= 1);
+    assert_eq!(
+        job.metadata.unwrap().get("SMTP_RECIPIENT").map(String::as_str),
+        Some("ops@example.com")
+    );
+}
+
+#[test]
+fn explicit_config_path_must_exist() {
+    let path = format!("scheduler_config_missing_{}.toml", uuid::Uuid::new_v4());
+    let err = Config::load_with_env(Some(&path), no_env).unwrap_err();
+    assert!(err.contains(&path), "{}", err);
+}
+
+#[test]
+fn loads_from_file() {
+    let path = format!("scheduler_config_{}.toml", uuid::Uuid::new_v4());
+    std::fs::write(&path, "jobs = []\n[worker]\npool_size = 3\n").unwrap();
+    let config = Config::load_with_env(Some(&path), no_env);
+    let _ = std::fs::remove_file(&path);
+
+    let config = config.unwrap();
+    assert_eq!(config.worker.pool_size, 3);
+    assert!(config.jobs.is_empty());
+    assert_eq!(config.source.unwrap().to_str(), Some(path.as_str()));
+}
+
+#[test]
+fn restrict_to_keeps_only_named_functions() {
+    let mut worker = Worker::new();
+    worker.register("a", |_| Ok(()));
+    worker.register("b", |_| Ok(()));
+
+    assert!(worker.restrict_to(&["c".to_string()]).is_err());
+    assert_eq!(worker.functions(), vec!["a", "b"], "unchanged after an error");
+
+    worker.restrict_to(&["b".to_string()]).unwrap();
+    assert_eq!(worker.functions(), vec!["b"]);
+}
+
+#[test]
+fn worker_pool_runs_jobs_on_every_worker() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (tx, rx) = mpsc::channel();
+    let handles = worker::start_pool(3, rx, || {
+        let seen = Arc::clone(&seen);
+        let mut worker = Worker::new();
+        worker.register("record", move |ctx| {
+            seen.lock().unwrap().push(ctx.job.description.clone());
+            Ok(())
+        });
+        worker
+    });
+    assert_eq!(handles.len(), 3);
+
+    for i in 0..6 {
+        tx.send(Job::new(Job::now(), 1, format!("job {}", i), "record", 0).unwrap())
+            .unwrap();
+    }
+    drop(tx);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(seen.lock().unwrap().len(), 6);
+}
+
+#[test]
+fn example_file_is_valid() {
+    let config = Config::load_with_env(Some("scheduler.example.toml"), no_env).unwrap();
+    assert_eq!(config.jobs.len(), 2);
+}
+
+#[test]
+fn definitions_section_and_job_schedules() {
+    let text = r#"
+        [definitions]
+        dir = "deploy/jobs"
+        watch = false
+
+        [[jobs]]
+        description = "Bad schedule"
+        function = "backup_fn"
+        schedule = "every week"
+    "#;
+    let err = Config::from_toml(text, no_env).unwrap_err();
+    assert!(err.contains("'Bad schedule'): unknown interval unit"), "{}", err);
+
+    let config = Config::from_toml("[definitions]\nwatch = false\n", env(&[("SCHEDULER_JOBS_DIR", "other")])).unwrap();
+    assert_eq!(config.definitions.dir.to_str(), Some("other"));
+    assert!(!config.definitions.watch);
+}
+
+#[test]
+fn email_section_selects_the_transport() {
+    let config = Config::from_toml("", no_env).unwrap();
+    assert_eq!(config.email.transport, TransportKind::Resend);
+
+    let text = r#"
+        [email]
+        transport = "smtp"
+        from = "Scheduler <scheduler@example.com>"
+
+        [email.smtp]
+        host = "mail.example.com"
+        security = "tls"
+        username = "scheduler"
+        password = "secret"
+        timeout_secs = 5
+    "#;
+    let config = Config::from_toml(text, env(&[("SMTP_PORT", "2465")])).unwrap();
+    assert_eq!(config.email.transport, TransportKind::Smtp);
+    assert_eq!(config.email.from.as_deref(), Some("Scheduler <scheduler@example.com>"));
+    assert_eq!(config.email.smtp.host.as_deref(), Some("mail.example.com"));
+    assert_eq!(config.email.smtp.port, Some(2465));
+    assert_eq!(config.email.smtp.security, SmtpSecurity::Tls);
+    assert_eq!(config.email.smtp.timeout, Duration::from_secs(5));
+
+    let config = Config::from_toml("", env(&[("EMAIL_TRANSPORT", "outbox"), ("EMAIL_OUTBOX_DIR", "mail")])).unwrap();
+    assert_eq!(config.email.transport, TransportKind::Outbox);
+    assert_eq!(config.email.outbox_dir.to_str(), Some("mail"));
+
+    let err = Config::from_toml("[email]\ntransport = \"smtp\"\n[email.smtp]\nsecurity = \"maybe\"\n", no_env).unwrap_err();
+    assert!(err.contains("email.smtp.host (or SMTP_HOST) is required"), "{}", err);
+    assert!(err.contains("email.smtp.security: expected starttls, tls or none"), "{}", err);
+    let err = Config::from_toml("", env(&[("EMAIL_TRANSPORT", "pigeon")])).unwrap_err();
+    assert!(err.contains("EMAIL_TRANSPORT: expected resend, smtp or outbox"), "{}", err);
+}
+
+#[test]
+fn tui_section_sets_the_log_size() {
+    let config = Config::from_toml("", no_env).unwrap();
+    assert_eq!(config.tui.log_lines, scheduler::tui::DEFAULT_LOG_LINES);
+
+    let config = Config::from_toml("[tui]\nlog_lines = 5000\n", no_env).unwrap();
+    assert_eq!(config.tui.log_lines, 5000);
+    let config = Config::from_toml("[tui]\nlog_lines = 5000\n", env(&[("SCHEDULER_TUI_LOG_LINES", "50")])).unwrap();
+    assert_eq!(config.tui.log_lines, 50);
+
+    let err = Config::from_toml("[tui]\nlog_lines = 0\n", no_env).unwrap_err();
+    assert!(err.contains("tui.log_lines must be at least 1"), "{}", err);
+}
+
+#[test]
+fn tui_section_selects_and_overrides_the_keymap() {
+    use scheduler::tui::{Action, KeyContext, Keymap, Preset};
+    let config = Config::from_toml("", no_env).unwrap();
+    assert_eq!(config.tui.keymap().unwrap(), Keymap::default());
+    assert!(config.tui.mouse);
+
+    let text = "[tui]\nkeymap = \"vim\"\nmouse = false\n\n[tui.keys]\nadd_job = [\"n\"]\n";
+    let config = Config::from_toml(text, no_env).unwrap();
+    assert_eq!(config.tui.preset, Preset::Vim);
+    assert!(!config.tui.mouse);
+    let keymap = config.tui.keymap().unwrap();
+    assert_eq!(keymap.describe(Action::AddJob), "N");
+    assert_eq!(keymap.describe(Action::Down), "J, \u{2193}");
+    assert!(keymap.help(KeyContext::List).iter().any(|(keys, _)| keys == "N"));
+
+    // The environment picks the preset, the file's overrides still apply
+    let config = Config::from_toml(text, env(&[("SCHEDULER_TUI_KEYMAP", "default")])).unwrap();
+    assert_eq!(config.tui.keymap().unwrap().describe(Action::Down), "\u{2193}");
+    assert_eq!(config.tui.keymap().unwrap().describe(Action::AddJob), "N");
+
+    let err = Config::from_toml("[tui]\nkeymap = \"emacs\"\n[tui.keys]\nsort = [\"f\"]\n", no_env).unwrap_err();
+    assert!(err.contains("tui.keymap: expected default or vim, got 'emacs'"), "{}", err);
+    assert!(err.contains("tui.keys: F is bound to both filter_function and sort (job list)"), "{}", err);
+}
+
+#[test]
+fn tui_theme_comes_from_the_file_env_or_no_color() {
+    use scheduler::tui::ThemeName;
+    assert_eq!(Config::from_toml("", no_env).unwrap().tui.theme, None);
+    let config = Config::from_toml("[tui]\ntheme = \"light\"\n", no_env).unwrap();
+    assert_eq!(config.tui.theme, Some(ThemeName::Light));
+    let config = Config::from_toml("[tui]\ntheme = \"light\"\n", env(&[("SCHEDULER_TUI_THEME", "high-contrast")])).unwrap();
+    assert_eq!(config.tui.theme, Some(ThemeName::HighContrast));
+
+    // NO_COLOR applies when no theme was chosen, and not when it is empty
+    let config = Config::from_toml("", env(&[("NO_COLOR", "1")])).unwrap();
+    assert_eq!(config.tui.theme, Some(ThemeName::Monochrome));
+    let config = Config::from_toml("[tui]\ntheme = \"dark\"\n", env(&[("NO_COLOR", "1")])).unwrap();
+    assert_eq!(config.tui.theme, Some(ThemeName::Dark));
+    assert_eq!(Config::from_toml("", env(&[("NO_COLOR", "")])).unwrap().tui.theme, None);
+
+    let err = Config::from_toml("[tui]\ntheme = \"neon\"\n", no_env).unwrap_err();
+    assert!(err.contains("tui.theme: expected dark, light, high-contrast or monochrome, got 'neon'"), "{}", err);
+}
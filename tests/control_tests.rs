@@ -0,0 +1,165 @@
+#![cfg(unix)]
+
+use scheduler::control::client::ControlClient;
+use scheduler::control::server::ControlServer;
+use scheduler::control::{ControlContext, Request, Response};
+use scheduler::engine::TimePriorityEngine;
+use scheduler::events::EventBus;
+use scheduler::history::HistoryStore;
+use scheduler::job::{Job, Status};
+use scheduler::queue::QueueManager;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+fn start_server() -> (ControlServer, Receiver<Job>, Arc<TimePriorityEngine>) {
+    let queue = Arc::new(Mutex::new(QueueManager::new()));
+    let (worker_tx, worker_rx) = mpsc::channel();
+    let engine = Arc::new(TimePriorityEngine::new(Arc::clone(&queue), worker_tx.clone()));
+    let ctx = Arc::new(ControlContext {
+        queue,
+        history: Arc::new(Mutex::new(HistoryStore::open(&format!(
+            "scheduler_control_{}.jsonl",
+            Uuid::new_v4()
+        )))),
+        worker_tx,
+        engine: Some(Arc::clone(&engine)),
+        events: EventBus::new(),
+        functions: vec!["hotfix_fn".to_string()],
+    });
+    let path = std::env::temp_dir().join(format!("scheduler_{}.sock", Uuid::new_v4()));
+    let server = ControlServer::bind(&path, ctx).unwrap();
+    (server, worker_rx, engine)
+}
+
+#[test]
+fn client_manages_queue_over_socket() {
+    let (server, _worker_rx, _engine) = start_server();
+    let mut client = ControlClient::connect(server.socket_path()).unwrap();
+
+    let job = Job::new(Job::now() + 600, 2, "remote", "hotfix_fn", 3).unwrap();
+    let id = client.add(job).unwrap().id;
+    assert_eq!(client.list().unwrap().len(), 1);
+
+    assert_eq!(client.pause(id).unwrap().status, Status::Paused);
+    assert!(client.pause(id).is_err());
+    assert_eq!(client.resume(id).unwrap().status, Status::Pending);
+
+    let (job, runs) = client.get(id).unwrap();
+    assert_eq!(job.description, "remote");
+    assert!(runs.is_empty());
+
+    client.remove(id).unwrap();
+    assert!(client.list().unwrap().is_empty());
+    assert!(client.get(id).is_err());
+}
+
+#[test]
+fn run_now_dispatches_copy_and_keeps_schedule() {
+    let (server, worker_rx, _engine) = start_server();
+    let mut client = ControlClient::connect(server.socket_path()).unwrap();
+
+    let mut job = Job::new(Job::now() + 600, 1, "later", "hotfix_fn", 3).unwrap();
+    job.schedule = Some("every 1h".to_string());
+    let queued = client.add(job).unwrap();
+    client.run_now(queued.id).unwrap();
+
+    // The copy is a one-off under its own id, so its retries can't replace the queued job
+    let dispatched = worker_rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_ne!(dispatched.id, queued.id);
+    assert_eq!(dispatched.schedule, None);
+    assert_eq!(dispatched.description, "later");
+    let jobs = client.list().unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].id, queued.id);
+    assert_eq!(jobs[0].status, Status::Pending);
+    assert_eq!(jobs[0].execution_time, queued.execution_time);
+}
+
+#[test]
+fn add_assigns_a_new_id_and_validates_the_job() {
+    let (server, _worker_rx, _engine) = start_server();
+    let mut client = ControlClient::connect(server.socket_path()).unwrap();
+
+    let first = client.add(Job::new(Job::now() + 600, 1, "first", "hotfix_fn", 0).unwrap()).unwrap();
+    // A client-supplied id that is already queued does not replace that job
+    let mut second = Job::new(Job::now() + 900, 1, "second", "hotfix_fn", 0).unwrap();
+    second.id = first.id;
+    let second = client.add(second).unwrap();
+    assert_ne!(second.id, first.id);
+    assert_eq!(client.list().unwrap().len(), 2);
+    assert_eq!(client.get(first.id).unwrap().0.description, "first");
+
+    let unknown = Job::new(Job::now() + 600, 1, "unknown", "nope_fn", 0).unwrap();
+    assert!(client.add(unknown).unwrap_err().contains("No function registered for 'nope_fn'"));
+    let mut bad_schedule = Job::new(Job::now() + 600, 1, "bad", "hotfix_fn", 0).unwrap();
+    bad_schedule.schedule = Some("every fortnight-ish".to_string());
+    assert!(client.add(bad_schedule).unwrap_err().contains("Invalid schedule"));
+    let mut past = Job::new(Job::now() + 600, 1, "past", "hotfix_fn", 0).unwrap();
+    past.execution_time = Job::now() - 60;
+    assert!(client.add(past).unwrap_err().contains("in the past"));
+    assert_eq!(client.list().unwrap().len(), 2);
+
+    // State a client sends along is reset, so the job is queued like any new one
+    let mut forged = Job::new(Job::now() + 600, 1, "forged", "hotfix_fn", 3).unwrap();
+    forged.status = Status::Paused;
+    forged.retry_count = 2;
+    forged.definition = Some("nightly-backup".to_string());
+    let added = client.add(forged).unwrap();
+    let (queued, _) = client.get(added.id).unwrap();
+    assert_eq!(queued.status, Status::Pending);
+    assert_eq!(queued.retry_count, 0);
+    assert_eq!(queued.definition, None);
+}
+
+#[test]
+fn engine_can_be_stopped_and_started() {
+    let (server, _worker_rx, engine) = start_server();
+    let mut client = ControlClient::connect(server.socket_path()).unwrap();
+
+    assert!(!client.engine_status().unwrap().running);
+    assert!(client.engine_start().unwrap().running);
+    assert!(engine.is_running());
+    assert!(!client.engine_stop().unwrap().running);
+}
+
+#[test]
+fn raw_json_lines_protocol() {
+    let (server, _worker_rx, _engine) = start_server();
+    let mut stream = UnixStream::connect(server.socket_path()).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    writeln!(stream, "{{\"op\":\"list\"}}").unwrap();
+    writeln!(stream, "not json").unwrap();
+
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert!(matches!(serde_json::from_str(&line).unwrap(), Response::Jobs(jobs) if jobs.is_empty()));
+
+    line.clear();
+    reader.read_line(&mut line).unwrap();
+    assert!(matches!(serde_json::from_str(&line).unwrap(), Response::Error(_)));
+
+    let request = serde_json::to_string(&Request::History { limit: 5, job: None }).unwrap();
+    assert_eq!(request, r#"{"op":"history","limit":5,"job":null}"#);
+}
+
+#[test]
+fn second_server_on_live_socket_is_rejected() {
+    let (server, _worker_rx, _engine) = start_server();
+    let queue = Arc::new(Mutex::new(QueueManager::new()));
+    let (worker_tx, _rx) = mpsc::channel();
+    let ctx = Arc::new(ControlContext {
+        queue,
+        history: Arc::new(Mutex::new(HistoryStore::open("unused_history.jsonl"))),
+        worker_tx,
+        engine: None,
+        events: EventBus::new(),
+        functions: vec!["hotfix_fn".to_string()],
+    });
+    let err = ControlServer::bind(server.socket_path(), ctx).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+}
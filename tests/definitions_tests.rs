@@ -0,0 +1,194 @@
+use scheduler::definitions::{self, ReconcileReport};
+use scheduler::job::Job;
+use scheduler::queue::QueueManager;
+use scheduler::schedule::Schedule;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn temp_dir() -> PathBuf {
+    let dir = PathBuf::from(format!("scheduler_jobs_{}", uuid::Uuid::new_v4()));
+    fs::create_dir(&dir).unwrap();
+    dir
+}
+
+fn names(list: &[String]) -> Vec<&str> {
+    list.iter().map(String::as_str).collect()
+}
+
+const BACKUP: &str = r#"
+name = "nightly-backup"
+function = "backup_fn"
+schedule = "0 2 * * *"
+priority = 5
+payload = { DB_NAME = "prod" }
+"#;
+
+#[test]
+fn parses_intervals_and_cron() {
+    let every: Schedule = "every 15m".parse().unwrap();
+    assert_eq!(every.next_after(1_000), Some(1_900));
+    assert!("every 0s".parse::<Schedule>().is_err());
+    assert!("every 5w".parse::<Schedule>().is_err());
+    let err = "every 999999999999999d".parse::<Schedule>().unwrap_err();
+    assert!(err.contains("too long"), "{}", err);
+    assert_eq!("every 1d".parse::<Schedule>().unwrap().next_after(i64::MAX - 10), None);
+
+    // 02:00 UTC on 2001-09-09 follows 1_000_000_000 (01:46:40 UTC)
+    let cron: Schedule = "0 2 * * *".parse().unwrap();
+    assert_eq!(cron.next_after(1_000_000_000), Some(1_000_000_000 + 800));
+    assert!("@daily".parse::<Schedule>().is_ok());
+    assert!("not a schedule".parse::<Schedule>().is_err());
+}
+
+#[test]
+fn parses_toml_and_yaml_files() {
+    let single = definitions::parse(BACKUP, false).unwrap();
+    assert_eq!(single.len(), 1);
+    assert_eq!(single[0].description, "nightly-backup", "description defaults to name");
+    assert_eq!(single[0].metadata.get("DB_NAME").map(String::as_str), Some("prod"));
+
+    let yaml = r#"
+jobs:
+  - name: report
+    description: Weekly report
+    function: email_fn
+    schedule: every 7d
+    max_retries: 1
+  - name: cleanup
+    function: hotfix_fn
+    schedule: "@hourly"
+    tags: [heavy]
+"#;
+    let list = definitions::parse(yaml, true).unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].description, "Weekly report");
+    assert_eq!(list[0].max_retries, 1);
+    assert_eq!(list[1].tags, vec!["heavy"]);
+
+    let err = definitions::parse("name = \"x\"\nfunction = \"f\"\ncolour = \"red\"\n", false).unwrap_err();
+    assert!(err.contains("colour"), "{}", err);
+}
+
+#[test]
+fn load_dir_reports_every_problem() {
+    let dir = temp_dir();
+    fs::write(dir.join("a.toml"), BACKUP).unwrap();
+    fs::write(dir.join("b.yaml"), "name: nightly-backup\nfunction: backup_fn\nschedule: every 1h\n").unwrap();
+    fs::write(dir.join("c.yml"), "name: no-schedule\nfunction: email_fn\n").unwrap();
+    fs::write(dir.join("d.toml"), "name = \"bad\"\nfunction = \"f\"\nschedule = \"every soon\"\n").unwrap();
+    fs::write(dir.join("notes.txt"), "ignored").unwrap();
+    let result = definitions::load_dir(&dir);
+    let _ = fs::remove_dir_all(&dir);
+
+    let err = result.unwrap_err();
+    assert!(err.contains("'nightly-backup': name is defined more than once"), "{}", err);
+    assert!(err.contains("'no-schedule': schedule is required"), "{}", err);
+    assert!(err.contains("'bad': unknown interval unit 'soon'"), "{}", err);
+
+    assert!(definitions::load_dir(&PathBuf::from("scheduler_jobs_missing")).unwrap().is_empty());
+}
+
+#[test]
+fn reconcile_adds_updates_and_removes() {
+    let mut q = QueueManager::new();
+    let manual = Job::new(Job::now() + 60, 1, "Added by hand", "email_fn", 0).unwrap();
+    q.push(manual.clone());
+
+    let specs = definitions::parse(
+        r#"
+[[jobs]]
+name = "backup"
+function = "backup_fn"
+schedule = "every 1h"
+
+[[jobs]]
+name = "report"
+function = "email_fn"
+schedule = "every 1d"
+"#,
+        false,
+    )
+    .unwrap();
+    let report = definitions::reconcile(&mut q, &specs);
+    assert_eq!(names(&report.added), vec!["backup", "report"]);
+    assert_eq!(q.len(), 3);
+
+    // Same definitions again: nothing to do
+    assert_eq!(definitions::reconcile(&mut q, &specs), ReconcileReport::default());
+
+    let backup_id = q.snapshot().iter().find(|j| j.definition.as_deref() == Some("backup")).unwrap().id;
+    let specs = definitions::parse(
+        "[[jobs]]\nname = \"backup\"\nfunction = \"backup_fn\"\nschedule = \"every 1h\"\npriority = 7\n",
+        false,
+    )
+    .unwrap();
+    let report = definitions::reconcile(&mut q, &specs);
+    assert_eq!(names(&report.updated), vec!["backup"]);
+    assert_eq!(names(&report.removed), vec!["report"]);
+    assert_eq!(q.get(backup_id).unwrap().priority, 7, "updated in place");
+    assert!(q.get(manual.id).is_some(), "runtime jobs are untouched");
+    assert_eq!(q.len(), 2);
+
+    // Definitions are matched by name, so a new description is an update, not a new job
+    let specs = definitions::parse(
+        "[[jobs]]\nname = \"backup\"\ndescription = \"Hourly backup\"\nfunction = \"backup_fn\"\nschedule = \"every 1h\"\npriority = 7\n",
+        false,
+    )
+    .unwrap();
+    let report = definitions::reconcile(&mut q, &specs);
+    assert_eq!(names(&report.updated), vec!["backup"]);
+    assert!(report.added.is_empty() && report.removed.is_empty(), "{:?}", report);
+    assert_eq!(q.get(backup_id).unwrap().description, "Hourly backup");
+    assert_eq!(q.len(), 2);
+}
+
+#[test]
+fn reconcile_leaves_queued_retries_alone() {
+    let mut q = QueueManager::new();
+    let specs = definitions::parse(BACKUP, false).unwrap();
+    definitions::reconcile(&mut q, &specs);
+    let scheduled = q.snapshot()[0].clone();
+
+    // What the worker queues after a failed run: same job, later, without a schedule
+    let mut retry = scheduled.next_occurrence(Job::now()).unwrap();
+    retry.execution_time = Job::now() + 5;
+    retry.retry_count = 1;
+    retry.schedule = None;
+    q.push(retry.clone());
+
+    assert_eq!(definitions::reconcile(&mut q, &specs), ReconcileReport::default());
+    assert_eq!(q.len(), 2);
+    assert_eq!(q.get(scheduled.id), Some(&scheduled));
+    assert_eq!(q.get(retry.id), Some(&retry));
+    assert_eq!(q.snapshot().iter().filter(|j| j.schedule.is_some()).count(), 1);
+}
+
+#[test]
+fn check_functions_lists_unknown_ones() {
+    let specs = definitions::parse(BACKUP, false).unwrap();
+    assert!(definitions::check_functions(&specs, &["backup_fn".to_string()]).is_ok());
+    let err = definitions::check_functions(&specs, &["email_fn".to_string()]).unwrap_err();
+    assert!(err.contains("function 'backup_fn' is not enabled"), "{}", err);
+}
+
+#[test]
+fn watcher_applies_changed_files() {
+    let dir = temp_dir();
+    let queue = Arc::new(Mutex::new(QueueManager::new()));
+    let available = vec!["backup_fn".to_string()];
+    definitions::start_watcher(dir.clone(), &queue, available, Duration::from_millis(20));
+
+    fs::write(dir.join("backup.toml"), BACKUP).unwrap();
+    let mut queued = 0;
+    for _ in 0..100 {
+        std::thread::sleep(Duration::from_millis(20));
+        queued = queue.lock().unwrap().len();
+        if queued == 1 {
+            break;
+        }
+    }
+    let _ = fs::remove_dir_all(&dir);
+    assert_eq!(queued, 1);
+}
@@ -0,0 +1,264 @@
+use scheduler::email::outbox::Outbox;
+use scheduler::email::{EmailConfig, SmtpSecurity, TransportKind};
+use scheduler::events::{EventBus, SchedulerEvent};
+use scheduler::history::{RunOutcome, RunRecord};
+use scheduler::job::Job;
+use scheduler::tasks;
+use scheduler::worker::Worker;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use uuid::Uuid;
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("scheduler_email_{}", Uuid::new_v4()))
+}
+
+fn email_job(payload: &[(&str, &str)]) -> Job {
+    let mut job = Job::new(Job::now(), 1, "Report", "email_fn", 0).unwrap();
+    job.metadata = Some(
+        payload
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+    );
+    job
+}
+
+/// Runs the job on a worker whose `email_fn` uses `config`, or the per-job settings without one
+fn run(job: &mut Job, config: Option<EmailConfig>) -> RunRecord {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let mut worker = Worker::new();
+    tasks::register_builtin(&mut worker);
+    if let Some(config) = config {
+        tasks::register_email(&mut worker, config);
+    }
+    worker.set_events(bus);
+    worker.run_job(job);
+    rx.try_iter()
+        .find_map(|e: SchedulerEvent| RunRecord::from_event(&e))
+        .expect("finished attempt")
+}
+
+fn outbox_config(dir: &Path) -> EmailConfig {
+    EmailConfig {
+        transport: TransportKind::Outbox,
+        from: Some("Scheduler <scheduler@example.com>".to_string()),
+        outbox_dir: dir.to_path_buf(),
+        ..EmailConfig::default()
+    }
+}
+
+#[test]
+fn outbox_stores_html_recipients_and_attachments() {
+    let dir = temp_dir();
+    fs::create_dir_all(&dir).unwrap();
+    let report = dir.join("report.csv");
+    fs::write(&report, "job,status\nbackup,ok\n").unwrap();
+
+    let mut job = email_job(&[
+        ("SMTP_RECIPIENT", "ops@example.com, Dev Team <dev@example.com>"),
+        ("EMAIL_CC", "lead@example.com"),
+        ("EMAIL_BCC", "audit@example.com"),
+        ("EMAIL_SUBJECT", "Nightly report"),
+        ("EMAIL_BODY", "All jobs ran."),
+        ("EMAIL_HTML", "<p>All jobs <b>ran</b>.</p>"),
+        ("EMAIL_ATTACHMENTS", report.to_str().unwrap()),
+    ]);
+    let record = run(&mut job, Some(outbox_config(&dir)));
+    assert_eq!(record.outcome, RunOutcome::Success, "{:?}", record.message);
+
+    let messages = Outbox::new(&dir).messages();
+    assert_eq!(messages.len(), 1);
+    assert!(record.output.unwrap().contains(messages[0].to_str().unwrap()));
+    assert!(fs::read_dir(dir.join("tmp")).unwrap().next().is_none());
+
+    let message = fs::read_to_string(&messages[0]).unwrap();
+    assert!(message.contains("From: Scheduler <scheduler@example.com>"), "{}", message);
+    assert!(message.contains("To: ops@example.com, \"Dev Team\" <dev@example.com>"), "{}", message);
+    assert!(message.contains("Cc: lead@example.com"), "{}", message);
+    assert!(message.contains("Bcc: audit@example.com"), "{}", message);
+    assert!(message.contains("Subject: Nightly report"), "{}", message);
+    assert!(message.contains("multipart/mixed"), "{}", message);
+    assert!(message.contains("multipart/alternative"), "{}", message);
+    assert!(message.contains("All jobs <b>ran</b>."), "{}", message);
+    assert!(message.contains("filename=\"report.csv\""), "{}", message);
+    assert!(message.contains("Content-Type: text/csv"), "{}", message);
+}
+
+#[test]
+fn per_job_settings_pick_the_transport_without_config() {
+    let dir = temp_dir();
+    let mut job = email_job(&[
+        ("EMAIL_TRANSPORT", "outbox"),
+        ("EMAIL_OUTBOX_DIR", dir.to_str().unwrap()),
+        ("SMTP_FROM", "scheduler@example.com"),
+        ("SMTP_RECIPIENT", "ops@example.com"),
+        ("EMAIL_HTML", "<h1>Done</h1>"),
+    ]);
+    assert_eq!(run(&mut job, None).outcome, RunOutcome::Success);
+
+    let message = fs::read_to_string(&Outbox::new(&dir).messages()[0]).unwrap();
+    assert!(message.contains("Content-Type: text/html"), "{}", message);
+    assert!(!message.contains("multipart"), "HTML-only mail has a single part: {}", message);
+}
+
+#[test]
+fn invalid_messages_fail_before_sending() {
+    let dir = temp_dir();
+    let record = run(&mut email_job(&[]), Some(outbox_config(&dir)));
+    assert_eq!(record.message.as_deref(), Some("No recipients: set SMTP_RECIPIENT"));
+
+    let record = run(&mut email_job(&[("SMTP_RECIPIENT", "not an address")]), Some(outbox_config(&dir)));
+    assert!(record.message.unwrap().starts_with("Invalid email address 'not an address'"));
+
+    let mut job = email_job(&[("SMTP_RECIPIENT", "ops@example.com"), ("EMAIL_ATTACHMENTS", "/nonexistent/report.pdf")]);
+    let record = run(&mut job, Some(outbox_config(&dir)));
+    assert!(record.message.unwrap().starts_with("Cannot attach /nonexistent/report.pdf"));
+    assert!(Outbox::new(&dir).messages().is_empty());
+
+    let config = EmailConfig {
+        transport: TransportKind::Smtp,
+        ..EmailConfig::default()
+    };
+    let mut job = email_job(&[("SMTP_FROM", "a@example.com"), ("SMTP_RECIPIENT", "b@example.com")]);
+    assert_eq!(run(&mut job, Some(config)).message.as_deref(), Some("SMTP_HOST is not set"));
+}
+
+/// Minimal SMTP server for one message; hands back the recipients and the DATA section
+fn smtp_server() -> (u16, mpsc::Receiver<(Vec<String>, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(&stream);
+        let mut writer = &stream;
+        writer.write_all(b"220 localhost ESMTP test\r\n").unwrap();
+        let mut recipients = Vec::new();
+        let mut data = String::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            let command = line.to_uppercase();
+            let reply: &[u8] = if command.starts_with("EHLO") {
+                b"250 localhost\r\n"
+            } else if command.starts_with("RCPT TO:") {
+                recipients.push(line[8..].trim().to_string());
+                b"250 OK\r\n"
+            } else if command.starts_with("DATA") {
+                writer.write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n").unwrap();
+                loop {
+                    let mut data_line = String::new();
+                    reader.read_line(&mut data_line).unwrap();
+                    if data_line == ".\r\n" {
+                        break;
+                    }
+                    data.push_str(&data_line);
+                }
+                b"250 2.0.0 queued as 42\r\n"
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 bye\r\n").unwrap();
+                break;
+            } else {
+                b"250 OK\r\n"
+            };
+            writer.write_all(reply).unwrap();
+        }
+        let _ = tx.send((recipients, data));
+    });
+    (port, rx)
+}
+
+#[test]
+fn smtp_delivers_to_every_recipient_without_a_bcc_header() {
+    let (port, received) = smtp_server();
+    let mut config = EmailConfig {
+        transport: TransportKind::Smtp,
+        from: Some("scheduler@example.com".to_string()),
+        ..EmailConfig::default()
+    };
+    config.smtp.host = Some("127.0.0.1".to_string());
+    config.smtp.port = Some(port);
+    config.smtp.security = SmtpSecurity::None;
+
+    let mut job = email_job(&[
+        ("SMTP_RECIPIENT", "ops@example.com;dev@example.com"),
+        ("EMAIL_BCC", "audit@example.com"),
+        ("EMAIL_SUBJECT", "Hello"),
+        ("EMAIL_BODY", "Plain text only"),
+    ]);
+    let record = run(&mut job, Some(config));
+    assert_eq!(record.outcome, RunOutcome::Success, "{:?}", record.message);
+    assert_eq!(record.output.as_deref(), Some("250 2.0.0 queued as 42"));
+
+    let (recipients, data) = received.recv().unwrap();
+    assert_eq!(recipients, ["<ops@example.com>", "<dev@example.com>", "<audit@example.com>"]);
+    assert!(data.contains("Subject: Hello"), "{}", data);
+    assert!(data.contains("Plain text only"), "{}", data);
+    assert!(!data.contains("audit@example.com"), "Bcc must not leak: {}", data);
+}
+
+#[test]
+fn resend_posts_json_with_attachments() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/emails", listener.local_addr().unwrap());
+    let (tx, received) = mpsc::channel();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(&stream);
+        let mut headers = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                content_length = value.trim().parse().unwrap();
+            }
+            headers.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let reply = r#"{"id":"email-123"}"#;
+        let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", reply.len(), reply);
+        (&stream).write_all(response.as_bytes()).unwrap();
+        let _ = tx.send((headers, String::from_utf8(body).unwrap()));
+    });
+
+    let attachment = temp_dir().with_extension("txt");
+    fs::write(&attachment, "hi").unwrap();
+    let mut config = EmailConfig::default();
+    config.resend.api_key = Some("re_test".to_string());
+    config.resend.endpoint = endpoint;
+    let mut job = email_job(&[
+        ("SMTP_RECIPIENT", "a@example.com, b@example.com"),
+        ("EMAIL_CC", "c@example.com"),
+        ("EMAIL_HTML", "<p>hi</p>"),
+        ("EMAIL_ATTACHMENTS", attachment.to_str().unwrap()),
+    ]);
+    let record = run(&mut job, Some(config));
+    assert_eq!(record.outcome, RunOutcome::Success, "{:?}", record.message);
+    assert_eq!(record.output.as_deref(), Some("Resend id email-123"));
+
+    let (headers, body) = received.recv().unwrap();
+    assert!(headers.to_lowercase().contains("authorization: bearer re_test"), "{}", headers);
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["from"], "onboarding@resend.dev");
+    assert_eq!(body["to"], serde_json::json!(["a@example.com", "b@example.com"]));
+    assert_eq!(body["cc"], serde_json::json!(["c@example.com"]));
+    assert!(body.get("bcc").is_none() && body.get("text").is_none());
+    assert_eq!(body["html"], "<p>hi</p>");
+    assert_eq!(body["attachments"][0]["content"], "aGk=");
+    assert_eq!(body["attachments"][0]["content_type"], "text/plain");
+}
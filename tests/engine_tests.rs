@@ -1,5 +1,5 @@
 use chrono::Utc;
-use scheduler::{engine::TimePriorityEngine, job::Job, queue::QueueManager};
+use scheduler::{engine::TimePriorityEngine, job::Job, queue::QueueManager, worker::Worker};
 use std::sync::{Arc, Mutex, mpsc};
 use std::time::Duration;
 
@@ -31,3 +31,57 @@ fn engine_dispatches_jobs_on_time() {
 
     engine.stop();
 }
+
+#[test]
+fn engine_queues_next_run_of_recurring_job() {
+    let queue = Arc::new(Mutex::new(QueueManager::new()));
+    let (tx, rx) = mpsc::channel();
+    let engine = TimePriorityEngine::new(Arc::clone(&queue), tx);
+
+    let now = Utc::now().timestamp();
+    let mut job = Job::new(now, 1, "recurring", "fn", 3).unwrap();
+    job.schedule = Some("every 1h".to_string());
+    queue.lock().unwrap().push(job.clone());
+
+    engine.start();
+    let dispatched = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    engine.stop();
+
+    assert_eq!(dispatched.id, job.id);
+    let queued = queue.lock().unwrap().snapshot();
+    assert_eq!(queued.len(), 1);
+    assert_ne!(queued[0].id, job.id);
+    assert_eq!(queued[0].schedule.as_deref(), Some("every 1h"));
+    assert!(queued[0].execution_time >= now + 3600);
+}
+
+#[test]
+fn failed_recurring_job_queues_one_next_run() {
+    let queue = Arc::new(Mutex::new(QueueManager::new()));
+    let (tx, rx) = mpsc::channel();
+    let engine = TimePriorityEngine::new(Arc::clone(&queue), tx);
+    let mut worker = Worker::new();
+    worker.register("flaky", |_ctx| Err("boom".to_string()));
+    worker.set_queue(Arc::clone(&queue));
+
+    let now = Utc::now().timestamp();
+    let mut job = Job::new(now, 1, "recurring", "flaky", 1).unwrap();
+    job.schedule = Some("every 1h".to_string());
+    queue.lock().unwrap().push(job.clone());
+
+    engine.start();
+    let mut first = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    worker.run_job(&mut first);
+    // Bring the retry forward so the engine dispatches it too
+    assert!(queue.lock().unwrap().reschedule(job.id, now));
+    let mut retry = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    engine.stop();
+    assert_eq!(retry.id, job.id);
+    assert_eq!(retry.schedule, None);
+    worker.run_job(&mut retry);
+
+    let queued = queue.lock().unwrap().snapshot();
+    assert_eq!(queued.len(), 1);
+    assert_ne!(queued[0].id, job.id);
+    assert_eq!(queued[0].schedule.as_deref(), Some("every 1h"));
+}
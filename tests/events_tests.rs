@@ -0,0 +1,112 @@
+use scheduler::events::{EventBus, JobEventKind, LogSource, SchedulerEvent};
+use scheduler::history::{RunOutcome, RunRecord};
+use scheduler::job::Job;
+use scheduler::queue::QueueManager;
+use scheduler::worker::Worker;
+use std::collections::HashMap;
+
+fn job(function: &str, max_retries: u32) -> Job {
+    Job::new(Job::now() + 60, 1, "evented", function, max_retries).unwrap()
+}
+
+#[test]
+fn every_subscriber_receives_events() {
+    let bus = EventBus::new();
+    let a = bus.subscribe();
+    let b = bus.subscribe();
+
+    let mut q = QueueManager::new();
+    q.set_events(bus.clone());
+    let j = job("fn", 0);
+    let id = j.id;
+    q.push(j);
+    q.remove(id);
+
+    for rx in [a, b] {
+        let kinds: Vec<JobEventKind> = rx
+            .try_iter()
+            .filter_map(|e| match e {
+                SchedulerEvent::Job(e) => Some(e.kind),
+                _ => None,
+            })
+            .collect();
+        assert!(matches!(kinds[0], JobEventKind::Added { .. }));
+        assert_eq!(kinds[1], JobEventKind::Cancelled);
+    }
+}
+
+#[test]
+fn dropped_subscribers_are_forgotten() {
+    let bus = EventBus::new();
+    drop(bus.subscribe());
+    let _live = bus.subscribe();
+
+    let mut q = QueueManager::new();
+    q.set_events(bus.clone());
+    q.push(job("fn", 0));
+    assert_eq!(bus.subscriber_count(), 1);
+}
+
+#[test]
+fn worker_publishes_run_lifecycle() {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let mut worker = Worker::new();
+    worker.register("ok_fn", |_ctx| Ok(()));
+    worker.set_events(bus);
+
+    worker.run_job(&mut job("ok_fn", 0));
+    worker.run_job(&mut job("missing_fn", 1));
+
+    let names: Vec<&str> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            SchedulerEvent::Job(e) => Some(e.name()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, ["started", "succeeded", "failed", "retried"]);
+}
+
+#[test]
+fn task_logs_carry_job_and_metadata() {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let mut worker = Worker::new();
+    worker.register("greet_fn", |ctx| {
+        ctx.log(format!("hello {}", ctx.var("NAME").unwrap_or_default()));
+        Ok(())
+    });
+    worker.set_events(bus);
+
+    let mut j = job("greet_fn", 0);
+    j.metadata = Some(HashMap::from([("NAME".to_string(), "world".to_string())]));
+    let id = j.id;
+    worker.run_job(&mut j);
+
+    let log = rx
+        .try_iter()
+        .find_map(|e| match e {
+            SchedulerEvent::Log(e) => Some(e),
+            _ => None,
+        })
+        .expect("task log event");
+    assert_eq!(log.source, LogSource::Task);
+    assert_eq!(log.job_id, Some(id));
+    assert_eq!(log.message, "hello world");
+}
+
+#[test]
+fn run_records_are_built_from_finished_attempts() {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let mut worker = Worker::new();
+    worker.set_events(bus);
+    worker.run_job(&mut job("missing_fn", 0));
+
+    let records: Vec<RunRecord> = rx.try_iter().filter_map(|e| RunRecord::from_event(&e)).collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].outcome, RunOutcome::Failed);
+    assert_eq!(records[0].attempt, 1);
+    assert_eq!(records[0].message.as_deref(), Some("No function registered for 'missing_fn'"));
+}
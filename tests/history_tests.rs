@@ -0,0 +1,92 @@
+use scheduler::history::{HistoryStore, RunOutcome, RunRecord};
+use std::fs;
+use uuid::Uuid;
+
+fn get_temp_path() -> String {
+    format!("scheduler_history_{}.jsonl", Uuid::new_v4())
+}
+
+fn record(job_id: Uuid, outcome: RunOutcome) -> RunRecord {
+    RunRecord {
+        job_id,
+        description: "job".to_string(),
+        function: "fn".to_string(),
+        attempt: 1,
+        started_at: 0,
+        duration_ms: 5,
+        outcome,
+        message: None,
+        output: None,
+    }
+}
+
+#[test]
+fn appended_records_survive_reopen() {
+    let path = get_temp_path();
+    let id = Uuid::new_v4();
+    {
+        let mut store = HistoryStore::open(&path);
+        store.append(record(id, RunOutcome::Failed)).unwrap();
+        store.append(record(Uuid::new_v4(), RunOutcome::Success)).unwrap();
+        store.append(record(id, RunOutcome::Success)).unwrap();
+    }
+
+    let store = HistoryStore::open(&path);
+    assert_eq!(store.len(), 3);
+    let runs = store.for_job(id, 10);
+    assert_eq!(runs.len(), 2);
+    // Newest first
+    assert_eq!(runs[0].outcome, RunOutcome::Success);
+    assert_eq!(store.recent(1).len(), 1);
+
+    fs::remove_file(path).expect("Failed to remove file");
+}
+
+#[test]
+fn malformed_lines_are_skipped() {
+    let path = get_temp_path();
+    let line = serde_json::to_string(&record(Uuid::new_v4(), RunOutcome::Success)).unwrap();
+    fs::write(&path, format!("not json\n{}\n", line)).unwrap();
+
+    let store = HistoryStore::open(&path);
+    assert_eq!(store.len(), 1);
+
+    fs::remove_file(path).expect("Failed to remove file");
+}
+
+#[test]
+fn latest_finds_newest_matching_record() {
+    let path = get_temp_path();
+    let id = Uuid::new_v4();
+    let mut store = HistoryStore::open(&path);
+    store.append(record(id, RunOutcome::Failed)).unwrap();
+    let mut other = record(Uuid::new_v4(), RunOutcome::Success);
+    other.function = "other_fn".to_string();
+    store.append(other).unwrap();
+
+    assert_eq!(store.latest(|r| r.job_id == id).unwrap().outcome, RunOutcome::Failed);
+    assert_eq!(store.latest(|r| r.function == "fn").unwrap().job_id, id);
+    assert!(store.latest(|r| r.attempt > 1).is_none());
+
+    fs::remove_file(path).expect("Failed to remove file");
+}
+
+#[test]
+fn between_returns_runs_overlapping_the_window() {
+    let path = get_temp_path();
+    let mut store = HistoryStore::open(&path);
+    for (started_at, duration_ms) in [(100, 1_000), (150, 60_000), (300, 0)] {
+        let mut r = record(Uuid::new_v4(), RunOutcome::Success);
+        r.started_at = started_at;
+        r.duration_ms = duration_ms;
+        store.append(r).unwrap();
+    }
+
+    let starts = |from, to| store.between(from, to).iter().map(|r| r.started_at).collect::<Vec<_>>();
+    assert_eq!(starts(0, 1000), [100, 150, 300]);
+    // The 60 s run that started at 150 is still going at 200
+    assert_eq!(starts(200, 300), [150]);
+    assert!(starts(400, 500).is_empty());
+
+    fs::remove_file(path).expect("Failed to remove file");
+}
@@ -0,0 +1,243 @@
+#![cfg(feature = "http")]
+
+use reqwest::blocking::Client;
+use scheduler::control::ControlContext;
+use scheduler::events::EventBus;
+use scheduler::history::HistoryStore;
+use scheduler::http::HttpServer;
+use scheduler::job::Job;
+use scheduler::queue::QueueManager;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader};
+use std::sync::{Arc, Mutex, mpsc};
+use uuid::Uuid;
+
+const TOKEN: &str = "test-token";
+
+fn start_server() -> (HttpServer, String) {
+    let (worker_tx, _worker_rx) = mpsc::channel();
+    let events = EventBus::new();
+    let mut queue = QueueManager::new();
+    queue.set_events(events.clone());
+    let ctx = Arc::new(ControlContext {
+        queue: Arc::new(Mutex::new(queue)),
+        history: Arc::new(Mutex::new(HistoryStore::open(&format!(
+            "scheduler_http_{}.jsonl",
+            Uuid::new_v4()
+        )))),
+        worker_tx,
+        engine: None,
+        events,
+        functions: vec!["hotfix_fn".to_string()],
+    });
+    let server = HttpServer::start("127.0.0.1:0", TOKEN, ctx).unwrap();
+    let base = format!("http://{}", server.addr());
+    (server, base)
+}
+
+#[test]
+fn rejects_requests_without_token() {
+    let (_server, base) = start_server();
+    let client = Client::new();
+
+    let resp = client.get(format!("{}/jobs", base)).send().unwrap();
+    assert_eq!(resp.status(), 401);
+
+    let resp = client.get(format!("{}/jobs", base)).bearer_auth("wrong").send().unwrap();
+    assert_eq!(resp.status(), 401);
+
+    // The schema document is public
+    let resp = client.get(format!("{}/openapi.json", base)).send().unwrap();
+    assert_eq!(resp.status(), 200);
+    let doc: Value = resp.json().unwrap();
+    assert!(doc["components"]["schemas"]["Job"].is_object());
+}
+
+#[test]
+fn create_get_reschedule_and_cancel_job() {
+    let (_server, base) = start_server();
+    let client = Client::new();
+
+    let resp = client
+        .post(format!("{}/jobs", base))
+        .bearer_auth(TOKEN)
+        .json(&json!({
+            "delay_secs": 600,
+            "priority": 7,
+            "description": "webhook",
+            "function": "hotfix_fn",
+            "metadata": { "KEY": "value" }
+        }))
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let job: Job = resp.json().unwrap();
+    assert_eq!(job.priority, 7);
+
+    let list: Vec<Job> = client
+        .get(format!("{}/jobs", base))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(list.len(), 1);
+
+    let detail: Value = client
+        .get(format!("{}/jobs/{}", base, job.id))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(detail["job"]["description"], "webhook");
+    assert_eq!(detail["runs"], json!([]));
+
+    let target = Job::now() + 3600;
+    let moved: Job = client
+        .post(format!("{}/jobs/{}/reschedule", base, job.id))
+        .bearer_auth(TOKEN)
+        .json(&json!({ "execution_time": target }))
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(moved.execution_time, target);
+
+    let resp = client
+        .delete(format!("{}/jobs/{}", base, job.id))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .get(format!("{}/jobs/{}", base, job.id))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[test]
+fn invalid_input_is_a_bad_request() {
+    let (_server, base) = start_server();
+    let client = Client::new();
+
+    let resp = client
+        .post(format!("{}/jobs", base))
+        .bearer_auth(TOKEN)
+        .json(&json!({ "description": "no time", "function": "fn" }))
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .get(format!("{}/jobs/not-a-uuid", base))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .get(format!("{}/history?limit=5", base))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+}
+
+#[test]
+fn reschedule_rejects_past_and_out_of_range_times() {
+    let (_server, base) = start_server();
+    let client = Client::new();
+
+    let job: Job = client
+        .post(format!("{}/jobs", base))
+        .bearer_auth(TOKEN)
+        .json(&json!({ "delay_secs": 600, "description": "fixed", "function": "hotfix_fn" }))
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+
+    for body in [
+        json!({ "execution_time": Job::now() - 60 }),
+        json!({ "execution_time": i64::MAX }),
+        json!({ "delay_secs": i64::MAX }),
+    ] {
+        let resp = client
+            .post(format!("{}/jobs/{}/reschedule", base, job.id))
+            .bearer_auth(TOKEN)
+            .json(&body)
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 400, "{}", body);
+    }
+
+    let detail: Value = client
+        .get(format!("{}/jobs/{}", base, job.id))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(detail["job"]["execution_time"], job.execution_time);
+}
+
+#[test]
+fn query_values_are_percent_decoded() {
+    let (_server, base) = start_server();
+    let client = Client::new();
+
+    let encoded = Uuid::new_v4().to_string().replace('-', "%2D");
+    let resp = client
+        .get(format!("{}/history?limit=%35&job={}", base, encoded))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.json::<Value>().unwrap(), json!([]));
+}
+
+#[test]
+fn event_stream_delivers_job_events() {
+    let (_server, base) = start_server();
+    let client = Client::new();
+
+    let stream = client
+        .get(format!("{}/events?types=added,cancelled", base))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap();
+    assert_eq!(stream.status(), 200);
+    assert_eq!(stream.headers()["content-type"], "text/event-stream");
+
+    let job: Job = client
+        .post(format!("{}/jobs", base))
+        .bearer_auth(TOKEN)
+        .json(&json!({ "delay_secs": 600, "description": "streamed", "function": "hotfix_fn" }))
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+    client
+        .delete(format!("{}/jobs/{}", base, job.id))
+        .bearer_auth(TOKEN)
+        .send()
+        .unwrap();
+
+    let mut lines = BufReader::new(stream).lines();
+    let mut next_data = |expected_event: &str| -> Value {
+        assert_eq!(lines.next().unwrap().unwrap(), format!("event: {}", expected_event));
+        let data = lines.next().unwrap().unwrap();
+        assert_eq!(lines.next().unwrap().unwrap(), "");
+        serde_json::from_str(data.strip_prefix("data: ").unwrap()).unwrap()
+    };
+
+    let added = next_data("added");
+    assert_eq!(added["job_id"], job.id.to_string());
+    assert_eq!(added["description"], "streamed");
+    let cancelled = next_data("cancelled");
+    assert_eq!(cancelled["type"], "cancelled");
+}
@@ -0,0 +1,157 @@
+use scheduler::events::{EventBus, SchedulerEvent};
+use scheduler::history::{RunOutcome, RunRecord};
+use scheduler::job::Job;
+use scheduler::tasks::{self, OUTPUT_SNIPPET_BYTES, TaskContext};
+use scheduler::worker::Worker;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+/// Serves one canned response and hands back the raw request it received
+fn serve_once(status: &str, body: &str) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(&stream);
+        let mut request = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                content_length = value.trim().parse().unwrap();
+            }
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8_lossy(&body));
+        (&stream).write_all(response.as_bytes()).unwrap();
+        let _ = tx.send(request);
+    });
+    (url, rx)
+}
+
+fn http_job(payload: &[(&str, &str)]) -> Job {
+    let mut job = Job::new(Job::now(), 1, "Webhook", "http", 0).unwrap();
+    job.metadata = Some(
+        payload
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+    );
+    job
+}
+
+/// Runs the job through a worker and returns the history record of the attempt
+fn run(job: &mut Job) -> RunRecord {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let mut worker = Worker::new();
+    tasks::register_builtin(&mut worker);
+    worker.set_events(bus);
+    worker.run_job(job);
+    rx.try_iter()
+        .find_map(|e: SchedulerEvent| RunRecord::from_event(&e))
+        .expect("finished attempt")
+}
+
+#[test]
+fn sends_method_headers_and_body() {
+    let (url, request) = serve_once("201 Created", r#"{"id": 7}"#);
+    let mut job = http_job(&[
+        ("url", &url),
+        ("method", "post"),
+        ("header.X-Token", "abc"),
+        ("auth_token", "s3cret"),
+        ("body", r#"{"ping": true}"#),
+    ]);
+    let record = run(&mut job);
+
+    let request = request.recv().unwrap();
+    assert!(request.starts_with("POST /hook HTTP/1.1"), "{}", request);
+    assert!(request.to_lowercase().contains("x-token: abc"), "{}", request);
+    assert!(request.contains("authorization: Bearer s3cret"), "{}", request);
+    assert!(request.ends_with(r#"{"ping": true}"#), "{}", request);
+
+    assert_eq!(record.outcome, RunOutcome::Success);
+    assert_eq!(record.output.as_deref(), Some("HTTP 201 Created\n{\"id\": 7}"));
+}
+
+#[test]
+fn unexpected_status_fails_and_keeps_the_response() {
+    let (url, _request) = serve_once("503 Service Unavailable", "try later");
+    let mut job = http_job(&[("url", &url), ("expected_status", "200, 204")]);
+    let record = run(&mut job);
+
+    assert_eq!(record.outcome, RunOutcome::Failed);
+    let message = record.message.unwrap();
+    assert!(message.starts_with("Unexpected status 503"), "{}", message);
+    assert_eq!(record.output.as_deref(), Some("HTTP 503 Service Unavailable\ntry later"));
+}
+
+#[test]
+fn status_classes_and_exact_codes_match() {
+    let (url, _request) = serve_once("404 Not Found", "");
+    let mut job = http_job(&[("url", &url), ("expected_status", "2xx,404")]);
+    assert_eq!(run(&mut job).outcome, RunOutcome::Success);
+}
+
+#[test]
+fn connection_errors_and_bad_payloads_fail() {
+    let record = run(&mut http_job(&[]));
+    assert!(record.message.unwrap().contains("'url'"));
+
+    let record = run(&mut http_job(&[("url", "http://127.0.0.1:1"), ("expected_status", "ok")]));
+    assert!(record.message.unwrap().contains("expected_status"));
+
+    let record = run(&mut http_job(&[("url", "http://127.0.0.1:1/"), ("timeout_secs", "2")]));
+    assert_eq!(record.outcome, RunOutcome::Failed);
+    assert!(record.message.unwrap().starts_with("GET http://127.0.0.1:1/ failed"));
+}
+
+#[test]
+fn endless_response_bodies_are_cut_short() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/stream", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let _ = stream.read(&mut request);
+        // No Content-Length: the body lasts until the connection closes
+        let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n");
+        let chunk = [b'x'; 4096];
+        while stream.write_all(&chunk).is_ok() {}
+    });
+
+    let started = std::time::Instant::now();
+    let record = run(&mut http_job(&[("url", &url), ("timeout_secs", "10")]));
+    assert!(started.elapsed().as_secs() < 5, "took {:?}", started.elapsed());
+    assert_eq!(record.outcome, RunOutcome::Success);
+    let output = record.output.unwrap();
+    assert!(output.starts_with("HTTP 200 OK\nxxx"));
+    assert!(output.ends_with('…'));
+}
+
+#[test]
+fn output_snippets_are_capped() {
+    let ctx = TaskContext::new(http_job(&[]), None);
+    ctx.set_output("é".repeat(OUTPUT_SNIPPET_BYTES));
+    let output = ctx.take_output().unwrap();
+    assert!(output.len() <= OUTPUT_SNIPPET_BYTES + '…'.len_utf8());
+    assert!(output.ends_with('…'));
+    assert_eq!(ctx.take_output(), None);
+}
@@ -0,0 +1,83 @@
+use scheduler::events::{EventBus, JobEvent, JobEventKind};
+use scheduler::job::Job;
+use scheduler::metrics::{Metrics, MetricsServer};
+use scheduler::queue::QueueManager;
+use scheduler::worker::Worker;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn job(function: &str) -> Job {
+    Job::new(Job::now() + 60, 1, "measured", function, 1).unwrap()
+}
+
+fn observe_all(metrics: &Metrics, bus_rx: std::sync::mpsc::Receiver<scheduler::events::SchedulerEvent>) {
+    for event in bus_rx.try_iter() {
+        metrics.observe(&event);
+    }
+}
+
+#[test]
+fn counts_job_lifecycle_per_function() {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let queue = Arc::new(Mutex::new(QueueManager::new()));
+    queue.lock().unwrap().set_events(bus.clone());
+    queue.lock().unwrap().push(job("ok_fn"));
+
+    let mut worker = Worker::new();
+    worker.register("ok_fn", |_ctx| Ok(()));
+    worker.set_events(bus.clone());
+    worker.run_job(&mut job("ok_fn"));
+    worker.run_job(&mut job("missing_fn"));
+    bus.publish(JobEvent::new(&job("ok_fn"), JobEventKind::Dispatched { lag_secs: 3 }));
+
+    let mut metrics = Metrics::new();
+    metrics.set_queue(Arc::clone(&queue));
+    observe_all(&metrics, rx);
+    let text = metrics.render();
+
+    assert!(text.contains("scheduler_jobs_enqueued_total{function=\"ok_fn\"} 1"));
+    assert!(text.contains("scheduler_jobs_succeeded_total{function=\"ok_fn\"} 1"));
+    assert!(text.contains("scheduler_jobs_failed_total{function=\"missing_fn\"} 1"));
+    assert!(text.contains("scheduler_jobs_retried_total{function=\"missing_fn\"} 1"));
+    assert!(text.contains("scheduler_dispatch_lag_seconds_sum{function=\"ok_fn\"} 3"));
+    assert!(text.contains("scheduler_job_duration_seconds_count{function=\"ok_fn\"} 1"));
+    assert!(text.contains("scheduler_queue_depth 1"));
+}
+
+#[test]
+fn serves_metrics_over_http() {
+    let metrics = Arc::new(Metrics::new());
+    metrics.observe(&JobEvent::new(&job("ok_fn"), JobEventKind::Added { execution_time: 0 }).into());
+    let server = MetricsServer::start("127.0.0.1:0", Arc::clone(&metrics)).unwrap();
+
+    let get = |path: &str| {
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+
+    let response = get("/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("text/plain; version=0.0.4"));
+    assert!(response.contains("scheduler_jobs_enqueued_total{function=\"ok_fn\"} 1"));
+    assert!(get("/other").starts_with("HTTP/1.1 404"));
+}
+
+#[test]
+fn idle_connection_does_not_block_scrapes() {
+    let server = MetricsServer::start("127.0.0.1:0", Arc::new(Metrics::new())).unwrap();
+    // Connected but never sends a request
+    let _idle = TcpStream::connect(server.addr()).unwrap();
+
+    let mut stream = TcpStream::connect(server.addr()).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    write!(stream, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+}
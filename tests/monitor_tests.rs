@@ -0,0 +1,111 @@
+use scheduler::engine::TimePriorityEngine;
+use scheduler::job::Job;
+use scheduler::monitor::{LoadThreshold, ResourceMonitor, ResourceSample, SAMPLE_HISTORY};
+use scheduler::queue::QueueManager;
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+fn sample(system_cpu_percent: f32, load_average: f64) -> ResourceSample {
+    ResourceSample {
+        at: Job::now(),
+        process_cpu_percent: 1.0,
+        process_rss_bytes: 10 * 1024 * 1024,
+        system_cpu_percent,
+        system_memory_used_bytes: 0,
+        load_average,
+    }
+}
+
+fn cpu_limit(percent: f32) -> LoadThreshold {
+    LoadThreshold {
+        max_system_cpu_percent: Some(percent),
+        max_load_average: None,
+    }
+}
+
+fn heavy_job(description: &str) -> Job {
+    let mut job = Job::new(Job::now(), 1, description, "fn", 0).unwrap();
+    job.tags = vec!["heavy".to_string()];
+    job
+}
+
+#[test]
+fn threshold_checks_cpu_and_load() {
+    let threshold = LoadThreshold {
+        max_system_cpu_percent: Some(80.0),
+        max_load_average: Some(4.0),
+    };
+    assert!(!threshold.exceeded_by(&sample(50.0, 1.0)));
+    assert!(threshold.exceeded_by(&sample(95.0, 1.0)));
+    assert!(threshold.exceeded_by(&sample(10.0, 6.5)));
+    assert!(!LoadThreshold::default().exceeded_by(&sample(100.0, 99.0)));
+}
+
+#[test]
+fn threshold_reads_env() {
+    let mut threshold = LoadThreshold::default();
+    threshold
+        .apply_env(|key| match key {
+            "SCHEDULER_HEAVY_MAX_CPU" => Some("75".to_string()),
+            "SCHEDULER_HEAVY_MAX_LOAD" => Some("2.5".to_string()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(threshold.max_system_cpu_percent, Some(75.0));
+    assert_eq!(threshold.max_load_average, Some(2.5));
+    assert!(threshold.apply_env(|_| Some("lots".to_string())).is_err());
+}
+
+#[test]
+fn keeps_a_bounded_history() {
+    let monitor = ResourceMonitor::new(LoadThreshold::default());
+    assert!(monitor.latest().is_none());
+    for i in 0..SAMPLE_HISTORY + 5 {
+        monitor.record(sample(i as f32, 0.0));
+    }
+    let samples = monitor.samples();
+    assert_eq!(samples.len(), SAMPLE_HISTORY);
+    assert_eq!(samples[0].system_cpu_percent, 5.0);
+    assert_eq!(monitor.latest().unwrap().system_cpu_percent, (SAMPLE_HISTORY + 4) as f32);
+}
+
+#[test]
+fn only_heavy_jobs_are_held_when_overloaded() {
+    let monitor = ResourceMonitor::new(cpu_limit(80.0));
+    let light = Job::new(Job::now(), 1, "light", "fn", 0).unwrap();
+    let heavy = heavy_job("heavy");
+
+    monitor.record(sample(20.0, 0.0));
+    assert!(!monitor.should_hold(&heavy));
+
+    monitor.record(sample(95.0, 0.0));
+    assert!(monitor.should_hold(&heavy));
+    assert!(!monitor.should_hold(&light));
+}
+
+#[test]
+fn engine_holds_heavy_jobs_until_load_drops() {
+    let queue = Arc::new(Mutex::new(QueueManager::new()));
+    let (tx, rx) = mpsc::channel();
+    let monitor = Arc::new(ResourceMonitor::new(cpu_limit(80.0)));
+    monitor.record(sample(95.0, 0.0));
+
+    let mut engine = TimePriorityEngine::new(Arc::clone(&queue), tx);
+    engine.set_monitor(Arc::clone(&monitor));
+    {
+        let mut q = queue.lock().unwrap();
+        q.push(heavy_job("heavy"));
+        q.push(Job::new(Job::now(), 1, "light", "fn", 0).unwrap());
+    }
+    engine.start();
+
+    let first = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(first.description, "light");
+    assert!(rx.recv_timeout(Duration::from_millis(700)).is_err());
+    assert_eq!(queue.lock().unwrap().len(), 1);
+
+    monitor.record(sample(30.0, 0.0));
+    let released = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(released.description, "heavy");
+    engine.stop();
+}
@@ -0,0 +1,91 @@
+use scheduler::job::Job;
+use scheduler::telemetry::otlp::{OtlpFileLayer, OtlpTarget};
+use scheduler::worker::Worker;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Writer that keeps everything in memory so the test can inspect it
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn exported_spans(buffer: &SharedBuffer) -> Vec<Value> {
+    let data = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    data.lines()
+        .map(|line| {
+            let request: Value = serde_json::from_str(line).unwrap();
+            request["resourceSpans"][0]["scopeSpans"][0]["spans"][0].clone()
+        })
+        .collect()
+}
+
+fn attribute<'a>(span: &'a Value, key: &str) -> &'a Value {
+    span["attributes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["key"] == key)
+        .map(|a| &a["value"])
+        .unwrap_or(&Value::Null)
+}
+
+fn run_traced(function: &str) -> Vec<Value> {
+    let buffer = SharedBuffer::default();
+    let subscriber =
+        tracing_subscriber::registry().with(OtlpFileLayer::with_writer(Box::new(buffer.clone())));
+
+    let mut worker = Worker::new();
+    worker.register("traced_fn", |ctx| {
+        ctx.log("inside the task");
+        Ok(())
+    });
+    let mut job = Job::new(Job::now(), 7, "Traced", function, 2).unwrap();
+    tracing::subscriber::with_default(subscriber, || worker.run_job(&mut job));
+    exported_spans(&buffer)
+}
+
+#[test]
+fn job_run_span_carries_job_attributes_and_task_logs() {
+    let spans = run_traced("traced_fn");
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+
+    assert_eq!(span["name"], "job_run");
+    assert_eq!(span["traceId"].as_str().unwrap().len(), 32);
+    assert_eq!(span["spanId"].as_str().unwrap().len(), 16);
+    assert_eq!(attribute(span, "job.function")["stringValue"], "traced_fn");
+    assert_eq!(attribute(span, "job.priority")["intValue"], "7");
+    assert_eq!(attribute(span, "job.attempt")["intValue"], "1");
+    assert_eq!(span["status"]["code"], 1);
+
+    let events = span["events"].as_array().unwrap();
+    assert!(events.iter().any(|e| e["name"] == "inside the task"));
+}
+
+#[test]
+fn failed_run_is_exported_with_error_status() {
+    let spans = run_traced("missing_fn");
+    assert_eq!(spans[0]["status"]["code"], 2);
+    assert!(spans[0]["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["name"] == "No function registered for 'missing_fn'"));
+}
+
+#[test]
+fn target_parses_stdout_or_path() {
+    assert_eq!(OtlpTarget::parse("stdout"), OtlpTarget::Stdout);
+    assert_eq!(OtlpTarget::parse("traces/spans.jsonl"), OtlpTarget::File("traces/spans.jsonl".into()));
+}
@@ -0,0 +1,120 @@
+use scheduler::tasks::{self, ParamKind, ParamSpec, params};
+use scheduler::worker::Worker;
+use std::collections::HashMap;
+
+fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn builtin_tasks_declare_their_parameters() {
+    let mut worker = Worker::new();
+    tasks::register_builtin(&mut worker);
+
+    let http = worker.params("http");
+    let url = http.iter().find(|p| p.name == "url").unwrap();
+    assert!(url.required);
+    let token = http.iter().find(|p| p.name == "auth_token").unwrap();
+    assert!(token.secret);
+    let method = http.iter().find(|p| p.name == "method").unwrap();
+    assert_eq!(method.default.as_deref(), Some("GET"));
+    assert!(matches!(&method.kind, ParamKind::Choice(options) if options.contains(&"POST".to_string())));
+
+    let email: Vec<&str> = worker.params("email_fn").iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(email[..3], ["SMTP_RECIPIENT", "EMAIL_CC", "EMAIL_BCC"]);
+    assert!(worker.params("hotfix_fn").is_empty());
+    assert!(worker.params("unknown").is_empty());
+
+    let schemas = worker.param_schemas();
+    assert_eq!(schemas.len(), worker.functions().len());
+    assert_eq!(schemas["shell"][0].name, "command");
+}
+
+#[test]
+fn plain_functions_and_restrictions_keep_schemas_consistent() {
+    let mut worker = Worker::new();
+    tasks::register_builtin(&mut worker);
+    worker.register("custom", |_| Ok(()));
+    assert!(worker.param_schemas()["custom"].is_empty());
+
+    worker.restrict_to(&["shell".to_string(), "custom".to_string()]).unwrap();
+    let schemas = worker.param_schemas();
+    assert_eq!(schemas.len(), 2);
+    assert!(worker.params("http").is_empty());
+}
+
+#[test]
+fn values_are_checked_by_kind() {
+    let retries = ParamSpec::integer("n", "Retries");
+    assert!(retries.check("12").is_ok());
+    assert_eq!(retries.check("twelve").unwrap_err(), "Retries must be a whole number");
+
+    let flag = ParamSpec::boolean("f", "Verbose");
+    assert!(flag.check("TRUE").is_ok());
+    assert_eq!(flag.check("maybe").unwrap_err(), "Verbose must be one of true, false");
+
+    let kind = ParamSpec::choice("k", "Kind", &["sqlite", "postgres"]);
+    assert!(kind.check("postgres").is_ok());
+    assert!(kind.check("oracle").is_err());
+
+    let to = ParamSpec::emails("to", "To");
+    assert!(to.check("a@example.com, Dev <dev@example.com>").is_ok());
+    assert_eq!(to.check("a@example.com, nope").unwrap_err(), "To: 'nope' is not an email address");
+
+    let url = ParamSpec::text("url", "URL").required();
+    assert_eq!(url.check("  ").unwrap_err(), "URL is required");
+    assert!(ParamSpec::integer("t", "Timeout").check("").is_ok(), "optional values may be empty");
+}
+
+#[test]
+fn validate_reports_everything_and_builds_the_payload() {
+    let specs = vec![
+        ParamSpec::text("url", "URL").required(),
+        ParamSpec::integer("timeout_secs", "Timeout"),
+        ParamSpec::multiline("body", "Body"),
+        ParamSpec::text("cwd", "Working directory"),
+    ];
+    let errors = params::validate(&specs, &values(&[("timeout_secs", "soon")])).unwrap_err();
+    assert_eq!(errors, ["URL is required", "Timeout must be a whole number"]);
+
+    let payload = params::validate(
+        &specs,
+        &values(&[
+            ("url", " https://example.com "),
+            ("timeout_secs", "5"),
+            ("body", "  line one\nline two\n"),
+            ("cwd", "   "),
+            ("header.X-Trace", "1"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(payload["url"], "https://example.com");
+    assert_eq!(payload["body"], "  line one\nline two\n", "multiline values are kept as typed");
+    assert!(!payload.contains_key("cwd"), "empty optional values are dropped");
+    assert_eq!(payload["header.X-Trace"], "1", "undeclared keys pass through");
+}
+
+#[test]
+fn secret_keys_are_declared_or_named_like_credentials() {
+    let specs = vec![
+        ParamSpec::text("auth_token", "Bearer token").secret(),
+        ParamSpec::text("api_token_label", "Label"),
+    ];
+    assert!(params::is_secret(&specs, "auth_token"));
+    // A declared, non-secret parameter is shown even if its name looks like a credential
+    assert!(!params::is_secret(&specs, "api_token_label"));
+    assert!(params::is_secret(&specs, "SMTP_PASSWORD"));
+    assert!(params::is_secret(&[], "resend_api_key"));
+    assert!(!params::is_secret(&[], "SMTP_RECIPIENT"));
+    assert!(params::is_secret(&[], "header.Authorization"));
+    for key in [
+        "DB_PASS", "client_credentials", "PRIVATE_KEY_PATH", "header.X-Api-Key", "KEY", "apikey", "APIKEY",
+        "apiKey", "privkey", "MYSQL_PASSWD", "dbPassword",
+    ] {
+        assert!(params::is_secret(&[], key), "{}", key);
+    }
+    // Hints only count as whole words
+    for key in ["author", "AUTHOR_NAME", "passenger_count", "compass", "MONKEY", "KEYBOARD_LAYOUT"] {
+        assert!(!params::is_secret(&[], key), "{}", key);
+    }
+}
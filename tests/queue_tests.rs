@@ -97,3 +97,120 @@ fn accepts_job_with_future_execution_time() {
     let result = Job::new(now() + 100, 5, "future job", "fn", 3);
     assert!(result.is_ok());
 }
+
+#[test]
+fn paused_job_is_not_ready_until_resumed() {
+    let mut q = QueueManager::new();
+    let base = now();
+    let j = job(base + 10, 1, "paused");
+    let id = j.id;
+    q.push(j);
+    assert!(q.pause(id));
+    assert!(!q.pause(id));
+    assert!(q.pop_ready(base + 20).is_empty());
+    assert_eq!(q.len(), 1);
+
+    assert!(q.resume(id));
+    let ready = q.pop_ready(base + 20);
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].description, "paused");
+}
+
+#[test]
+fn remove_paused_job() {
+    let mut q = QueueManager::new();
+    let j = job(now() + 10, 1, "paused");
+    let id = j.id;
+    q.push(j);
+    q.pause(id);
+    assert!(q.remove(id).is_some());
+    assert!(q.is_empty());
+}
+
+#[test]
+fn reschedule_rekeys_job() {
+    let mut q = QueueManager::new();
+    let base = now();
+    let j = job(base + 100, 1, "moved");
+    let id = j.id;
+    q.push(j);
+    q.push(job(base + 50, 1, "other"));
+
+    assert!(q.reschedule(id, base + 10));
+    assert_eq!(q.peek().unwrap().description, "moved");
+    assert!(!q.reschedule(Uuid::new_v4(), base));
+}
+
+#[test]
+fn pop_ready_where_keeps_held_jobs_queued() {
+    let mut q = QueueManager::new();
+    let t = now();
+    let mut heavy = job(t, 5, "heavy");
+    heavy.tags = vec!["heavy".to_string()];
+    q.push(heavy);
+    q.push(job(t, 1, "light"));
+
+    let ready = q.pop_ready_where(t, |j| j.is_heavy());
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].description, "light");
+    assert_eq!(q.len(), 1);
+
+    let ready = q.pop_ready(t);
+    assert_eq!(ready[0].description, "heavy");
+}
+
+#[test]
+fn update_replaces_job_and_rekeys() {
+    let mut q = QueueManager::new();
+    let base = now();
+    let j = job(base + 100, 1, "edited");
+    let id = j.id;
+    q.push(j.clone());
+    q.push(job(base + 50, 1, "other"));
+
+    let mut edited = j;
+    edited.execution_time = base + 10;
+    edited.priority = 9;
+    assert!(q.update(edited));
+    assert_eq!(q.peek().unwrap().id, id);
+    assert_eq!(q.get(id).unwrap().priority, 9);
+    assert_eq!(q.len(), 2);
+    assert!(!q.update(job(base, 1, "unknown")));
+}
+
+#[test]
+fn update_persists_edit() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut q = QueueManager::new();
+    q.set_persistence(tx);
+    let j = job(now() + 100, 1, "before");
+    q.push(j.clone());
+    while rx.try_recv().is_ok() {}
+
+    let mut edited = j;
+    edited.description = "after".to_string();
+    edited.max_retries = 7;
+    assert!(q.update(edited));
+    let snapshot = rx.try_recv().expect("update should persist the queue");
+    assert_eq!(snapshot.len(), 1);
+    assert_eq!(snapshot[0].description, "after");
+    assert_eq!(snapshot[0].max_retries, 7);
+}
+
+#[test]
+fn update_paused_job_uses_new_key_on_resume() {
+    let mut q = QueueManager::new();
+    let base = now();
+    let j = job(base + 100, 1, "paused");
+    let id = j.id;
+    q.push(j.clone());
+    q.push(job(base + 50, 1, "other"));
+    assert!(q.pause(id));
+
+    let mut edited = q.get(id).unwrap().clone();
+    edited.execution_time = base + 10;
+    assert!(q.update(edited));
+    assert_ne!(q.peek().unwrap().id, id);
+    assert!(q.resume(id));
+    assert_eq!(q.peek().unwrap().id, id);
+}
@@ -0,0 +1,126 @@
+#![cfg(unix)]
+
+use scheduler::events::{EventBus, JobEventKind, LogLevel, SchedulerEvent};
+use scheduler::job::{Job, Status};
+use scheduler::tasks::{self, TaskContext, shell::ShellTask, Task};
+use scheduler::worker::Worker;
+use std::collections::HashMap;
+
+fn shell_job(payload: &[(&str, &str)]) -> Job {
+    let mut job = Job::new(Job::now(), 1, "Shell", "shell", 0).unwrap();
+    job.metadata = Some(
+        payload
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+    );
+    job
+}
+
+/// Runs the task directly and returns its result with the logged lines
+fn run(payload: &[(&str, &str)]) -> (Result<(), String>, Vec<(LogLevel, String)>) {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let ctx = TaskContext::new(shell_job(payload), Some(bus));
+    let result = ShellTask::run(&ctx);
+    drop(ctx);
+    let lines = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            SchedulerEvent::Log(e) => Some((e.level, e.message)),
+            _ => None,
+        })
+        .collect();
+    (result, lines)
+}
+
+#[test]
+fn streams_output_lines_into_the_job_log() {
+    let (result, lines) = run(&[
+        ("command", "sh"),
+        ("args", r#"["-c", "echo one; echo two; echo oops >&2"]"#),
+    ]);
+    assert_eq!(result, Ok(()));
+    assert!(lines.contains(&(LogLevel::Info, "one".to_string())), "{:?}", lines);
+    assert!(lines.contains(&(LogLevel::Info, "two".to_string())), "{:?}", lines);
+    assert!(lines.contains(&(LogLevel::Warn, "oops".to_string())), "{:?}", lines);
+}
+
+#[test]
+fn passes_args_env_and_working_dir() {
+    let (result, lines) = run(&[
+        ("command", "sh"),
+        ("args", r#"["-c", "echo $GREETING; pwd"]"#),
+        ("env.GREETING", "hello"),
+        ("cwd", "/"),
+    ]);
+    assert_eq!(result, Ok(()));
+    assert!(lines.contains(&(LogLevel::Info, "hello".to_string())), "{:?}", lines);
+    assert!(lines.contains(&(LogLevel::Info, "/".to_string())), "{:?}", lines);
+
+    let (result, lines) = run(&[("command", "echo"), ("args", "a b  c")]);
+    assert_eq!(result, Ok(()));
+    assert!(lines.contains(&(LogLevel::Info, "a b c".to_string())), "{:?}", lines);
+}
+
+#[test]
+fn non_zero_exit_fails_with_last_stderr_line() {
+    let (result, _) = run(&[("command", "sh"), ("args", r#"["-c", "echo broken >&2; exit 3"]"#)]);
+    assert_eq!(result, Err("'sh' exited with status 3: broken".to_string()));
+}
+
+#[test]
+fn kills_commands_that_time_out() {
+    let started = std::time::Instant::now();
+    let (result, _) = run(&[("command", "sleep"), ("args", "5"), ("timeout_secs", "1")]);
+    assert_eq!(result, Err("'sleep' timed out after 1s".to_string()));
+    assert!(started.elapsed().as_secs() < 4);
+}
+
+#[test]
+fn timeout_holds_when_child_processes_keep_the_output_open() {
+    let started = std::time::Instant::now();
+    let (result, lines) = run(&[
+        ("command", "sh"),
+        ("args", r#"["-c", "echo started; sleep 30 & sleep 30; echo never"]"#),
+        ("timeout_secs", "1"),
+    ]);
+    assert_eq!(result, Err("'sh' timed out after 1s".to_string()));
+    assert!(started.elapsed().as_secs() < 4);
+    assert!(lines.contains(&(LogLevel::Info, "started".to_string())), "{:?}", lines);
+}
+
+#[test]
+fn rejects_bad_payloads() {
+    let (result, _) = run(&[]);
+    assert!(result.unwrap_err().contains("'command'"));
+    let (result, _) = run(&[("command", "true"), ("args", "[1, 2]")]);
+    assert!(result.unwrap_err().contains("JSON array"));
+    let (result, _) = run(&[("command", "true"), ("timeout_secs", "soon")]);
+    assert!(result.unwrap_err().contains("timeout_secs"));
+    let (result, _) = run(&[("command", "scheduler-no-such-command")]);
+    assert!(result.unwrap_err().starts_with("Cannot start"));
+}
+
+#[test]
+fn failed_command_goes_through_retry_logic() {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let mut worker = Worker::new();
+    tasks::register_builtin(&mut worker);
+    worker.set_events(bus);
+
+    let mut job = shell_job(&[("command", "false")]);
+    job.max_retries = 1;
+    worker.run_job(&mut job);
+    assert_eq!(job.status, Status::Pending);
+    assert_eq!(job.retry_count, 1);
+    worker.run_job(&mut job);
+    assert_eq!(job.status, Status::Failed);
+
+    let failures = rx
+        .try_iter()
+        .filter(|e| matches!(e, SchedulerEvent::Job(e) if matches!(e.kind, JobEventKind::Failed { .. })))
+        .count();
+    assert_eq!(failures, 2);
+}
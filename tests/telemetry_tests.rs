@@ -0,0 +1,108 @@
+use scheduler::events::{EventBus, LogLevel, LogSource, SchedulerEvent};
+use scheduler::telemetry::otlp::OtlpTarget;
+use scheduler::telemetry::rotation::SizeRotatingWriter;
+use scheduler::telemetry::tui_layer::TuiLogLayer;
+use scheduler::telemetry::{LogFormat, LogRotation, TelemetryConfig};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+
+fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |key| vars.get(key).cloned()
+}
+
+#[test]
+fn env_overrides_defaults() {
+    let mut config = TelemetryConfig::default();
+    config
+        .apply_env(env(&[
+            ("SCHEDULER_LOG", "warn,scheduler::engine=debug"),
+            ("SCHEDULER_LOG_FORMAT", "json"),
+            ("SCHEDULER_LOG_DIR", "/tmp/scheduler-logs"),
+            ("SCHEDULER_LOG_ROTATION", "size"),
+            ("SCHEDULER_LOG_MAX_SIZE", "2048"),
+            ("SCHEDULER_LOG_MAX_FILES", "3"),
+            ("SCHEDULER_LOG_STDOUT", "false"),
+            ("SCHEDULER_TUI_LOG_LEVEL", "warn"),
+            ("SCHEDULER_OTLP_EXPORT", "stdout"),
+        ]))
+        .unwrap();
+
+    assert_eq!(config.level, "warn,scheduler::engine=debug");
+    assert_eq!(config.format, LogFormat::Json);
+    assert_eq!(config.dir, PathBuf::from("/tmp/scheduler-logs"));
+    assert_eq!(config.rotation, LogRotation::Size);
+    assert_eq!(config.max_size_bytes, 2048);
+    assert_eq!(config.max_files, 3);
+    assert!(!config.stdout);
+    assert_eq!(config.tui_level, LevelFilter::WARN);
+    assert_eq!(config.otlp_export, Some(OtlpTarget::Stdout));
+}
+
+#[test]
+fn rust_log_is_the_fallback_level() {
+    let mut config = TelemetryConfig::default();
+    config.apply_env(env(&[("RUST_LOG", "debug")])).unwrap();
+    assert_eq!(config.level, "debug");
+}
+
+#[test]
+fn invalid_env_values_are_rejected() {
+    let mut config = TelemetryConfig::default();
+    assert!(config.apply_env(env(&[("SCHEDULER_LOG_FORMAT", "xml")])).is_err());
+    assert!(config.apply_env(env(&[("SCHEDULER_LOG_ROTATION", "hourly")])).is_err());
+    assert!(config.apply_env(env(&[("SCHEDULER_LOG_MAX_SIZE", "big")])).is_err());
+    assert!(config.apply_env(env(&[("SCHEDULER_LOG_STDOUT", "maybe")])).is_err());
+}
+
+#[test]
+fn size_rotation_keeps_max_files() {
+    let dir = std::env::temp_dir().join(format!("scheduler_logs_{}", uuid::Uuid::new_v4()));
+    let path = dir.join("scheduler.log");
+    let mut writer = SizeRotatingWriter::new(&path, 10, 2).unwrap();
+    for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+        writer.write_all(line.as_bytes()).unwrap();
+    }
+    writer.flush().unwrap();
+
+    let read = |p: PathBuf| fs::read_to_string(p).unwrap();
+    assert_eq!(read(path.clone()), "dddddddd\n");
+    assert_eq!(read(dir.join("scheduler.log.1")), "cccccccc\n");
+    assert_eq!(read(dir.join("scheduler.log.2")), "bbbbbbbb\n");
+    assert!(!dir.join("scheduler.log.3").exists());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn tui_layer_forwards_logs_to_the_bus() {
+    let bus = EventBus::new();
+    let rx = bus.subscribe();
+    let subscriber = tracing_subscriber::registry().with(TuiLogLayer::new(bus));
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(path = "queue.json", "Snapshot failed");
+        tracing::info!(target: "scheduler::engine", "Tick");
+        // Task output already reaches the TUI as bus events
+        tracing::info!(target: "scheduler::tasks", "Sending email");
+    });
+
+    let logs: Vec<_> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            SchedulerEvent::Log(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].level, LogLevel::Warn);
+    assert_eq!(logs[0].source, LogSource::System);
+    assert_eq!(logs[0].message, "Snapshot failed path=queue.json");
+    assert_eq!(logs[1].source, LogSource::Engine);
+}
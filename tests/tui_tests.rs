@@ -0,0 +1,312 @@
+use scheduler::events::{JobEvent, JobEventKind, LogEvent, LogLevel, LogSource, SchedulerEvent};
+use scheduler::job::{Job, Status};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use scheduler::tui::{
+    Action, JobView, Key, KeyContext, Keymap, LiveStats, LogBuffer, LogFilter, Preset, STATS_WINDOW_SECS, SortOrder,
+    TIMELINE_ZOOM, Theme, ThemeName, TimelineView,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn job(offset: i64, priority: u8, desc: &str, function: &str) -> Job {
+    Job::new(Job::now() + offset, priority, desc, function, 0).unwrap()
+}
+
+fn descriptions(jobs: &[Job]) -> Vec<&str> {
+    jobs.iter().map(|j| j.description.as_str()).collect()
+}
+
+fn queue() -> Vec<Job> {
+    let mut report = job(10, 1, "Nightly report", "email_fn");
+    report.metadata = Some(HashMap::from([
+        ("SMTP_RECIPIENT".to_string(), "ops@example.com".to_string()),
+        ("SMTP_PASSWORD".to_string(), "hunter2".to_string()),
+    ]));
+    let mut backup = job(20, 9, "Backup database", "backup_fn");
+    backup.tags = vec!["Heavy".to_string()];
+    let mut paused = job(30, 5, "Ping API", "http");
+    paused.status = Status::Paused;
+    vec![report, backup, paused]
+}
+
+#[test]
+fn search_matches_all_words_in_description_function_and_payload() {
+    let mut view = JobView::default();
+    assert!(!view.is_filtered());
+    assert_eq!(view.apply(queue()).len(), 3);
+
+    view.query = "BACKUP".to_string();
+    assert_eq!(descriptions(&view.apply(queue())), ["Backup database"]);
+    view.query = "email ops@example".to_string();
+    assert_eq!(descriptions(&view.apply(queue())), ["Nightly report"]);
+    view.query = "email nothing".to_string();
+    assert!(view.apply(queue()).is_empty());
+    // Keys of secret values are searchable, the values are not
+    view.query = "smtp_password".to_string();
+    assert_eq!(view.apply(queue()).len(), 1);
+    view.query = "hunter2".to_string();
+    assert!(view.apply(queue()).is_empty());
+}
+
+#[test]
+fn filters_combine_and_show_in_the_title() {
+    let view = JobView {
+        status: Some(Status::Pending),
+        tag: Some("heavy".to_string()),
+        ..JobView::default()
+    };
+    assert!(view.is_filtered());
+    assert_eq!(descriptions(&view.apply(queue())), ["Backup database"]);
+    assert_eq!(view.describe(), " \u{2502} status Pending \u{2502} tag heavy");
+
+    let view = JobView {
+        function: Some("http".to_string()),
+        status: Some(Status::Pending),
+        ..JobView::default()
+    };
+    assert!(view.apply(queue()).is_empty());
+}
+
+#[test]
+fn sort_orders_cycle_and_reorder() {
+    let mut view = JobView::default();
+    assert_eq!(descriptions(&view.apply(queue())), ["Nightly report", "Backup database", "Ping API"]);
+
+    view.sort = view.sort.next();
+    assert_eq!(view.sort, SortOrder::Priority);
+    assert_eq!(descriptions(&view.apply(queue())), ["Backup database", "Ping API", "Nightly report"]);
+    assert_eq!(view.describe(), " \u{2502} by priority");
+
+    view.sort = view.sort.next();
+    assert_eq!(descriptions(&view.apply(queue())), ["Backup database", "Nightly report", "Ping API"]);
+    assert_eq!(view.sort.next(), SortOrder::Time);
+}
+
+fn log(source: LogSource, job: Option<Uuid>, message: &str) -> SchedulerEvent {
+    SchedulerEvent::Log(LogEvent::new(LogLevel::Info, source, job, message))
+}
+
+fn messages(buffer: &LogBuffer) -> Vec<String> {
+    buffer.visible().map(|e| e.event.to_string()).collect()
+}
+
+#[test]
+fn log_buffer_drops_the_oldest_lines() {
+    let mut buffer = LogBuffer::new(3);
+    for i in 0..5 {
+        buffer.push(log(LogSource::Task, None, &format!("line {}", i)));
+    }
+    assert_eq!(buffer.len(), 3);
+    assert_eq!(messages(&buffer), ["[Task] line 2", "[Task] line 3", "[Task] line 4"]);
+}
+
+#[test]
+fn log_filter_by_source_and_job() {
+    let id = Uuid::new_v4();
+    let mut buffer = LogBuffer::new(10);
+    buffer.push(log(LogSource::Engine, None, "engine"));
+    buffer.push(log(LogSource::Task, Some(id), "mine"));
+    buffer.push(log(LogSource::Task, Some(Uuid::new_v4()), "other"));
+
+    buffer.set_filter(LogFilter { source: Some(LogSource::Task), job: None });
+    assert_eq!(messages(&buffer), ["[Task] mine", "[Task] other"]);
+    buffer.set_filter(LogFilter { source: None, job: Some(id) });
+    assert_eq!(messages(&buffer), ["[Task] mine"]);
+    assert_eq!(buffer.filter().describe(), format!(" \u{2502} job {}", &id.to_string()[..8]));
+}
+
+#[test]
+fn scrolled_log_stays_put_while_lines_arrive() {
+    let mut buffer = LogBuffer::new(100);
+    for i in 0..10 {
+        buffer.push(log(LogSource::Worker, None, &format!("line {}", i)));
+    }
+    assert!(buffer.is_following());
+    buffer.scroll_up(4);
+    assert_eq!(buffer.scroll(), 4);
+
+    buffer.push(log(LogSource::Worker, None, "new"));
+    assert_eq!(buffer.scroll(), 5, "the same lines stay in view");
+    buffer.scroll_down(2);
+    assert_eq!(buffer.scroll(), 3);
+    buffer.scroll_up(100);
+    assert_eq!(buffer.scroll(), 10, "stops at the oldest line");
+    buffer.follow();
+    assert!(buffer.is_following());
+    buffer.push(log(LogSource::Worker, None, "newer"));
+    assert!(buffer.is_following());
+}
+
+#[test]
+fn log_export_writes_the_filtered_lines() {
+    let mut buffer = LogBuffer::new(10);
+    buffer.push(log(LogSource::Engine, None, "engine"));
+    buffer.push(log(LogSource::Task, None, "task"));
+    buffer.set_filter(LogFilter { source: Some(LogSource::Task), job: None });
+
+    let path = std::env::temp_dir().join(format!("scheduler_log_{}.txt", Uuid::new_v4()));
+    assert_eq!(buffer.export(&path).unwrap(), 1);
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.ends_with(" [Task] task\n"), "{}", text);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn timeline_places_times_on_columns() {
+    let now = 1_800_000_000;
+    let view = TimelineView::default();
+    assert_eq!(view.span(), 3600);
+    // A quarter of the window is in the past
+    assert_eq!(view.start(now), now - 900);
+    assert_eq!(view.column(now, 100, now), Some(25));
+    assert_eq!(view.column(now, 100, now - 900), Some(0));
+    assert_eq!(view.column(now, 100, now + 2699), Some(99));
+    assert_eq!(view.column(now, 100, now + 2700), None);
+    assert_eq!(view.column(now, 100, now - 901), None);
+    assert_eq!(view.tick_step(100), 600);
+    assert_eq!(view.tick_step(300), 300);
+}
+
+#[test]
+fn timeline_zooms_around_the_middle_and_pans() {
+    let now = 1_800_000_000;
+    let mut view = TimelineView::default();
+    let middle = |v: &TimelineView| v.start(now) + v.span() / 2;
+    let before = middle(&view);
+    view.zoom_out();
+    assert_eq!(view.span(), 6 * 3600);
+    assert_eq!(middle(&view), before);
+    for _ in 0..10 {
+        view.zoom_out();
+    }
+    assert_eq!(view.span(), TIMELINE_ZOOM[TIMELINE_ZOOM.len() - 1].0);
+    for _ in 0..10 {
+        view.zoom_in();
+    }
+    assert_eq!(view.span(), 300);
+    assert_eq!(middle(&view), before);
+
+    let start = view.start(now);
+    view.pan(true);
+    assert_eq!(view.start(now), start + 75);
+    view.pan(false);
+    view.pan(false);
+    assert_eq!(view.start(now), start - 75);
+}
+
+fn job_event(job: &Job, at: i64, kind: JobEventKind) -> SchedulerEvent {
+    let mut event = JobEvent::new(job, kind);
+    event.at = at;
+    SchedulerEvent::Job(event)
+}
+
+#[test]
+fn live_stats_track_running_jobs_and_dispatch_lag() {
+    let now = Job::now();
+    let (a, b) = (job(10, 1, "a", "fn"), job(10, 1, "b", "fn"));
+    let mut stats = LiveStats::default();
+    assert_eq!(stats.average_lag(now), None);
+
+    stats.observe(&job_event(&a, now - STATS_WINDOW_SECS - 10, JobEventKind::Dispatched { lag_secs: 100 }));
+    stats.observe(&job_event(&a, now - 5, JobEventKind::Dispatched { lag_secs: 1 }));
+    stats.observe(&job_event(&b, now - 5, JobEventKind::Dispatched { lag_secs: 3 }));
+    assert_eq!(stats.average_lag(now), Some(2.0), "dispatches older than the window are left out");
+
+    stats.observe(&job_event(&a, now, JobEventKind::Started { attempt: 1 }));
+    stats.observe(&job_event(&b, now, JobEventKind::Started { attempt: 1 }));
+    assert_eq!(stats.running(), 2);
+    let failed = JobEventKind::Failed { attempt: 1, duration_ms: 5, error: "boom".to_string(), will_retry: true, output: None };
+    stats.observe(&job_event(&a, now, failed));
+    stats.observe(&job_event(&b, now, JobEventKind::Succeeded { attempt: 1, duration_ms: 5, output: None }));
+    assert_eq!(stats.running(), 0);
+}
+
+fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn keys_parse_and_display() {
+    let key: Key = "ctrl+a".parse().unwrap();
+    assert_eq!(key, Key::new(KeyCode::Char('a'), KeyModifiers::CONTROL));
+    assert_eq!(key.to_string(), "Ctrl+A");
+    assert_eq!("shift+g".parse::<Key>().unwrap(), "G".parse().unwrap());
+    assert_eq!("G".parse::<Key>().unwrap().to_string(), "Shift+G");
+    assert_eq!("PgDn".parse::<Key>().unwrap().code, KeyCode::PageDown);
+    assert_eq!("f5".parse::<Key>().unwrap().code, KeyCode::F(5));
+    assert_eq!("+".parse::<Key>().unwrap().code, KeyCode::Char('+'));
+    assert_eq!("ctrl++".parse::<Key>().unwrap(), Key::new(KeyCode::Char('+'), KeyModifiers::CONTROL));
+    assert!("hyper+x".parse::<Key>().unwrap_err().contains("unknown modifier"));
+    assert!("enterr".parse::<Key>().unwrap_err().contains("unknown key"));
+
+    // Terminals report Shift along with the capital letter
+    let key: Key = "G".parse().unwrap();
+    assert!(key.matches(&press(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+    assert!(!key.matches(&press(KeyCode::Char('g'), KeyModifiers::NONE)));
+}
+
+#[test]
+fn presets_map_keys_to_actions_per_context() {
+    let default = Keymap::default();
+    let j = press(KeyCode::Char('j'), KeyModifiers::NONE);
+    assert_eq!(default.action(KeyContext::List, &j), Some(Action::LogJob));
+    assert_eq!(default.action(KeyContext::List, &press(KeyCode::Esc, KeyModifiers::NONE)), Some(Action::Back));
+    assert_eq!(default.action(KeyContext::Detail, &press(KeyCode::Esc, KeyModifiers::NONE)), Some(Action::Close));
+    assert_eq!(default.action(KeyContext::Timeline, &press(KeyCode::Home, KeyModifiers::NONE)), Some(Action::Now));
+    assert_eq!(default.action(KeyContext::Timeline, &press(KeyCode::Char('r'), KeyModifiers::NONE)), None);
+
+    let vim = Keymap::preset(Preset::Vim);
+    assert_eq!(vim.action(KeyContext::List, &j), Some(Action::Down));
+    assert_eq!(vim.action(KeyContext::List, &press(KeyCode::Char('J'), KeyModifiers::SHIFT)), Some(Action::LogJob));
+    assert_eq!(vim.action(KeyContext::List, &press(KeyCode::Char('d'), KeyModifiers::CONTROL)), Some(Action::LogPageDown));
+    assert_eq!(vim.action(KeyContext::Timeline, &press(KeyCode::Char('h'), KeyModifiers::NONE)), Some(Action::Earlier));
+    assert!(vim.validate().is_ok());
+
+    assert_eq!(default.describe(Action::Edit), "E, Enter");
+    assert!(default.help(KeyContext::List).contains(&("Ctrl+A".to_string(), Action::AddJob)));
+    assert_eq!(default.hints(&[Action::Help, Action::Quit]), " ?: Help \u{2502} Q: Quit ");
+}
+
+#[test]
+fn overrides_rebind_and_report_conflicts() {
+    let mut keymap = Keymap::default();
+    let keys = HashMap::from([("add_job".to_string(), vec!["n".to_string()]), ("copy_id".to_string(), vec![])]);
+    keymap.apply(&keys).unwrap();
+    assert_eq!(keymap.action(KeyContext::List, &press(KeyCode::Char('n'), KeyModifiers::NONE)), Some(Action::AddJob));
+    assert_eq!(keymap.action(KeyContext::List, &press(KeyCode::Char('y'), KeyModifiers::NONE)), None);
+    assert_eq!(keymap.hints(&[Action::CopyId]), "  ");
+    assert!(keymap.help(KeyContext::Detail).contains(&("-".to_string(), Action::CopyId)));
+
+    let mut keymap = Keymap::default();
+    let errors = keymap.apply(&HashMap::from([("delete".to_string(), vec!["e".to_string()])])).unwrap_err();
+    assert_eq!(errors, ["tui.keys: E is bound to both edit and delete (job list)"]);
+
+    // The same key may do different things in different contexts
+    let mut keymap = Keymap::default();
+    keymap.apply(&HashMap::from([("zoom_in".to_string(), vec!["e".to_string()])])).unwrap();
+
+    let errors = Keymap::default()
+        .apply(&HashMap::from([("fly".to_string(), vec!["f".to_string()]), ("sort".to_string(), vec!["ctrl+".to_string()])]))
+        .unwrap_err();
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors[0].contains("unknown action 'fly'"), "{:?}", errors);
+    assert!(errors[1].starts_with("tui.keys.sort:"), "{:?}", errors);
+}
+
+#[test]
+fn themes_parse_and_monochrome_has_no_colors() {
+    for name in ThemeName::ALL {
+        assert_eq!(name.to_string().parse::<ThemeName>(), Ok(name));
+    }
+    assert_eq!("High_Contrast".parse::<ThemeName>(), Ok(ThemeName::HighContrast));
+    assert!("solarized".parse::<ThemeName>().unwrap_err().contains("expected dark, light"));
+
+    assert_eq!(Theme::default(), Theme::new(ThemeName::Dark));
+    assert!(!Theme::new(ThemeName::Monochrome).uses_color());
+    for name in [ThemeName::Dark, ThemeName::Light, ThemeName::HighContrast] {
+        assert!(Theme::new(name).uses_color(), "{}", name);
+    }
+    // Success and failure stay apart without color
+    let mono = Theme::new(ThemeName::Monochrome);
+    assert_ne!(mono.success, mono.failure);
+}
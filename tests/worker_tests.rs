@@ -1,9 +1,11 @@
 use scheduler::{
     job::{Job, Status},
-    worker::Worker,
+    tasks::{TaskContext, TaskResult},
+    queue::QueueManager,
+    worker::{RETRY_BACKOFF_SECS, Worker},
 };
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc;
+use std::sync::{Arc, Mutex, mpsc};
 use std::thread;
 use std::time::Duration;
 
@@ -14,12 +16,14 @@ mod tests {
     static WAS_CALLED_REGISTRY: AtomicBool = AtomicBool::new(false);
     static WAS_CALLED_CHANNEL: AtomicBool = AtomicBool::new(false);
 
-    fn test_task_registry(_log_tx: std::sync::mpsc::Sender<String>) {
+    fn test_task_registry(_ctx: &TaskContext) -> TaskResult {
         WAS_CALLED_REGISTRY.store(true, Ordering::SeqCst);
+        Ok(())
     }
 
-    fn test_task_channel(_log_tx: std::sync::mpsc::Sender<String>) {
+    fn test_task_channel(_ctx: &TaskContext) -> TaskResult {
         WAS_CALLED_CHANNEL.store(true, Ordering::SeqCst);
+        Ok(())
     }
 
     #[test]
@@ -40,12 +44,14 @@ mod tests {
             max_retries: 3,
             retry_count: 0,
             metadata: None,
+            tags: Vec::new(),
+            definition: None,
+            schedule: None,
         };
 
         // 3. Reset the flag and run the job
-        let (log_tx, _log_rx) = mpsc::channel();
         WAS_CALLED_REGISTRY.store(false, Ordering::SeqCst);
-        worker.run_job(&mut job, log_tx);
+        worker.run_job(&mut job);
 
         // 4. Assert the function was triggered
         assert!(
@@ -68,11 +74,13 @@ mod tests {
             max_retries: 3,
             retry_count: 0,
             metadata: None,
+            tags: Vec::new(),
+            definition: None,
+            schedule: None,
         };
 
         // Should not panic, just log an error
-        let (log_tx, _log_rx) = mpsc::channel();
-        worker.run_job(&mut job, log_tx);
+        worker.run_job(&mut job);
     }
 
     #[test]
@@ -83,11 +91,9 @@ mod tests {
         let (tx, rx) = mpsc::channel();
         WAS_CALLED_CHANNEL.store(false, Ordering::SeqCst);
 
-        let (log_tx, _log_rx) = mpsc::channel();
-
         // Start worker in a thread
         thread::spawn(move || {
-            worker.start(rx, log_tx);
+            worker.start(rx);
         });
 
         let job = Job {
@@ -100,6 +106,9 @@ mod tests {
             max_retries: 3,
             retry_count: 0,
             metadata: None,
+            tags: Vec::new(),
+            definition: None,
+            schedule: None,
         };
 
         tx.send(job).unwrap();
@@ -113,4 +122,49 @@ mod tests {
             "The registered function should have been executed via channel"
         );
     }
+
+    #[test]
+    fn test_failed_job_is_requeued_for_retry() {
+        let queue = Arc::new(Mutex::new(QueueManager::new()));
+        let mut worker = Worker::new();
+        worker.register("flaky", |_ctx| Err("boom".to_string()));
+        worker.set_queue(Arc::clone(&queue));
+
+        let mut job = Job::new(Job::now(), 1, "Flaky", "flaky", 1).unwrap();
+        worker.run_job(&mut job);
+
+        let retry = queue.lock().unwrap().get(job.id).cloned().expect("job requeued");
+        assert_eq!(retry.retry_count, 1);
+        assert_eq!(retry.status, Status::Pending);
+        assert!(retry.execution_time >= Job::now() + RETRY_BACKOFF_SECS - 1);
+
+        // Out of retries: not queued again
+        let mut job = retry;
+        queue.lock().unwrap().remove(job.id);
+        worker.run_job(&mut job);
+        assert_eq!(job.status, Status::Failed);
+        assert!(queue.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_run_now_leaves_queued_job_alone() {
+        let queue = Arc::new(Mutex::new(QueueManager::new()));
+        let mut worker = Worker::new();
+        worker.register("flaky", |_ctx| Err("boom".to_string()));
+        worker.set_queue(Arc::clone(&queue));
+
+        let mut original = Job::new(Job::now() + 3600, 1, "Nightly", "flaky", 2).unwrap();
+        original.schedule = Some("every 1d".to_string());
+        queue.lock().unwrap().push(original.clone());
+
+        let mut copy = original.run_now_copy();
+        worker.run_job(&mut copy);
+
+        let q = queue.lock().unwrap();
+        assert_eq!(q.len(), 2);
+        assert_eq!(q.get(original.id), Some(&original));
+        let retry = q.get(copy.id).expect("copy requeued under its own id");
+        assert_eq!(retry.retry_count, 1);
+        assert_eq!(retry.schedule, None);
+    }
 }